@@ -0,0 +1,4 @@
+-- migrations/2026-10-17-080000_create_api_tokens/down.sql
+DROP POLICY IF EXISTS "Users can manage their own api_tokens" ON api_tokens;
+DROP TRIGGER IF EXISTS set_api_tokens_timestamp ON api_tokens;
+DROP TABLE api_tokens;
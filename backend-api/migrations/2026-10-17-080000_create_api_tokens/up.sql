@@ -0,0 +1,31 @@
+-- migrations/2026-10-17-080000_create_api_tokens/up.sql
+
+-- Tokens personnels longue durée pour les scripts et intégrations.
+-- Seul le hash SHA-256 du token est stocké, le token en clair n'est affiché qu'une fois à la création.
+CREATE TABLE api_tokens (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    token_prefix TEXT NOT NULL, -- Début du token en clair, pour l'identifier dans la liste
+    token_hash TEXT NOT NULL UNIQUE,
+    scopes TEXT[] NOT NULL DEFAULT '{}', -- ex: 'tasks:read', 'time:write', 'analytics:read'
+    last_used_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_api_tokens_user_id ON api_tokens(user_id);
+
+CREATE TRIGGER set_api_tokens_timestamp
+BEFORE UPDATE ON api_tokens
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+ALTER TABLE api_tokens ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own api_tokens" ON api_tokens
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
@@ -0,0 +1,8 @@
+-- migrations/2026-10-17-090000_enforce_rls_request_identity/down.sql
+
+-- On ne supprime ni le rôle `authenticated` ni `auth.uid()` : sur Supabase ils
+-- préexistent à cette migration et d'autres objets en dépendent.
+ALTER DEFAULT PRIVILEGES IN SCHEMA public
+    REVOKE USAGE, SELECT ON SEQUENCES FROM authenticated;
+ALTER DEFAULT PRIVILEGES IN SCHEMA public
+    REVOKE SELECT, INSERT, UPDATE, DELETE ON TABLES FROM authenticated;
@@ -0,0 +1,57 @@
+-- migrations/2026-10-17-090000_enforce_rls_request_identity/up.sql
+
+-- Le backend exécute chaque requête dans une transaction qui fait
+-- `SET LOCAL ROLE authenticated` et positionne `request.jwt.claims`
+-- (voir db::with_user_rls). Sur Supabase, le rôle `authenticated` et
+-- `auth.uid()` existent déjà ; on les crée ici seulement s'ils manquent
+-- (Postgres local, CI) pour que les politiques RLS existantes fonctionnent.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'authenticated') THEN
+        CREATE ROLE authenticated NOLOGIN;
+    END IF;
+END
+$$;
+
+-- Le rôle de connexion du backend doit pouvoir endosser `authenticated`
+DO $$
+BEGIN
+    IF NOT pg_has_role(current_user, 'authenticated', 'MEMBER') THEN
+        EXECUTE format('GRANT authenticated TO %I', current_user);
+    END IF;
+END
+$$;
+
+CREATE SCHEMA IF NOT EXISTS auth;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE n.nspname = 'auth' AND p.proname = 'uid'
+    ) THEN
+        -- Même sémantique que la fonction Supabase : lit le `sub` des claims de la requête
+        CREATE FUNCTION auth.uid() RETURNS UUID
+        LANGUAGE sql STABLE
+        AS $fn$
+            SELECT COALESCE(
+                NULLIF(current_setting('request.jwt.claim.sub', true), ''),
+                (NULLIF(current_setting('request.jwt.claims', true), '')::jsonb ->> 'sub')
+            )::uuid
+        $fn$;
+    END IF;
+END
+$$;
+
+GRANT USAGE ON SCHEMA auth TO authenticated;
+GRANT EXECUTE ON FUNCTION auth.uid() TO authenticated;
+
+GRANT USAGE ON SCHEMA public TO authenticated;
+GRANT SELECT, INSERT, UPDATE, DELETE ON ALL TABLES IN SCHEMA public TO authenticated;
+GRANT USAGE, SELECT ON ALL SEQUENCES IN SCHEMA public TO authenticated;
+ALTER DEFAULT PRIVILEGES IN SCHEMA public
+    GRANT SELECT, INSERT, UPDATE, DELETE ON TABLES TO authenticated;
+ALTER DEFAULT PRIVILEGES IN SCHEMA public
+    GRANT USAGE, SELECT ON SEQUENCES TO authenticated;
@@ -0,0 +1,7 @@
+-- migrations/2026-10-17-100000_create_users_and_settings/down.sql
+DROP POLICY IF EXISTS "Users can manage their own settings" ON user_settings;
+DROP POLICY IF EXISTS "Users can manage their own profile" ON users;
+DROP TRIGGER IF EXISTS set_user_settings_timestamp ON user_settings;
+DROP TRIGGER IF EXISTS set_users_timestamp ON users;
+DROP TABLE user_settings;
+DROP TABLE users;
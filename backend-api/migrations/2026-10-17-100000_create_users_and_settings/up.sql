@@ -0,0 +1,53 @@
+-- migrations/2026-10-17-100000_create_users_and_settings/up.sql
+
+-- L'ancienne table `users` (id entier, seed, channel_address, last_message)
+-- venait d'un autre projet et n'était utilisée nulle part.
+DROP TABLE IF EXISTS users;
+
+-- Un utilisateur est identifié par l'UUID fourni par l'authentification (`sub` du JWT)
+CREATE TABLE users (
+    id UUID PRIMARY KEY,
+    display_name TEXT,
+    email TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE user_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    timezone TEXT NOT NULL DEFAULT 'UTC', -- Nom IANA, ex: 'Europe/Paris'
+    week_start_day SMALLINT NOT NULL DEFAULT 1 CHECK (week_start_day BETWEEN 1 AND 7), -- ISO : 1 = lundi, 7 = dimanche
+    pomodoro_work_minutes INTEGER NOT NULL DEFAULT 25 CHECK (pomodoro_work_minutes > 0),
+    pomodoro_short_break_minutes INTEGER NOT NULL DEFAULT 5 CHECK (pomodoro_short_break_minutes > 0),
+    pomodoro_long_break_minutes INTEGER NOT NULL DEFAULT 15 CHECK (pomodoro_long_break_minutes > 0),
+    pomodoro_cycles_before_long_break INTEGER NOT NULL DEFAULT 4 CHECK (pomodoro_cycles_before_long_break > 0),
+    working_hours_start TIME NOT NULL DEFAULT '09:00',
+    working_hours_end TIME NOT NULL DEFAULT '17:00',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT working_hours_order CHECK (working_hours_start < working_hours_end)
+);
+
+CREATE TRIGGER set_users_timestamp
+BEFORE UPDATE ON users
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+CREATE TRIGGER set_user_settings_timestamp
+BEFORE UPDATE ON user_settings
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+ALTER TABLE users ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own profile" ON users
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = id)
+    WITH CHECK (auth.uid() = id);
+
+ALTER TABLE user_settings ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own settings" ON user_settings
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
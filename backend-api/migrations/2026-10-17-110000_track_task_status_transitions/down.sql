@@ -0,0 +1,9 @@
+-- migrations/2026-10-17-110000_track_task_status_transitions/down.sql
+DROP TRIGGER IF EXISTS record_task_status_transition ON tasks;
+DROP TRIGGER IF EXISTS set_task_completed_at ON tasks;
+DROP FUNCTION IF EXISTS trigger_record_task_status_transition();
+DROP FUNCTION IF EXISTS trigger_set_task_completed_at();
+DROP INDEX IF EXISTS idx_tasks_completed_at;
+ALTER TABLE tasks DROP COLUMN IF EXISTS completed_at;
+DROP POLICY IF EXISTS "Users can manage their own task_status_transitions" ON task_status_transitions;
+DROP TABLE task_status_transitions;
@@ -0,0 +1,72 @@
+-- migrations/2026-10-17-110000_track_task_status_transitions/up.sql
+
+-- Historique des changements de statut des tâches, pour les métriques de flux
+-- (lead time, cycle time, throughput). Alimenté par trigger : tous les chemins
+-- d'écriture sur `tasks.status` sont couverts sans passer par les handlers.
+CREATE TABLE task_status_transitions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    from_status TEXT, -- NULL pour le statut initial à la création
+    to_status TEXT NOT NULL,
+    transitioned_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_task_status_transitions_task_id ON task_status_transitions(task_id, transitioned_at);
+CREATE INDEX idx_task_status_transitions_user_id ON task_status_transitions(user_id);
+
+ALTER TABLE task_status_transitions ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own task_status_transitions" ON task_status_transitions
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+
+-- Date de passage à 'done', remise à NULL si la tâche est rouverte
+ALTER TABLE tasks ADD COLUMN completed_at TIMESTAMPTZ;
+CREATE INDEX idx_tasks_completed_at ON tasks(user_id, completed_at);
+
+CREATE OR REPLACE FUNCTION trigger_set_task_completed_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status = 'done' THEN
+        IF TG_OP = 'INSERT' OR OLD.status IS DISTINCT FROM 'done' THEN
+            NEW.completed_at = NOW();
+        END IF;
+    ELSE
+        NEW.completed_at = NULL;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION trigger_record_task_status_transition()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO task_status_transitions (task_id, user_id, from_status, to_status, transitioned_at)
+        VALUES (NEW.id, NEW.user_id, NULL, NEW.status, NEW.created_at);
+    ELSIF NEW.status IS DISTINCT FROM OLD.status THEN
+        INSERT INTO task_status_transitions (task_id, user_id, from_status, to_status)
+        VALUES (NEW.id, NEW.user_id, OLD.status, NEW.status);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_task_completed_at
+BEFORE INSERT OR UPDATE OF status ON tasks
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_task_completed_at();
+
+CREATE TRIGGER record_task_status_transition
+AFTER INSERT OR UPDATE OF status ON tasks
+FOR EACH ROW
+EXECUTE FUNCTION trigger_record_task_status_transition();
+
+-- Reprise de l'existant : on ne connaît que le statut courant. Les tâches déjà
+-- terminées reçoivent leur dernière date de modification comme date de fin.
+UPDATE tasks SET completed_at = updated_at WHERE status = 'done';
+
+INSERT INTO task_status_transitions (task_id, user_id, from_status, to_status, transitioned_at)
+SELECT id, user_id, NULL, status, created_at FROM tasks;
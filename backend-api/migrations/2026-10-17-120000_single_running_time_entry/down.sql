@@ -0,0 +1,2 @@
+-- migrations/2026-10-17-120000_single_running_time_entry/down.sql
+DROP INDEX IF EXISTS idx_time_entries_one_running_per_user;
@@ -0,0 +1,21 @@
+-- migrations/2026-10-17-120000_single_running_time_entry/up.sql
+
+-- Un timer en cours est une entrée sans `end_time`. Au plus un par utilisateur,
+-- garanti par la base pour que deux appareils ne puissent pas démarrer en même temps.
+
+-- Reprise de l'existant : si un utilisateur a plusieurs entrées ouvertes, on ne garde
+-- que la plus récente et on ferme les autres sur leur durée connue (ou à leur début).
+UPDATE time_entries te
+SET end_time = te.start_time + make_interval(secs => COALESCE(te.duration_seconds, 0)),
+    duration_seconds = COALESCE(te.duration_seconds, 0)
+WHERE te.end_time IS NULL
+AND EXISTS (
+    SELECT 1 FROM time_entries newer
+    WHERE newer.user_id = te.user_id
+    AND newer.end_time IS NULL
+    AND (newer.start_time, newer.id) > (te.start_time, te.id)
+);
+
+CREATE UNIQUE INDEX idx_time_entries_one_running_per_user
+    ON time_entries(user_id)
+    WHERE end_time IS NULL;
@@ -0,0 +1,8 @@
+-- migrations/2026-10-17-130000_create_pomodoro_sessions/down.sql
+DROP INDEX IF EXISTS idx_time_entries_pomodoro_session_id;
+ALTER TABLE time_entries DROP COLUMN IF EXISTS pomodoro_session_id;
+DROP POLICY IF EXISTS "Users can manage their own pomodoro_interruptions" ON pomodoro_interruptions;
+DROP TABLE pomodoro_interruptions;
+DROP POLICY IF EXISTS "Users can manage their own pomodoro_sessions" ON pomodoro_sessions;
+DROP TRIGGER IF EXISTS set_pomodoro_sessions_timestamp ON pomodoro_sessions;
+DROP TABLE pomodoro_sessions;
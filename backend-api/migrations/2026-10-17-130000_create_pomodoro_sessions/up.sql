@@ -0,0 +1,69 @@
+-- migrations/2026-10-17-130000_create_pomodoro_sessions/up.sql
+
+-- Session Pomodoro pilotée par le serveur : n'importe quel client peut la reprendre.
+-- Le temps restant de la phase courante se calcule à partir de
+-- phase_duration_seconds - phase_elapsed_seconds - (NOW() - phase_resumed_at) quand
+-- la phase tourne (paused_at NULL).
+CREATE TABLE pomodoro_sessions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'completed')),
+    phase TEXT NOT NULL DEFAULT 'work' CHECK (phase IN ('work', 'short_break', 'long_break')),
+    -- Réglages figés au démarrage (copiés de user_settings ou surchargés)
+    work_minutes INTEGER NOT NULL CHECK (work_minutes > 0),
+    short_break_minutes INTEGER NOT NULL CHECK (short_break_minutes > 0),
+    long_break_minutes INTEGER NOT NULL CHECK (long_break_minutes > 0),
+    cycles_before_long_break INTEGER NOT NULL CHECK (cycles_before_long_break > 0),
+    completed_work_cycles INTEGER NOT NULL DEFAULT 0,
+    interruption_count INTEGER NOT NULL DEFAULT 0,
+    phase_duration_seconds INTEGER NOT NULL,
+    phase_elapsed_seconds INTEGER NOT NULL DEFAULT 0, -- Temps écoulé avant la dernière pause
+    phase_resumed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    paused_at TIMESTAMPTZ,
+    ended_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_pomodoro_sessions_user_id ON pomodoro_sessions(user_id);
+-- Une seule session active par utilisateur
+CREATE UNIQUE INDEX idx_pomodoro_sessions_one_active_per_user
+    ON pomodoro_sessions(user_id)
+    WHERE status = 'active';
+
+CREATE TRIGGER set_pomodoro_sessions_timestamp
+BEFORE UPDATE ON pomodoro_sessions
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+ALTER TABLE pomodoro_sessions ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own pomodoro_sessions" ON pomodoro_sessions
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+
+-- Interruptions (pauses) d'une session, avec la phase concernée et une raison libre
+CREATE TABLE pomodoro_interruptions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    session_id UUID NOT NULL REFERENCES pomodoro_sessions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    phase TEXT NOT NULL,
+    reason TEXT,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_pomodoro_interruptions_session_id ON pomodoro_interruptions(session_id);
+
+ALTER TABLE pomodoro_interruptions ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own pomodoro_interruptions" ON pomodoro_interruptions
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+
+-- Les phases de travail créent des entrées de temps liées à la session
+ALTER TABLE time_entries
+    ADD COLUMN pomodoro_session_id UUID REFERENCES pomodoro_sessions(id) ON DELETE SET NULL;
+CREATE INDEX idx_time_entries_pomodoro_session_id ON time_entries(pomodoro_session_id);
@@ -0,0 +1,5 @@
+-- migrations/2026-10-17-140000_auto_stop_running_entries/down.sql
+DROP INDEX IF EXISTS idx_time_entries_running;
+ALTER TABLE time_entries DROP COLUMN IF EXISTS auto_stopped_at, DROP COLUMN IF EXISTS auto_stop_reason;
+ALTER TABLE users DROP COLUMN IF EXISTS last_active_at;
+ALTER TABLE user_settings DROP COLUMN IF EXISTS idle_policy, DROP COLUMN IF EXISTS max_running_entry_minutes;
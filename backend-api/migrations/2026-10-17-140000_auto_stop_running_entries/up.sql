@@ -0,0 +1,19 @@
+-- migrations/2026-10-17-140000_auto_stop_running_entries/up.sql
+
+-- Politique appliquée aux timers oubliés (voir jobs::sweep_forgotten_timers) :
+-- 'cap'           : fermeture à start_time + max_running_entry_minutes
+-- 'last_activity' : fermeture à la dernière activité connue (à défaut, comme 'cap')
+-- 'flag'          : l'entrée reste ouverte, elle est seulement annotée
+ALTER TABLE user_settings
+    ADD COLUMN max_running_entry_minutes INTEGER NOT NULL DEFAULT 600 CHECK (max_running_entry_minutes > 0),
+    ADD COLUMN idle_policy TEXT NOT NULL DEFAULT 'cap' CHECK (idle_policy IN ('cap', 'last_activity', 'flag'));
+
+-- Dernier signe de vie explicite du client (POST /me/heartbeat)
+ALTER TABLE users ADD COLUMN last_active_at TIMESTAMPTZ;
+
+-- Annotation posée par le sweeper : 'capped_at_max', 'cut_at_last_activity' ou 'flagged_long_running'
+ALTER TABLE time_entries
+    ADD COLUMN auto_stop_reason TEXT,
+    ADD COLUMN auto_stopped_at TIMESTAMPTZ;
+
+CREATE INDEX idx_time_entries_running ON time_entries(start_time) WHERE end_time IS NULL;
@@ -0,0 +1,12 @@
+-- migrations/2026-10-17-150000_create_clients_and_billing/down.sql
+DROP POLICY IF EXISTS "Users can manage their own hourly_rates" ON hourly_rates;
+DROP TRIGGER IF EXISTS set_hourly_rates_timestamp ON hourly_rates;
+DROP TABLE hourly_rates;
+ALTER TABLE time_entries DROP COLUMN IF EXISTS billable;
+ALTER TABLE tasks DROP COLUMN IF EXISTS billable;
+DROP INDEX IF EXISTS idx_projects_client_id;
+ALTER TABLE projects DROP COLUMN IF EXISTS billable;
+ALTER TABLE projects DROP COLUMN IF EXISTS client_id;
+DROP POLICY IF EXISTS "Users can manage their own clients" ON clients;
+DROP TRIGGER IF EXISTS set_clients_timestamp ON clients;
+DROP TABLE clients;
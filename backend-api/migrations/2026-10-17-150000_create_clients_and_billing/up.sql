@@ -0,0 +1,69 @@
+-- migrations/2026-10-17-150000_create_clients_and_billing/up.sql
+
+-- Clients facturés : un client possède des projets
+CREATE TABLE clients (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    email TEXT,
+    address TEXT, -- Adresse libre (plusieurs lignes), reprise sur les factures
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_clients_user_id ON clients(user_id);
+
+CREATE TRIGGER set_clients_timestamp
+BEFORE UPDATE ON clients
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+ALTER TABLE clients ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own clients" ON clients
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+
+ALTER TABLE projects
+    ADD COLUMN client_id UUID REFERENCES clients(id) ON DELETE SET NULL,
+    ADD COLUMN billable BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE INDEX idx_projects_client_id ON projects(client_id);
+
+-- NULL = hérite du niveau supérieur (entrée -> tâche -> projet)
+ALTER TABLE tasks ADD COLUMN billable BOOLEAN;
+ALTER TABLE time_entries ADD COLUMN billable BOOLEAN;
+
+-- Taux horaires avec historique : un taux s'applique à partir de effective_from
+-- jusqu'au taux suivant de la même cible. Chaque taux vise exactement un client,
+-- un projet ou une tâche ; la tâche l'emporte sur le projet, qui l'emporte sur le client.
+CREATE TABLE hourly_rates (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    client_id UUID REFERENCES clients(id) ON DELETE CASCADE,
+    project_id UUID REFERENCES projects(id) ON DELETE CASCADE,
+    task_id UUID REFERENCES tasks(id) ON DELETE CASCADE,
+    amount_cents BIGINT NOT NULL CHECK (amount_cents >= 0), -- Montant par heure, en centimes
+    currency TEXT NOT NULL CHECK (currency ~ '^[A-Z]{3}$'), -- Code ISO 4217
+    effective_from DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT hourly_rates_single_target CHECK (num_nonnulls(client_id, project_id, task_id) = 1)
+);
+
+CREATE INDEX idx_hourly_rates_user_id ON hourly_rates(user_id);
+-- Un seul taux par cible et par date d'effet
+CREATE UNIQUE INDEX idx_hourly_rates_one_per_target_and_date
+    ON hourly_rates(COALESCE(task_id, project_id, client_id), effective_from);
+
+CREATE TRIGGER set_hourly_rates_timestamp
+BEFORE UPDATE ON hourly_rates
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+ALTER TABLE hourly_rates ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own hourly_rates" ON hourly_rates
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
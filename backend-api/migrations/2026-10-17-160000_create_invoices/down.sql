@@ -0,0 +1,10 @@
+-- migrations/2026-10-17-160000_create_invoices/down.sql
+DROP TRIGGER IF EXISTS lock_invoiced_time_entry ON time_entries;
+DROP FUNCTION IF EXISTS trigger_lock_invoiced_time_entry();
+DROP INDEX IF EXISTS idx_time_entries_invoice_id;
+ALTER TABLE time_entries DROP COLUMN IF EXISTS invoice_id;
+DROP POLICY IF EXISTS "Users can manage their own invoice_line_items" ON invoice_line_items;
+DROP TABLE invoice_line_items;
+DROP POLICY IF EXISTS "Users can manage their own invoices" ON invoices;
+DROP TRIGGER IF EXISTS set_invoices_timestamp ON invoices;
+DROP TABLE invoices;
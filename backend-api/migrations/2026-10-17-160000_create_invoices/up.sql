@@ -0,0 +1,100 @@
+-- migrations/2026-10-17-160000_create_invoices/up.sql
+
+-- Factures générées à partir des time entries facturables d'un client.
+-- Les coordonnées du client sont copiées à la création : la facture ne change pas
+-- si le client est modifié ou supprimé ensuite.
+CREATE TABLE invoices (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    client_id UUID REFERENCES clients(id) ON DELETE SET NULL,
+    sequence_number INTEGER NOT NULL, -- Numéro dans l'année d'émission, par utilisateur
+    invoice_number TEXT NOT NULL, -- ex: 'INV-2026-0007'
+    status TEXT NOT NULL DEFAULT 'draft' CHECK (status IN ('draft', 'sent', 'paid')),
+    client_name TEXT NOT NULL,
+    client_email TEXT,
+    client_address TEXT,
+    currency TEXT NOT NULL,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    issue_date DATE NOT NULL,
+    due_date DATE,
+    group_by TEXT NOT NULL CHECK (group_by IN ('project', 'task')),
+    rounding_increment_minutes INTEGER NOT NULL DEFAULT 0 CHECK (rounding_increment_minutes >= 0),
+    rounding_direction TEXT NOT NULL DEFAULT 'up' CHECK (rounding_direction IN ('up', 'down', 'nearest')),
+    total_cents BIGINT NOT NULL,
+    notes TEXT,
+    sent_at TIMESTAMPTZ,
+    paid_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_invoices_user_id ON invoices(user_id);
+CREATE INDEX idx_invoices_client_id ON invoices(client_id);
+CREATE UNIQUE INDEX idx_invoices_user_number ON invoices(user_id, invoice_number);
+
+CREATE TRIGGER set_invoices_timestamp
+BEFORE UPDATE ON invoices
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+ALTER TABLE invoices ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own invoices" ON invoices
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+
+CREATE TABLE invoice_line_items (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    project_id UUID REFERENCES projects(id) ON DELETE SET NULL,
+    task_id UUID REFERENCES tasks(id) ON DELETE SET NULL,
+    description TEXT NOT NULL,
+    quantity_seconds BIGINT NOT NULL, -- Temps facturé, après arrondi
+    hourly_rate_cents BIGINT NOT NULL,
+    amount_cents BIGINT NOT NULL,
+    position INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_invoice_line_items_invoice_id ON invoice_line_items(invoice_id, position);
+
+ALTER TABLE invoice_line_items ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own invoice_line_items" ON invoice_line_items
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+
+-- Une entrée facturée est rattachée à sa facture ; supprimer une facture brouillon la libère
+ALTER TABLE time_entries
+    ADD COLUMN invoice_id UUID REFERENCES invoices(id) ON DELETE SET NULL;
+CREATE INDEX idx_time_entries_invoice_id ON time_entries(invoice_id);
+
+-- Verrou des entrées facturées : ni suppression ni modification de ce qui a été facturé.
+-- Seul le détachement de la facture (invoice_id remis à NULL) reste possible.
+CREATE OR REPLACE FUNCTION trigger_lock_invoiced_time_entry()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.invoice_id IS NULL THEN
+        RETURN COALESCE(NEW, OLD);
+    END IF;
+    IF TG_OP = 'DELETE'
+        OR (NEW.task_id, NEW.start_time, NEW.end_time, NEW.duration_seconds, NEW.billable, NEW.user_id)
+            IS DISTINCT FROM
+           (OLD.task_id, OLD.start_time, OLD.end_time, OLD.duration_seconds, OLD.billable, OLD.user_id)
+        OR (NEW.invoice_id IS NOT NULL AND NEW.invoice_id <> OLD.invoice_id)
+    THEN
+        RAISE EXCEPTION 'time entry % is locked by invoice %', OLD.id, OLD.invoice_id
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'time_entries_invoice_lock';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER lock_invoiced_time_entry
+BEFORE UPDATE OR DELETE ON time_entries
+FOR EACH ROW
+EXECUTE FUNCTION trigger_lock_invoiced_time_entry();
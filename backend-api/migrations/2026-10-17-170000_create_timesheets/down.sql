@@ -0,0 +1,9 @@
+-- migrations/2026-10-17-170000_create_timesheets/down.sql
+DROP POLICY IF EXISTS "Approvers can read submitted timesheet lines" ON timesheet_lines;
+DROP POLICY IF EXISTS "Users can manage their own timesheet lines" ON timesheet_lines;
+DROP TABLE timesheet_lines;
+DROP POLICY IF EXISTS "Approvers can update submitted timesheets" ON timesheets;
+DROP POLICY IF EXISTS "Approvers can review submitted timesheets" ON timesheets;
+DROP POLICY IF EXISTS "Users can manage their own timesheets" ON timesheets;
+DROP TRIGGER IF EXISTS set_timesheets_timestamp ON timesheets;
+DROP TABLE timesheets;
@@ -0,0 +1,72 @@
+-- migrations/2026-10-17-170000_create_timesheets/up.sql
+
+-- Feuille de temps hebdomadaire (semaine ISO, dans le fuseau de l'utilisateur).
+-- Soumise par l'utilisateur à un approbateur (le responsable des projets), qui l'approuve
+-- ou la rejette avec un commentaire. Une semaine approuvée est figée tant qu'elle n'est
+-- pas rouverte.
+CREATE TABLE timesheets (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    approver_id UUID NOT NULL REFERENCES users(id),
+    iso_year INTEGER NOT NULL,
+    iso_week INTEGER NOT NULL CHECK (iso_week BETWEEN 1 AND 53),
+    week_start DATE NOT NULL, -- Lundi
+    week_end DATE NOT NULL, -- Dimanche
+    status TEXT NOT NULL DEFAULT 'submitted' CHECK (status IN ('open', 'submitted', 'approved', 'rejected')),
+    total_seconds BIGINT NOT NULL DEFAULT 0, -- Photo prise à la soumission
+    submit_comment TEXT,
+    review_comment TEXT,
+    submitted_at TIMESTAMPTZ,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT timesheets_user_week_unique UNIQUE (user_id, iso_year, iso_week)
+);
+
+CREATE INDEX idx_timesheets_approver_id ON timesheets(approver_id);
+
+CREATE TRIGGER set_timesheets_timestamp
+BEFORE UPDATE ON timesheets
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+ALTER TABLE timesheets ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own timesheets" ON timesheets
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+-- L'approbateur lit et statue sur les feuilles qui lui sont soumises
+CREATE POLICY "Approvers can review submitted timesheets" ON timesheets
+    FOR SELECT
+    TO authenticated
+    USING (auth.uid() = approver_id);
+CREATE POLICY "Approvers can update submitted timesheets" ON timesheets
+    FOR UPDATE
+    TO authenticated
+    USING (auth.uid() = approver_id)
+    WITH CHECK (auth.uid() = approver_id);
+
+-- Agrégat par projet et par jour, copié à la soumission : l'approbateur n'a pas
+-- accès aux time entries de l'utilisateur.
+CREATE TABLE timesheet_lines (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    timesheet_id UUID NOT NULL REFERENCES timesheets(id) ON DELETE CASCADE,
+    project_id UUID REFERENCES projects(id) ON DELETE SET NULL,
+    project_name TEXT NOT NULL,
+    work_date DATE NOT NULL,
+    seconds BIGINT NOT NULL
+);
+
+CREATE INDEX idx_timesheet_lines_timesheet_id ON timesheet_lines(timesheet_id);
+
+ALTER TABLE timesheet_lines ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own timesheet lines" ON timesheet_lines
+    FOR ALL
+    TO authenticated
+    USING (EXISTS (SELECT 1 FROM timesheets ts WHERE ts.id = timesheet_id AND ts.user_id = auth.uid()))
+    WITH CHECK (EXISTS (SELECT 1 FROM timesheets ts WHERE ts.id = timesheet_id AND ts.user_id = auth.uid()));
+CREATE POLICY "Approvers can read submitted timesheet lines" ON timesheet_lines
+    FOR SELECT
+    TO authenticated
+    USING (EXISTS (SELECT 1 FROM timesheets ts WHERE ts.id = timesheet_id AND ts.approver_id = auth.uid()));
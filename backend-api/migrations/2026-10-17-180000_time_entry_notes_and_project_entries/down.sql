@@ -0,0 +1,30 @@
+-- migrations/2026-10-17-180000_time_entry_notes_and_project_entries/down.sql
+CREATE OR REPLACE FUNCTION trigger_lock_invoiced_time_entry()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.invoice_id IS NULL THEN
+        RETURN COALESCE(NEW, OLD);
+    END IF;
+    IF TG_OP = 'DELETE'
+        OR (NEW.task_id, NEW.start_time, NEW.end_time, NEW.duration_seconds, NEW.billable, NEW.user_id)
+            IS DISTINCT FROM
+           (OLD.task_id, OLD.start_time, OLD.end_time, OLD.duration_seconds, OLD.billable, OLD.user_id)
+        OR (NEW.invoice_id IS NOT NULL AND NEW.invoice_id <> OLD.invoice_id)
+    THEN
+        RAISE EXCEPTION 'time entry % is locked by invoice %', OLD.id, OLD.invoice_id
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'time_entries_invoice_lock';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP INDEX IF EXISTS idx_time_entries_note_trgm;
+-- Les entrées de projet n'ont pas de tâche et ne peuvent pas revenir au schéma précédent
+ALTER TABLE time_entries DISABLE TRIGGER lock_invoiced_time_entry;
+DELETE FROM time_entries WHERE task_id IS NULL;
+ALTER TABLE time_entries ENABLE TRIGGER lock_invoiced_time_entry;
+ALTER TABLE time_entries DROP CONSTRAINT IF EXISTS time_entries_task_or_project;
+ALTER TABLE time_entries ALTER COLUMN task_id SET NOT NULL;
+DROP INDEX IF EXISTS idx_time_entries_project_id;
+ALTER TABLE time_entries DROP COLUMN IF EXISTS project_id;
+ALTER TABLE time_entries DROP COLUMN IF EXISTS note;
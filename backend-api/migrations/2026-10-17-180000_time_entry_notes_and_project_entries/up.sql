@@ -0,0 +1,39 @@
+-- migrations/2026-10-17-180000_time_entry_notes_and_project_entries/up.sql
+
+-- Note libre sur ce qui a été fait pendant l'entrée
+ALTER TABLE time_entries ADD COLUMN note TEXT;
+
+-- Entrées rattachées directement à un projet (travail transverse, hors tâche) :
+-- une entrée vise soit une tâche, soit un projet. Le projet d'une entrée de tâche
+-- reste celui de la tâche.
+ALTER TABLE time_entries
+    ADD COLUMN project_id UUID REFERENCES projects(id) ON DELETE CASCADE,
+    ALTER COLUMN task_id DROP NOT NULL,
+    ADD CONSTRAINT time_entries_task_or_project CHECK (num_nonnulls(task_id, project_id) = 1);
+CREATE INDEX idx_time_entries_project_id ON time_entries(project_id);
+
+-- Recherche dans les notes (ILIKE '%...%')
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE INDEX idx_time_entries_note_trgm ON time_entries USING GIN (note gin_trgm_ops);
+
+-- Le verrou des entrées facturées couvre aussi le projet et la note
+CREATE OR REPLACE FUNCTION trigger_lock_invoiced_time_entry()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.invoice_id IS NULL THEN
+        RETURN COALESCE(NEW, OLD);
+    END IF;
+    IF TG_OP = 'DELETE'
+        OR (NEW.task_id, NEW.project_id, NEW.start_time, NEW.end_time, NEW.duration_seconds,
+            NEW.billable, NEW.note, NEW.user_id)
+            IS DISTINCT FROM
+           (OLD.task_id, OLD.project_id, OLD.start_time, OLD.end_time, OLD.duration_seconds,
+            OLD.billable, OLD.note, OLD.user_id)
+        OR (NEW.invoice_id IS NOT NULL AND NEW.invoice_id <> OLD.invoice_id)
+    THEN
+        RAISE EXCEPTION 'time entry % is locked by invoice %', OLD.id, OLD.invoice_id
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'time_entries_invoice_lock';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
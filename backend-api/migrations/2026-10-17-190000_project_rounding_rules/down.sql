@@ -0,0 +1,6 @@
+-- migrations/2026-10-17-190000_project_rounding_rules/down.sql
+DROP FUNCTION IF EXISTS round_duration_seconds(BIGINT, INTEGER, TEXT);
+ALTER TABLE projects
+    DROP COLUMN IF EXISTS rounding_scope,
+    DROP COLUMN IF EXISTS rounding_direction,
+    DROP COLUMN IF EXISTS rounding_increment_minutes;
@@ -0,0 +1,34 @@
+-- migrations/2026-10-17-190000_project_rounding_rules/up.sql
+
+-- Arrondi du temps facturé, par projet. Le temps suivi n'est jamais modifié :
+-- l'arrondi s'applique dans les rapports et à la facturation, soit à chaque entrée,
+-- soit au total de chaque jour (local) du projet.
+ALTER TABLE projects
+    ADD COLUMN rounding_increment_minutes INTEGER NOT NULL DEFAULT 0
+        CHECK (rounding_increment_minutes BETWEEN 0 AND 480), -- 0 : pas d'arrondi
+    ADD COLUMN rounding_direction TEXT NOT NULL DEFAULT 'up'
+        CHECK (rounding_direction IN ('up', 'down', 'nearest')),
+    ADD COLUMN rounding_scope TEXT NOT NULL DEFAULT 'entry'
+        CHECK (rounding_scope IN ('entry', 'day'));
+
+-- Même règle que rounding::round_seconds côté backend ('nearest' : une demi-période
+-- est arrondie au supérieur)
+CREATE OR REPLACE FUNCTION round_duration_seconds(
+    seconds BIGINT,
+    increment_minutes INTEGER,
+    direction TEXT
+) RETURNS BIGINT
+LANGUAGE sql IMMUTABLE
+AS $$
+    SELECT CASE
+        WHEN increment_minutes IS NULL OR increment_minutes <= 0 OR seconds <= 0
+            THEN GREATEST(seconds, 0)
+        WHEN seconds % (increment_minutes * 60) = 0 THEN seconds
+        WHEN direction = 'down'
+            OR (direction = 'nearest' AND (seconds % (increment_minutes * 60)) * 2 < increment_minutes * 60)
+            THEN seconds - seconds % (increment_minutes * 60)
+        ELSE seconds - seconds % (increment_minutes * 60) + increment_minutes * 60
+    END
+$$;
+
+GRANT EXECUTE ON FUNCTION round_duration_seconds(BIGINT, INTEGER, TEXT) TO authenticated;
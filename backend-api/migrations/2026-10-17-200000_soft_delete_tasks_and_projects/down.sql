@@ -0,0 +1,47 @@
+-- migrations/2026-10-17-200000_soft_delete_tasks_and_projects/down.sql
+CREATE OR REPLACE FUNCTION trigger_lock_invoiced_time_entry()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.invoice_id IS NULL THEN
+        RETURN COALESCE(NEW, OLD);
+    END IF;
+    IF TG_OP = 'DELETE'
+        OR (NEW.task_id, NEW.project_id, NEW.start_time, NEW.end_time, NEW.duration_seconds,
+            NEW.billable, NEW.note, NEW.user_id)
+            IS DISTINCT FROM
+           (OLD.task_id, OLD.project_id, OLD.start_time, OLD.end_time, OLD.duration_seconds,
+            OLD.billable, OLD.note, OLD.user_id)
+        OR (NEW.invoice_id IS NOT NULL AND NEW.invoice_id <> OLD.invoice_id)
+    THEN
+        RAISE EXCEPTION 'time entry % is locked by invoice %', OLD.id, OLD.invoice_id
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'time_entries_invoice_lock';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS keep_time_entries_on_task_delete ON tasks;
+DROP FUNCTION IF EXISTS trigger_keep_task_time_entries();
+
+-- Retour à la suppression en cascade : le contenu de la corbeille et les entrées
+-- sans cible ne peuvent pas revenir au schéma précédent
+ALTER TABLE time_entries DISABLE TRIGGER lock_invoiced_time_entry;
+DELETE FROM time_entries WHERE num_nonnulls(task_id, project_id) = 0;
+ALTER TABLE time_entries
+    DROP CONSTRAINT time_entries_task_id_fkey,
+    ADD CONSTRAINT time_entries_task_id_fkey
+        FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    DROP CONSTRAINT time_entries_project_id_fkey,
+    ADD CONSTRAINT time_entries_project_id_fkey
+        FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE;
+DELETE FROM tasks WHERE deleted_at IS NOT NULL;
+DELETE FROM projects WHERE deleted_at IS NOT NULL;
+ALTER TABLE time_entries ENABLE TRIGGER lock_invoiced_time_entry;
+ALTER TABLE time_entries
+    DROP CONSTRAINT time_entries_task_or_project,
+    ADD CONSTRAINT time_entries_task_or_project CHECK (num_nonnulls(task_id, project_id) = 1);
+
+DROP INDEX IF EXISTS idx_projects_deleted_at;
+DROP INDEX IF EXISTS idx_tasks_deleted_at;
+ALTER TABLE projects DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE tasks DROP COLUMN IF EXISTS deleted_at;
@@ -0,0 +1,69 @@
+-- migrations/2026-10-17-200000_soft_delete_tasks_and_projects/up.sql
+
+-- Corbeille : une tâche ou un projet supprimé reste en base avec deleted_at jusqu'à
+-- sa restauration ou sa purge (manuelle, ou par le job de rétention).
+ALTER TABLE tasks ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE projects ADD COLUMN deleted_at TIMESTAMPTZ;
+CREATE INDEX idx_tasks_deleted_at ON tasks(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_projects_deleted_at ON projects(deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Les time entries survivent à la purge de leur cible : elles restent dans les analytics
+-- historiques. Une entrée dont la tâche est purgée passe sur le projet de la tâche ;
+-- une entrée dont le projet est purgé n'a plus de cible (bucket "No project").
+ALTER TABLE time_entries
+    DROP CONSTRAINT time_entries_task_id_fkey,
+    ADD CONSTRAINT time_entries_task_id_fkey
+        FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL,
+    DROP CONSTRAINT time_entries_project_id_fkey,
+    ADD CONSTRAINT time_entries_project_id_fkey
+        FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE SET NULL,
+    DROP CONSTRAINT time_entries_task_or_project,
+    ADD CONSTRAINT time_entries_task_or_project CHECK (num_nonnulls(task_id, project_id) <= 1);
+
+CREATE OR REPLACE FUNCTION trigger_keep_task_time_entries()
+RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE time_entries
+    SET task_id = NULL, project_id = OLD.project_id
+    WHERE task_id = OLD.id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER keep_time_entries_on_task_delete
+BEFORE DELETE ON tasks
+FOR EACH ROW
+EXECUTE FUNCTION trigger_keep_task_time_entries();
+
+-- Le verrou des entrées facturées laisse passer le détachement de la cible lors d'une
+-- purge (mise à jour faite par un trigger ou une action de clé étrangère) : la facture
+-- garde ses libellés copiés, seul le lien vers la tâche ou le projet disparaît.
+CREATE OR REPLACE FUNCTION trigger_lock_invoiced_time_entry()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.invoice_id IS NULL THEN
+        RETURN COALESCE(NEW, OLD);
+    END IF;
+    IF TG_OP = 'UPDATE' AND pg_trigger_depth() > 1
+        AND (NEW.start_time, NEW.end_time, NEW.duration_seconds, NEW.billable, NEW.note,
+             NEW.user_id, NEW.invoice_id)
+            IS NOT DISTINCT FROM
+            (OLD.start_time, OLD.end_time, OLD.duration_seconds, OLD.billable, OLD.note,
+             OLD.user_id, OLD.invoice_id)
+    THEN
+        RETURN NEW;
+    END IF;
+    IF TG_OP = 'DELETE'
+        OR (NEW.task_id, NEW.project_id, NEW.start_time, NEW.end_time, NEW.duration_seconds,
+            NEW.billable, NEW.note, NEW.user_id)
+            IS DISTINCT FROM
+           (OLD.task_id, OLD.project_id, OLD.start_time, OLD.end_time, OLD.duration_seconds,
+            OLD.billable, OLD.note, OLD.user_id)
+        OR (NEW.invoice_id IS NOT NULL AND NEW.invoice_id <> OLD.invoice_id)
+    THEN
+        RAISE EXCEPTION 'time entry % is locked by invoice %', OLD.id, OLD.invoice_id
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'time_entries_invoice_lock';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,4 @@
+-- migrations/2026-10-17-210000_task_hierarchy/down.sql
+DROP INDEX IF EXISTS idx_tasks_parent_task_id;
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_parent_not_self;
+ALTER TABLE tasks DROP COLUMN IF EXISTS parent_task_id;
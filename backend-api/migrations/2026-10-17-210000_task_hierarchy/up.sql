@@ -0,0 +1,9 @@
+-- migrations/2026-10-17-210000_task_hierarchy/up.sql
+
+-- Sous-tâches : une tâche peut avoir une tâche parente du même projet.
+-- La profondeur maximale et l'absence de cycle sont vérifiées par le backend ;
+-- la purge définitive d'une tâche emporte ses sous-tâches (déjà dans la corbeille avec elle).
+ALTER TABLE tasks
+    ADD COLUMN parent_task_id UUID REFERENCES tasks(id) ON DELETE CASCADE,
+    ADD CONSTRAINT tasks_parent_not_self CHECK (parent_task_id <> id);
+CREATE INDEX idx_tasks_parent_task_id ON tasks(parent_task_id);
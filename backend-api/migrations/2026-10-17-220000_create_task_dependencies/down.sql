@@ -0,0 +1,2 @@
+-- migrations/2026-10-17-220000_create_task_dependencies/down.sql
+DROP TABLE IF EXISTS task_dependencies;
@@ -0,0 +1,22 @@
+-- migrations/2026-10-17-220000_create_task_dependencies/up.sql
+
+-- "task_id est bloquée par blocked_by_task_id". Le graphe reste acyclique :
+-- les cycles sont refusés par le backend à l'insertion.
+CREATE TABLE task_dependencies (
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    blocked_by_task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (task_id, blocked_by_task_id),
+    CONSTRAINT task_dependencies_not_self CHECK (task_id <> blocked_by_task_id)
+);
+
+CREATE INDEX idx_task_dependencies_blocked_by ON task_dependencies(blocked_by_task_id);
+CREATE INDEX idx_task_dependencies_user_id ON task_dependencies(user_id);
+
+ALTER TABLE task_dependencies ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own task_dependencies" ON task_dependencies
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
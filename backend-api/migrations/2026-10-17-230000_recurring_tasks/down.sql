@@ -0,0 +1,6 @@
+-- migrations/2026-10-17-230000_recurring_tasks/down.sql
+DROP INDEX IF EXISTS idx_tasks_recurrence_occurrence;
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_recurrence_index_positive;
+ALTER TABLE tasks DROP COLUMN IF EXISTS recurrence_index;
+ALTER TABLE tasks DROP COLUMN IF EXISTS recurrence_series_id;
+ALTER TABLE tasks DROP COLUMN IF EXISTS recurrence_rule;
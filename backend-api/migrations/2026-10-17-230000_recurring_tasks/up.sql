@@ -0,0 +1,19 @@
+-- migrations/2026-10-17-230000_recurring_tasks/up.sql
+
+-- Tâches récurrentes : une tâche porte une règle RRULE (sous-ensemble de la RFC 5545,
+-- validée et normalisée par le backend). Chaque occurrence est une tâche distincte ;
+-- les occurrences terminées restent dans l'historique de la série.
+-- recurrence_series_id : première occurrence de la série (NULL pour celle-ci), sans clé
+-- étrangère pour que l'historique survive à la purge de la première occurrence.
+-- recurrence_index : rang de l'occurrence (1 pour la première), utilisé pour COUNT.
+ALTER TABLE tasks
+    ADD COLUMN recurrence_rule TEXT,
+    ADD COLUMN recurrence_series_id UUID,
+    ADD COLUMN recurrence_index INT NOT NULL DEFAULT 1,
+    ADD CONSTRAINT tasks_recurrence_index_positive CHECK (recurrence_index >= 1);
+
+-- Une seule occurrence par rang : la génération à la complétion et celle du planificateur
+-- ne peuvent pas créer de doublon
+CREATE UNIQUE INDEX idx_tasks_recurrence_occurrence
+    ON tasks(recurrence_series_id, recurrence_index)
+    WHERE recurrence_series_id IS NOT NULL;
@@ -0,0 +1,40 @@
+-- migrations/2026-10-17-240000_task_status_workflows/down.sql
+-- Les statuts normalisés par la reprise ne retrouvent pas leur ancienne graphie.
+CREATE OR REPLACE FUNCTION trigger_set_task_completed_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status = 'done' THEN
+        IF TG_OP = 'INSERT' OR OLD.status IS DISTINCT FROM 'done' THEN
+            NEW.completed_at = NOW();
+        END IF;
+    ELSE
+        NEW.completed_at = NULL;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION trigger_record_task_status_transition()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO task_status_transitions (task_id, user_id, from_status, to_status, transitioned_at)
+        VALUES (NEW.id, NEW.user_id, NULL, NEW.status, NEW.created_at);
+    ELSIF NEW.status IS DISTINCT FROM OLD.status THEN
+        INSERT INTO task_status_transitions (task_id, user_id, from_status, to_status)
+        VALUES (NEW.id, NEW.user_id, OLD.status, NEW.status);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS set_task_completed_at ON tasks;
+CREATE TRIGGER set_task_completed_at
+BEFORE INSERT OR UPDATE OF status ON tasks
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_task_completed_at();
+
+ALTER TABLE task_status_transitions DROP COLUMN IF EXISTS to_category;
+ALTER TABLE tasks DROP COLUMN IF EXISTS status_category;
+DROP TABLE IF EXISTS workflow_transitions;
+DROP TABLE IF EXISTS workflow_statuses;
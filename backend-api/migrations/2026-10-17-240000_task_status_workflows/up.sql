@@ -0,0 +1,148 @@
+-- migrations/2026-10-17-240000_task_status_workflows/up.sql
+
+-- Workflows de statuts : statuts ordonnés, classés dans une catégorie (todo, in_progress,
+-- done), avec les transitions autorisées. Un projet peut avoir son propre workflow ; sinon
+-- il suit le workflow par défaut de l'utilisateur (project_id NULL), et à défaut le
+-- workflow intégré todo / inprogress / done. Un workflow sans transition permet tous les
+-- passages.
+CREATE TABLE workflow_statuses (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    project_id UUID REFERENCES projects(id) ON DELETE CASCADE, -- NULL : workflow par défaut de l'utilisateur
+    key TEXT NOT NULL CHECK (key ~ '^[a-z0-9_]{1,40}$'), -- Valeur enregistrée dans tasks.status
+    name TEXT NOT NULL,
+    category TEXT NOT NULL CHECK (category IN ('todo', 'in_progress', 'done')),
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_workflow_statuses_project_key ON workflow_statuses(project_id, key)
+    WHERE project_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_workflow_statuses_user_default_key ON workflow_statuses(user_id, key)
+    WHERE project_id IS NULL;
+
+CREATE TRIGGER set_workflow_statuses_timestamp
+BEFORE UPDATE ON workflow_statuses
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
+
+ALTER TABLE workflow_statuses ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own workflow_statuses" ON workflow_statuses
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+
+CREATE TABLE workflow_transitions (
+    from_status_id UUID NOT NULL REFERENCES workflow_statuses(id) ON DELETE CASCADE,
+    to_status_id UUID NOT NULL REFERENCES workflow_statuses(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    PRIMARY KEY (from_status_id, to_status_id),
+    CONSTRAINT workflow_transitions_not_self CHECK (from_status_id <> to_status_id)
+);
+
+CREATE INDEX idx_workflow_transitions_user_id ON workflow_transitions(user_id);
+
+ALTER TABLE workflow_transitions ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Users can manage their own workflow_transitions" ON workflow_transitions
+    FOR ALL
+    TO authenticated
+    USING (auth.uid() = user_id)
+    WITH CHECK (auth.uid() = user_id);
+
+-- Catégorie du statut courant, tenue à jour par le backend : 'done' remplace les
+-- comparaisons à la valeur littérale du statut (completed_at, cumuls, dépendances...)
+ALTER TABLE tasks
+    ADD COLUMN status_category TEXT NOT NULL DEFAULT 'todo'
+    CHECK (status_category IN ('todo', 'in_progress', 'done'));
+ALTER TABLE task_status_transitions ADD COLUMN to_category TEXT;
+
+-- Reprise de l'existant : statuts ramenés à une clé (casse, espaces, alias courants)
+CREATE FUNCTION pg_temp.normalize_status(raw TEXT) RETURNS TEXT AS $$
+    SELECT CASE
+        WHEN squashed IN ('done', 'completed', 'complete', 'closed', 'finished') THEN 'done'
+        WHEN squashed IN ('inprogress', 'doing', 'started', 'wip') THEN 'inprogress'
+        WHEN squashed IN ('todo', 'open', 'new', '') THEN 'todo'
+        ELSE COALESCE(NULLIF(left(slug, 40), ''), 'todo')
+    END
+    FROM (
+        SELECT regexp_replace(lower(btrim(raw)), '[^a-z0-9]+', '', 'g') AS squashed,
+            btrim(regexp_replace(lower(btrim(raw)), '[^a-z0-9]+', '_', 'g'), '_') AS slug
+    ) s;
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE FUNCTION pg_temp.builtin_category(key TEXT) RETURNS TEXT AS $$
+    SELECT CASE key WHEN 'done' THEN 'done' WHEN 'inprogress' THEN 'in_progress' ELSE 'todo' END;
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Correction de données : ni horodatage, ni historique de transition, ni completed_at
+-- recalculé par les triggers
+ALTER TABLE tasks DISABLE TRIGGER USER;
+UPDATE tasks SET status = pg_temp.normalize_status(status)
+    WHERE status IS DISTINCT FROM pg_temp.normalize_status(status);
+UPDATE tasks SET status_category = pg_temp.builtin_category(status);
+UPDATE tasks SET completed_at = updated_at WHERE status = 'done' AND completed_at IS NULL;
+ALTER TABLE tasks ENABLE TRIGGER USER;
+
+-- Les statuts hors workflow intégré deviennent des statuts du workflow par défaut de
+-- leur utilisateur (catégorie 'todo'), à la suite des trois statuts intégrés
+INSERT INTO workflow_statuses (user_id, project_id, key, name, category, position)
+SELECT u.user_id, NULL, b.key, b.name, b.category, b.position
+FROM (
+    SELECT DISTINCT user_id FROM tasks WHERE status NOT IN ('todo', 'inprogress', 'done')
+) u
+CROSS JOIN (VALUES
+    ('todo', 'To do', 'todo', 0),
+    ('inprogress', 'In progress', 'in_progress', 1),
+    ('done', 'Done', 'done', 2)
+) AS b(key, name, category, position);
+
+INSERT INTO workflow_statuses (user_id, project_id, key, name, category, position)
+SELECT user_id, NULL, status, initcap(replace(status, '_', ' ')), 'todo',
+    2 + ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY status)
+FROM (
+    SELECT DISTINCT user_id, status FROM tasks WHERE status NOT IN ('todo', 'inprogress', 'done')
+) extra;
+
+UPDATE task_status_transitions
+SET from_status = pg_temp.normalize_status(from_status)
+WHERE from_status IS NOT NULL;
+UPDATE task_status_transitions
+SET to_status = pg_temp.normalize_status(to_status),
+    to_category = pg_temp.builtin_category(pg_temp.normalize_status(to_status));
+
+-- completed_at et l'historique suivent désormais la catégorie du statut
+CREATE OR REPLACE FUNCTION trigger_set_task_completed_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status_category = 'done' THEN
+        IF TG_OP = 'INSERT' OR OLD.status_category IS DISTINCT FROM 'done' THEN
+            NEW.completed_at = NOW();
+        END IF;
+    ELSE
+        NEW.completed_at = NULL;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION trigger_record_task_status_transition()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO task_status_transitions (task_id, user_id, from_status, to_status, to_category, transitioned_at)
+        VALUES (NEW.id, NEW.user_id, NULL, NEW.status, NEW.status_category, NEW.created_at);
+    ELSIF NEW.status IS DISTINCT FROM OLD.status THEN
+        INSERT INTO task_status_transitions (task_id, user_id, from_status, to_status, to_category)
+        VALUES (NEW.id, NEW.user_id, OLD.status, NEW.status, NEW.status_category);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER set_task_completed_at ON tasks;
+CREATE TRIGGER set_task_completed_at
+BEFORE INSERT OR UPDATE OF status, status_category ON tasks
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_task_completed_at();
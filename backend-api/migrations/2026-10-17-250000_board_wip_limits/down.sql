@@ -0,0 +1,4 @@
+-- migrations/2026-10-17-250000_board_wip_limits/down.sql
+DROP INDEX IF EXISTS idx_tasks_project_status;
+ALTER TABLE workflow_statuses DROP COLUMN IF EXISTS wip_policy;
+ALTER TABLE workflow_statuses DROP COLUMN IF EXISTS wip_limit;
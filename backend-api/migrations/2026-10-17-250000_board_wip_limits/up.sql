@@ -0,0 +1,10 @@
+-- migrations/2026-10-17-250000_board_wip_limits/up.sql
+
+-- Limites WIP des colonnes du tableau Kanban (une colonne par statut du workflow).
+-- La limite compte les tâches hors corbeille du projet dans le statut ; 'warn' laisse
+-- entrer la tâche avec un avertissement, 'reject' refuse le déplacement.
+ALTER TABLE workflow_statuses
+    ADD COLUMN wip_limit INTEGER CHECK (wip_limit > 0), -- NULL : pas de limite
+    ADD COLUMN wip_policy TEXT NOT NULL DEFAULT 'warn' CHECK (wip_policy IN ('warn', 'reject'));
+
+CREATE INDEX idx_tasks_project_status ON tasks(project_id, status) WHERE deleted_at IS NULL;
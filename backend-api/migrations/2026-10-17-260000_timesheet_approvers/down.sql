@@ -0,0 +1,8 @@
+-- migrations/2026-10-17-260000_timesheet_approvers/down.sql
+
+ALTER TABLE timesheets DROP CONSTRAINT IF EXISTS timesheets_approver_not_owner;
+
+DROP INDEX IF EXISTS idx_projects_approver_id;
+ALTER TABLE projects
+    DROP CONSTRAINT IF EXISTS projects_approver_not_owner,
+    DROP COLUMN IF EXISTS approver_id;
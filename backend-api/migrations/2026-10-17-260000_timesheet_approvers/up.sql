@@ -0,0 +1,19 @@
+-- migrations/2026-10-17-260000_timesheet_approvers/up.sql
+
+-- Responsable d'un projet : il approuve les feuilles de temps qui contiennent du temps
+-- sur ce projet. Ce n'est jamais le propriétaire du projet (celui qui y saisit son temps).
+ALTER TABLE projects
+    ADD COLUMN approver_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD CONSTRAINT projects_approver_not_owner CHECK (approver_id <> user_id);
+
+CREATE INDEX idx_projects_approver_id ON projects(approver_id);
+
+-- Personne n'approuve sa propre feuille. Les feuilles déjà soumises ou approuvées par leur
+-- auteur sont rouvertes ; la contrainte (NOT VALID) s'applique à leur prochaine soumission.
+UPDATE timesheets
+SET status = 'open',
+    review_comment = 'Reopened: a timesheet cannot be approved by its own author.'
+WHERE approver_id = user_id AND status IN ('submitted', 'approved');
+
+ALTER TABLE timesheets
+    ADD CONSTRAINT timesheets_approver_not_owner CHECK (approver_id <> user_id) NOT VALID;
@@ -0,0 +1,5 @@
+-- migrations/2026-10-17-270000_lock_approved_week_time_entries/down.sql
+
+DROP TRIGGER IF EXISTS lock_approved_week_time_entry ON time_entries;
+DROP FUNCTION IF EXISTS trigger_lock_approved_week_time_entry();
+DROP FUNCTION IF EXISTS time_entry_in_approved_week(UUID, TIMESTAMPTZ);
@@ -0,0 +1,57 @@
+-- migrations/2026-10-17-270000_lock_approved_week_time_entries/up.sql
+
+-- Verrou des semaines approuvées : aucune entrée ne peut être créée, modifiée ou supprimée
+-- dans une semaine ISO (jour local du début, fuseau des réglages) couverte par une feuille
+-- de temps approuvée. Les handlers vérifient avant d'écrire ; ce trigger couvre tous les
+-- autres chemins.
+CREATE OR REPLACE FUNCTION time_entry_in_approved_week(entry_user UUID, entry_start TIMESTAMPTZ)
+RETURNS UUID AS $$
+    SELECT ts.id
+    FROM timesheets ts
+    WHERE ts.user_id = entry_user
+      AND ts.status = 'approved'
+      AND ts.iso_year = EXTRACT(ISOYEAR FROM entry_start AT TIME ZONE COALESCE(
+            (SELECT us.timezone FROM user_settings us WHERE us.user_id = entry_user), 'UTC'))
+      AND ts.iso_week = EXTRACT(WEEK FROM entry_start AT TIME ZONE COALESCE(
+            (SELECT us.timezone FROM user_settings us WHERE us.user_id = entry_user), 'UTC'))
+    LIMIT 1;
+$$ LANGUAGE sql STABLE;
+
+-- La facturation (rattachement à une facture, ou détachement à sa suppression) ne change
+-- pas le temps approuvé et reste permise. Comme pour le verrou de facturation, le
+-- détachement de la cible lors d'une purge (mise à jour faite par un trigger ou une action
+-- de clé étrangère) l'est aussi.
+CREATE OR REPLACE FUNCTION trigger_lock_approved_week_time_entry()
+RETURNS TRIGGER AS $$
+DECLARE
+    locking_timesheet UUID;
+BEGIN
+    IF TG_OP = 'UPDATE'
+        AND (NEW.start_time, NEW.end_time, NEW.duration_seconds, NEW.billable, NEW.note,
+             NEW.user_id)
+            IS NOT DISTINCT FROM
+            (OLD.start_time, OLD.end_time, OLD.duration_seconds, OLD.billable, OLD.note,
+             OLD.user_id)
+        AND (pg_trigger_depth() > 1
+             OR (NEW.task_id, NEW.project_id) IS NOT DISTINCT FROM (OLD.task_id, OLD.project_id))
+    THEN
+        RETURN NEW;
+    END IF;
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        locking_timesheet := time_entry_in_approved_week(OLD.user_id, OLD.start_time);
+    END IF;
+    IF locking_timesheet IS NULL AND TG_OP IN ('INSERT', 'UPDATE') THEN
+        locking_timesheet := time_entry_in_approved_week(NEW.user_id, NEW.start_time);
+    END IF;
+    IF locking_timesheet IS NOT NULL THEN
+        RAISE EXCEPTION 'time entry is in a week covered by approved timesheet %', locking_timesheet
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'time_entries_timesheet_lock';
+    END IF;
+    RETURN COALESCE(NEW, OLD);
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER lock_approved_week_time_entry
+BEFORE INSERT OR UPDATE OR DELETE ON time_entries
+FOR EACH ROW
+EXECUTE FUNCTION trigger_lock_approved_week_time_entry();
@@ -0,0 +1,4 @@
+-- migrations/2026-10-17-280000_refuse_approval_with_running_entries/down.sql
+
+DROP TRIGGER IF EXISTS refuse_approval_with_running_entries ON timesheets;
+DROP FUNCTION IF EXISTS trigger_refuse_approval_with_running_entries();
@@ -0,0 +1,36 @@
+-- migrations/2026-10-17-280000_refuse_approval_with_running_entries/up.sql
+
+-- Une semaine ne s'approuve pas tant qu'une de ses entrées est en cours : verrouillée,
+-- l'entrée ne pourrait plus être arrêtée. L'approbateur ne voit pas les entrées de
+-- l'auteur (RLS), d'où SECURITY DEFINER.
+CREATE OR REPLACE FUNCTION trigger_refuse_approval_with_running_entries()
+RETURNS TRIGGER
+SECURITY DEFINER
+SET search_path = public
+AS $$
+DECLARE
+    running_entry UUID;
+BEGIN
+    IF NEW.status <> 'approved' OR OLD.status = 'approved' THEN
+        RETURN NEW;
+    END IF;
+    SELECT te.id INTO running_entry
+    FROM time_entries te
+    WHERE te.user_id = NEW.user_id
+      AND te.end_time IS NULL
+      AND (te.start_time AT TIME ZONE COALESCE(
+            (SELECT us.timezone FROM user_settings us WHERE us.user_id = NEW.user_id), 'UTC'))::DATE
+          BETWEEN NEW.week_start AND NEW.week_end
+    LIMIT 1;
+    IF running_entry IS NOT NULL THEN
+        RAISE EXCEPTION 'time entry % of the week is still running', running_entry
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'timesheets_no_running_entry';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER refuse_approval_with_running_entries
+BEFORE UPDATE OF status ON timesheets
+FOR EACH ROW
+EXECUTE FUNCTION trigger_refuse_approval_with_running_entries();
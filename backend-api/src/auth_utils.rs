@@ -1,62 +1,667 @@
 // OptiTask/backend-api/src/auth_utils.rs
-use actix_web::{dev::Payload, Error as ActixWebError, FromRequest, HttpRequest};
-use futures_util::future::{err, ok, Ready};
+use crate::db::DbPool;
+use crate::error_handler::ServiceError;
+use crate::models::ApiToken;
+use crate::schema::api_tokens;
+use actix_web::{dev::Payload, http::Method, web, FromRequest, HttpRequest};
+use chrono::Utc;
+use diesel::prelude::*;
+use futures_util::future::{err, ok, LocalBoxFuture};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::env;
 use uuid::Uuid;
 
+// Préfixe des tokens personnels, permet de les distinguer d'un JWT sans décodage
+pub const API_TOKEN_PREFIX: &str = "opt_";
+
+// Scopes qu'un token personnel peut porter. `:write` n'implique pas `:read`.
+pub const API_TOKEN_SCOPES: &[&str] = &[
+    "projects:read",
+    "projects:write",
+    "tasks:read",
+    "tasks:write",
+    "labels:read",
+    "labels:write",
+    "time:read",
+    "time:write",
+    "analytics:read",
+    "billing:read",
+    "billing:write",
+];
+
 #[derive(Debug, Deserialize)]
 pub struct AuthenticatedUser {
     pub id: Uuid,
 }
 
+// Claims attendus dans le JWT. `exp`, `nbf`, `aud` et `iss` sont vérifiés par
+// jsonwebtoken via `Validation`, on n'a besoin ici que du `sub`.
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,
+}
+
+// Configuration de l'authentification, chargée une seule fois au démarrage
+// et injectée dans l'App via `web::Data<AuthConfig>`.
+pub struct AuthConfig {
+    hs256_key: Option<DecodingKey>,
+    jwks: Option<JwkSet>,
+    audience: Option<String>,
+    issuer: Option<String>,
+    // Mode développement : accepte encore le header X-User-Id (proxy Next.js pendant la migration)
+    pub dev_mode: bool,
+}
+
+impl AuthConfig {
+    pub fn from_env() -> Self {
+        let hs256_key = env::var("JWT_HS256_SECRET")
+            .or_else(|_| env::var("NEXTAUTH_SECRET"))
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| DecodingKey::from_secret(secret.as_bytes()));
+
+        let jwks = env::var("JWT_JWKS_PATH").ok().map(|path| {
+            let raw = std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Failed to read JWKS file {}: {}", path, e));
+            serde_json::from_str::<JwkSet>(&raw)
+                .unwrap_or_else(|e| panic!("Invalid JWKS file {}: {}", path, e))
+        });
+
+        let dev_mode = env::var("AUTH_DEV_MODE")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        if hs256_key.is_none() && jwks.is_none() && !dev_mode {
+            log::warn!(
+                "No JWT_HS256_SECRET/NEXTAUTH_SECRET or JWT_JWKS_PATH configured: every authenticated request will be rejected."
+            );
+        }
+        if dev_mode {
+            log::warn!("AUTH_DEV_MODE is enabled: the X-User-Id header is trusted. Never enable this in production.");
+        }
+
+        AuthConfig {
+            hs256_key,
+            jwks,
+            audience: env::var("JWT_AUDIENCE").ok(),
+            issuer: env::var("JWT_ISSUER").ok(),
+            dev_mode,
+        }
+    }
+
+    // Tests des handlers : seul le header X-User-Id est accepté
+    #[cfg(test)]
+    pub fn dev_mode_only() -> Self {
+        AuthConfig {
+            hs256_key: None,
+            jwks: None,
+            audience: None,
+            issuer: None,
+            dev_mode: true,
+        }
+    }
+
+    fn validation_for(&self, algorithm: Algorithm) -> Validation {
+        let mut validation = Validation::new(algorithm);
+        validation.validate_nbf = true;
+        // Un `aud`/`iss` configuré doit être présent : jsonwebtoken ne vérifie
+        // sinon que les claims présents dans le token.
+        let mut required = vec!["exp", "sub"];
+        match &self.audience {
+            Some(aud) => {
+                validation.set_audience(&[aud]);
+                required.push("aud");
+            }
+            None => validation.validate_aud = false,
+        }
+        if let Some(iss) = &self.issuer {
+            validation.set_issuer(&[iss]);
+            required.push("iss");
+        }
+        validation.set_required_spec_claims(&required);
+        validation
+    }
+
+    // Vérifie la signature et les claims du token, puis retourne l'id utilisateur (`sub`).
+    fn verify_bearer_token(&self, token: &str) -> Result<Uuid, ServiceError> {
+        let header = decode_header(token).map_err(|e| {
+            log::warn!("Invalid JWT header: {}", e);
+            ServiceError::Unauthorized("Invalid bearer token.".to_string())
+        })?;
+
+        let decoding_key = match header.alg {
+            Algorithm::HS256 => self.hs256_key.clone().ok_or_else(|| {
+                ServiceError::Unauthorized("HS256 tokens are not accepted.".to_string())
+            })?,
+            Algorithm::RS256 => {
+                let jwks = self.jwks.as_ref().ok_or_else(|| {
+                    ServiceError::Unauthorized("RS256 tokens are not accepted.".to_string())
+                })?;
+                let jwk = match header.kid.as_deref() {
+                    Some(kid) => jwks.find(kid),
+                    // Sans `kid`, on n'accepte que le cas non ambigu d'une seule clé
+                    None if jwks.keys.len() == 1 => jwks.keys.first(),
+                    None => None,
+                }
+                .ok_or_else(|| {
+                    ServiceError::Unauthorized("Unknown signing key for bearer token.".to_string())
+                })?;
+                DecodingKey::from_jwk(jwk).map_err(|e| {
+                    log::error!("Unusable JWK in JWKS file: {}", e);
+                    ServiceError::Unauthorized("Unknown signing key for bearer token.".to_string())
+                })?
+            }
+            other => {
+                log::warn!("Rejected JWT signed with unsupported algorithm {:?}", other);
+                return Err(ServiceError::Unauthorized(
+                    "Unsupported bearer token algorithm.".to_string(),
+                ));
+            }
+        };
+
+        let token_data = decode::<Claims>(token, &decoding_key, &self.validation_for(header.alg))
+            .map_err(|e| {
+            log::warn!("JWT validation failed: {}", e);
+            ServiceError::Unauthorized("Invalid or expired bearer token.".to_string())
+        })?;
+
+        Uuid::parse_str(&token_data.claims.sub).map_err(|_| {
+            ServiceError::Unauthorized("Bearer token subject is not a valid user id.".to_string())
+        })
+    }
+}
+
+// Génère un nouveau token personnel : (token en clair, hash à stocker)
+pub fn generate_api_token() -> (String, String) {
+    // Deux UUID v4 donnent 244 bits aléatoires issus du générateur de l'OS
+    let token = format!(
+        "{}{}{}",
+        API_TOKEN_PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    );
+    let token_hash = hash_api_token(&token);
+    (token, token_hash)
+}
+
+pub fn hash_api_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+// Scope requis pour une route donnée. `None` signifie que la route
+// n'est pas accessible avec un token personnel (ex: /tokens lui-même).
+fn required_scope_for(method: &Method, path: &str) -> Option<&'static str> {
+    let is_read = method == Method::GET || method == Method::HEAD;
+    let resource = path.trim_start_matches('/').split('/').next().unwrap_or("");
+
+    match (resource, is_read) {
+        ("projects", true) => Some("projects:read"),
+        ("projects", false) => Some("projects:write"),
+        // Workflows de statuts : configuration des projets
+        ("workflows", true) => Some("projects:read"),
+        ("workflows", false) => Some("projects:write"),
+        ("tasks", true) => Some("tasks:read"),
+        ("tasks", false) => Some("tasks:write"),
+        // Corbeille : scope du type d'élément (/trash/projects/...), tâches par défaut
+        ("trash", is_read) if path.trim_start_matches('/').starts_with("trash/projects") => {
+            Some(if is_read {
+                "projects:read"
+            } else {
+                "projects:write"
+            })
+        }
+        ("trash", true) => Some("tasks:read"),
+        ("trash", false) => Some("tasks:write"),
+        ("labels", true) => Some("labels:read"),
+        ("labels", false) => Some("labels:write"),
+        ("time-entries", true) => Some("time:read"),
+        ("time-entries", false) => Some("time:write"),
+        ("pomodoro", true) => Some("time:read"),
+        ("pomodoro", false) => Some("time:write"),
+        ("timesheets", true) => Some("time:read"),
+        ("timesheets", false) => Some("time:write"),
+        // Taux et montants facturés : scope de facturation
+        ("analytics", true)
+            if path
+                .trim_start_matches('/')
+                .starts_with("analytics/billable") =>
+        {
+            Some("billing:read")
+        }
+        ("analytics", true) => Some("analytics:read"),
+        ("clients", true) | ("rates", true) | ("invoices", true) => Some("billing:read"),
+        ("clients", false) | ("rates", false) | ("invoices", false) => Some("billing:write"),
+        _ => None,
+    }
+}
+
+// Résout un token personnel : vérifie hash, révocation, expiration et scope,
+// puis enregistre la date de dernière utilisation.
+fn authenticate_api_token(
+    pool: &DbPool,
+    token: &str,
+    required_scope: &str,
+) -> Result<ApiToken, ServiceError> {
+    let mut conn = pool.get()?;
+    let token_hash_value = hash_api_token(token);
+
+    let api_token = api_tokens::table
+        .filter(api_tokens::token_hash.eq(&token_hash_value))
+        .filter(api_tokens::revoked_at.is_null())
+        .select(ApiToken::as_select())
+        .first::<ApiToken>(&mut conn)
+        .optional()?
+        .ok_or_else(|| ServiceError::Unauthorized("Invalid or revoked API token.".to_string()))?;
+
+    if api_token
+        .expires_at
+        .is_some_and(|expiry| expiry <= Utc::now())
+    {
+        return Err(ServiceError::Unauthorized(
+            "API token has expired.".to_string(),
+        ));
+    }
+
+    if !api_token.scopes.iter().any(|scope| scope == required_scope) {
+        log::warn!(
+            "API token {} lacks scope {} (has {:?})",
+            api_token.id,
+            required_scope,
+            api_token.scopes
+        );
+        return Err(ServiceError::Forbidden(format!(
+            "API token does not grant the '{}' scope required by this route.",
+            required_scope
+        )));
+    }
+
+    diesel::update(api_tokens::table.filter(api_tokens::id.eq(api_token.id)))
+        .set(api_tokens::last_used_at.eq(Some(Utc::now())))
+        .execute(&mut conn)?;
+
+    Ok(api_token)
+}
+
+// Ancien mode d'authentification : confiance dans le header X-User-Id.
+// Uniquement disponible quand AUTH_DEV_MODE est activé.
+fn user_id_from_dev_header(req: &HttpRequest) -> Result<Uuid, ServiceError> {
+    let user_id_header_value = req.headers().get("X-User-Id").ok_or_else(|| {
+        log::warn!("Neither Authorization nor X-User-Id header was found in request headers.");
+        ServiceError::Unauthorized("Missing bearer token. Authentication required.".to_string())
+    })?;
+
+    let user_id_str = user_id_header_value.to_str().map_err(|_| {
+        log::warn!("X-User-Id header is not valid UTF-8.");
+        ServiceError::BadRequest("X-User-Id header contains invalid characters.".to_string())
+    })?;
+
+    if user_id_str.is_empty() {
+        log::warn!("X-User-Id header is present but empty.");
+        return Err(ServiceError::BadRequest(
+            "X-User-Id header cannot be empty.".to_string(),
+        ));
+    }
+
+    Uuid::parse_str(user_id_str).map_err(|parse_err| {
+        log::warn!(
+            "Failed to parse X-User-Id '{}' to UUID: {}",
+            user_id_str,
+            parse_err
+        );
+        ServiceError::BadRequest("Invalid X-User-Id header format (not a valid UUID).".to_string())
+    })
+}
+
 impl FromRequest for AuthenticatedUser {
-    type Error = ActixWebError;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Error = ServiceError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
-        log::debug!(
-            "Headers received by AuthenticatedUser extractor: {:?}",
-            req.headers()
-        ); // Gardez ce log pour le debug
-
-        if let Some(user_id_header_value) = req.headers().get("X-User-Id") {
-            if let Ok(user_id_str) = user_id_header_value.to_str() {
-                if user_id_str.is_empty() {
-                    // Vérifier si le header est présent mais vide
-                    log::warn!("X-User-Id header is present but empty.");
-                    return err(actix_web::error::ErrorBadRequest(
-                        "X-User-Id header cannot be empty.",
-                    ));
+        let auth_config = match req.app_data::<web::Data<AuthConfig>>() {
+            Some(config) => config,
+            None => {
+                log::error!("AuthConfig is not registered in app data.");
+                return Box::pin(err(ServiceError::InternalServerError(
+                    "Authentication is not configured.".to_string(),
+                )));
+            }
+        };
+
+        if let Some(auth_header_value) = req.headers().get("Authorization") {
+            let bearer_token = auth_header_value
+                .to_str()
+                .ok()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(str::trim)
+                .filter(|token| !token.is_empty());
+
+            let token = match bearer_token {
+                Some(token) => token,
+                None => {
+                    return Box::pin(err(ServiceError::Unauthorized(
+                        "Authorization header must use the Bearer scheme.".to_string(),
+                    )))
                 }
-                match Uuid::parse_str(user_id_str) {
-                    Ok(user_id_uuid) => {
-                        log::debug!("Successfully parsed X-User-Id: {}", user_id_uuid);
-                        return ok(AuthenticatedUser { id: user_id_uuid });
+            };
+
+            if token.starts_with(API_TOKEN_PREFIX) {
+                let required_scope = match required_scope_for(req.method(), req.path()) {
+                    Some(scope) => scope,
+                    None => {
+                        return Box::pin(err(ServiceError::Forbidden(
+                            "API tokens cannot be used on this route.".to_string(),
+                        )))
                     }
-                    Err(parse_err) => {
-                        log::warn!(
-                            "Failed to parse X-User-Id '{}' to UUID: {}",
-                            user_id_str,
-                            parse_err
-                        );
-                        // Retourner un 400 Bad Request pour un format invalide
-                        return err(actix_web::error::ErrorBadRequest(
-                            "Invalid X-User-Id header format (not a valid UUID).",
-                        ));
+                };
+                let pool = match req.app_data::<web::Data<DbPool>>() {
+                    Some(pool) => pool.clone(),
+                    None => {
+                        return Box::pin(err(ServiceError::InternalServerError(
+                            "Database pool is not configured.".to_string(),
+                        )))
                     }
-                }
-            } else {
-                log::warn!("X-User-Id header is not valid UTF-8.");
-                return err(actix_web::error::ErrorBadRequest(
-                    "X-User-Id header contains invalid characters.",
-                ));
+                };
+                let token = token.to_string();
+
+                return Box::pin(async move {
+                    let api_token =
+                        web::block(move || authenticate_api_token(&pool, &token, required_scope))
+                            .await
+                            .map_err(|e| {
+                                log::error!(
+                                    "Blocking task error (authenticate_api_token): {:?}",
+                                    e
+                                );
+                                ServiceError::InternalServerError(
+                                    "Error processing authentication".to_string(),
+                                )
+                            })??;
+
+                    log::debug!(
+                        "Authenticated user {} from API token {}",
+                        api_token.user_id,
+                        api_token.id
+                    );
+                    Ok(AuthenticatedUser {
+                        id: api_token.user_id,
+                    })
+                });
             }
-        } else {
-            log::warn!("X-User-Id header was NOT found in request headers.");
-            // Retourner un 401 Unauthorized pour un header manquant
-            return err(actix_web::error::ErrorUnauthorized(
-                "Missing X-User-Id header. Authentication required.",
-            ));
+
+            return Box::pin(match auth_config.verify_bearer_token(token) {
+                Ok(user_id_uuid) => {
+                    log::debug!("Authenticated user {} from bearer token", user_id_uuid);
+                    ok(AuthenticatedUser { id: user_id_uuid })
+                }
+                Err(e) => err(e),
+            });
+        }
+
+        if auth_config.dev_mode {
+            return Box::pin(match user_id_from_dev_header(req) {
+                Ok(user_id_uuid) => {
+                    log::debug!("Successfully parsed X-User-Id (dev mode): {}", user_id_uuid);
+                    ok(AuthenticatedUser { id: user_id_uuid })
+                }
+                Err(e) => err(e),
+            });
+        }
+
+        log::warn!("Authorization header was NOT found in request headers.");
+        Box::pin(err(ServiceError::Unauthorized(
+            "Missing bearer token. Authentication required.".to_string(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::{json, Value};
+
+    // Paire RSA de test : la clé privée signe, le JWKS (kid "test-key") vérifie
+    const RSA_PRIVATE_PEM: &str = include_str!("../tests/fixtures/jwt_test_rsa.pem");
+    const JWKS: &str = include_str!("../tests/fixtures/jwt_test_jwks.json");
+    const HS256_SECRET: &str = "test-hs256-secret";
+    const AUDIENCE: &str = "optitask-api";
+    const ISSUER: &str = "https://auth.optitask.test";
+
+    fn test_config() -> AuthConfig {
+        AuthConfig {
+            hs256_key: Some(DecodingKey::from_secret(HS256_SECRET.as_bytes())),
+            jwks: Some(serde_json::from_str(JWKS).unwrap()),
+            audience: Some(AUDIENCE.to_string()),
+            issuer: Some(ISSUER.to_string()),
+            dev_mode: false,
+        }
+    }
+
+    fn claims(sub: &str) -> Value {
+        let now = Utc::now().timestamp();
+        json!({ "sub": sub, "exp": now + 600, "nbf": now - 10, "aud": AUDIENCE, "iss": ISSUER })
+    }
+
+    fn hs256(claims: &Value) -> String {
+        encode(
+            &Header::new(Algorithm::HS256),
+            claims,
+            &EncodingKey::from_secret(HS256_SECRET.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    fn rs256(claims: &Value, kid: Option<&str>) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = kid.map(str::to_string);
+        encode(
+            &header,
+            claims,
+            &EncodingKey::from_rsa_pem(RSA_PRIVATE_PEM.as_bytes()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn rejected(config: &AuthConfig, token: &str) -> bool {
+        matches!(
+            config.verify_bearer_token(token),
+            Err(ServiceError::Unauthorized(_))
+        )
+    }
+
+    #[test]
+    fn valid_tokens_resolve_to_their_subject() {
+        let user = Uuid::new_v4();
+        let claims = claims(&user.to_string());
+        let config = test_config();
+        assert_eq!(config.verify_bearer_token(&hs256(&claims)).unwrap(), user);
+        assert_eq!(
+            config
+                .verify_bearer_token(&rs256(&claims, Some("test-key")))
+                .unwrap(),
+            user
+        );
+        // Sans `kid`, la clé unique du JWKS
+        assert_eq!(
+            config.verify_bearer_token(&rs256(&claims, None)).unwrap(),
+            user
+        );
+    }
+
+    #[test]
+    fn time_bounds_are_enforced() {
+        let now = Utc::now().timestamp();
+        let mut expired = claims(&Uuid::new_v4().to_string());
+        expired["exp"] = json!(now - 600);
+        let mut not_yet_valid = claims(&Uuid::new_v4().to_string());
+        not_yet_valid["nbf"] = json!(now + 600);
+        let mut without_exp = claims(&Uuid::new_v4().to_string());
+        without_exp.as_object_mut().unwrap().remove("exp");
+
+        let config = test_config();
+        for claims in [expired, not_yet_valid, without_exp] {
+            assert!(rejected(&config, &hs256(&claims)), "{}", claims);
+            assert!(
+                rejected(&config, &rs256(&claims, Some("test-key"))),
+                "{}",
+                claims
+            );
+        }
+    }
+
+    #[test]
+    fn audience_and_issuer_must_match() {
+        let mut wrong_audience = claims(&Uuid::new_v4().to_string());
+        wrong_audience["aud"] = json!("another-api");
+        let mut wrong_issuer = claims(&Uuid::new_v4().to_string());
+        wrong_issuer["iss"] = json!("https://evil.test");
+        let mut without_audience = claims(&Uuid::new_v4().to_string());
+        without_audience.as_object_mut().unwrap().remove("aud");
+        let mut without_issuer = claims(&Uuid::new_v4().to_string());
+        without_issuer.as_object_mut().unwrap().remove("iss");
+
+        let config = test_config();
+        for claims in [
+            wrong_audience,
+            wrong_issuer,
+            without_audience,
+            without_issuer,
+        ] {
+            assert!(rejected(&config, &hs256(&claims)), "{}", claims);
+            assert!(
+                rejected(&config, &rs256(&claims, Some("test-key"))),
+                "{}",
+                claims
+            );
+        }
+    }
+
+    #[test]
+    fn algorithm_confusion_is_rejected() {
+        let claims = claims(&Uuid::new_v4().to_string());
+        let config = test_config();
+
+        // HS256 signé avec la clé publique RSA (publique, donc connue de l'attaquant)
+        let forged = encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(JWKS.as_bytes()),
+        )
+        .unwrap();
+        assert!(rejected(&config, &forged));
+
+        // Chaque algorithme n'est accepté que si sa clé est configurée
+        let rs256_only = AuthConfig {
+            hs256_key: None,
+            ..test_config()
+        };
+        assert!(rejected(&rs256_only, &hs256(&claims)));
+        let hs256_only = AuthConfig {
+            jwks: None,
+            ..test_config()
+        };
+        assert!(rejected(&hs256_only, &rs256(&claims, Some("test-key"))));
+
+        // Algorithmes hors HS256/RS256, et "none"
+        let hs384 = encode(
+            &Header::new(Algorithm::HS384),
+            &claims,
+            &EncodingKey::from_secret(HS256_SECRET.as_bytes()),
+        )
+        .unwrap();
+        assert!(rejected(&config, &hs384));
+        let unsigned = {
+            let valid = hs256(&claims);
+            let payload = valid.split('.').nth(1).unwrap();
+            // {"alg":"none","typ":"JWT"}
+            format!("eyJhbGciOiJub25lIiwidHlwIjoiSldUIn0.{}.", payload)
+        };
+        assert!(rejected(&config, &unsigned));
+    }
+
+    #[test]
+    fn unknown_kid_is_rejected() {
+        let claims = claims(&Uuid::new_v4().to_string());
+        assert!(rejected(
+            &test_config(),
+            &rs256(&claims, Some("rotated-away"))
+        ));
+    }
+
+    #[test]
+    fn subject_must_be_a_user_id() {
+        let config = test_config();
+        for sub in ["admin", "", "12345"] {
+            let claims = claims(sub);
+            assert!(rejected(&config, &hs256(&claims)), "{}", sub);
+            assert!(
+                rejected(&config, &rs256(&claims, Some("test-key"))),
+                "{}",
+                sub
+            );
+        }
+    }
+
+    #[test]
+    fn api_token_scopes_by_route() {
+        let cases: &[(Method, &str, Option<&str>)] = &[
+            (Method::GET, "/projects", Some("projects:read")),
+            (Method::HEAD, "/projects/1", Some("projects:read")),
+            (Method::PATCH, "/projects/1", Some("projects:write")),
+            (Method::GET, "/workflows/default", Some("projects:read")),
+            (Method::PUT, "/workflows/default", Some("projects:write")),
+            (Method::GET, "/tasks", Some("tasks:read")),
+            (Method::POST, "/tasks/1/move", Some("tasks:write")),
+            (Method::GET, "/trash", Some("tasks:read")),
+            (Method::POST, "/trash/tasks/1/restore", Some("tasks:write")),
+            (Method::DELETE, "/trash/tasks/1", Some("tasks:write")),
+            (
+                Method::POST,
+                "/trash/projects/1/restore",
+                Some("projects:write"),
+            ),
+            (Method::DELETE, "/trash/projects/1", Some("projects:write")),
+            (Method::GET, "/labels", Some("labels:read")),
+            (Method::DELETE, "/labels/1", Some("labels:write")),
+            (Method::GET, "/time-entries", Some("time:read")),
+            (Method::POST, "/time-entries/merge", Some("time:write")),
+            (Method::GET, "/pomodoro/current", Some("time:read")),
+            (Method::POST, "/pomodoro/skip", Some("time:write")),
+            (Method::GET, "/timesheets", Some("time:read")),
+            (Method::POST, "/timesheets/1/submit", Some("time:write")),
+            (
+                Method::GET,
+                "/analytics/time-by-project",
+                Some("analytics:read"),
+            ),
+            (Method::GET, "/analytics/flow", Some("analytics:read")),
+            (Method::GET, "/analytics/billable", Some("billing:read")),
+            (Method::GET, "/clients", Some("billing:read")),
+            (Method::POST, "/clients", Some("billing:write")),
+            (Method::GET, "/rates", Some("billing:read")),
+            (Method::PUT, "/rates/1", Some("billing:write")),
+            (Method::GET, "/invoices/1", Some("billing:read")),
+            (Method::POST, "/invoices", Some("billing:write")),
+            // Routes fermées aux tokens personnels
+            (Method::POST, "/analytics/time-by-project", None),
+            (Method::GET, "/tokens", None),
+            (Method::POST, "/tokens", None),
+            (Method::DELETE, "/tokens/1", None),
+            (Method::GET, "/me", None),
+            (Method::PATCH, "/me", None),
+            (Method::GET, "/", None),
+        ];
+        for (method, path, expected) in cases {
+            assert_eq!(
+                required_scope_for(method, path),
+                *expected,
+                "{} {}",
+                method,
+                path
+            );
         }
     }
 }
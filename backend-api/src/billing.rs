@@ -0,0 +1,60 @@
+// OptiTask/backend-api/src/billing.rs
+// Résolution du caractère facturable et du taux horaire de chaque time entry.
+// - Facturable : le premier drapeau renseigné parmi entrée -> tâche -> projet (défaut : non).
+// - Taux : la cible la plus spécifique qui a un taux en vigueur (tâche, puis projet, puis
+//   client du projet) ; pour cette cible, le dernier taux dont la date d'effet est
+//   antérieure ou égale au jour local du début de l'entrée.
+// - Arrondi : règle du projet (incrément, sens, par entrée ou par jour local), appliquée
+//   aux secondes valorisées ; le temps suivi reste le temps brut.
+// Les montants ne sont jamais convertis d'une devise à l'autre.
+
+// CTE `billed_entries` : une ligne par entrée de la période, avec son taux résolu.
+// Paramètres : $1 user_id, $2/$3 bornes UTC [début, fin) sur start_time, $4 fuseau IANA.
+// `hourly_rate_cents` et `currency` sont NULL quand aucun taux ne s'applique.
+pub const BILLED_ENTRIES_CTE: &str = "WITH billed_entries AS ( \
+        SELECT te.id AS entry_id, te.task_id, t.title AS task_title, \
+            te.start_time, te.end_time, \
+            p.id AS project_id, p.name AS project_name, \
+            c.id AS client_id, c.name AS client_name, \
+            COALESCE(te.billable, t.billable, p.billable, FALSE) AS is_billable, \
+            COALESCE(te.duration_seconds, 0)::BIGINT AS seconds, \
+            te.invoice_id, te.note, rate.currency, rate.amount_cents AS hourly_rate_cents, \
+            COALESCE(p.rounding_increment_minutes, 0) AS rounding_increment_minutes, \
+            COALESCE(p.rounding_direction, 'up') AS rounding_direction, \
+            COALESCE(p.rounding_scope, 'entry') AS rounding_scope, \
+            (te.start_time AT TIME ZONE $4)::DATE AS work_date \
+        FROM time_entries te \
+        LEFT JOIN tasks t ON t.id = te.task_id \
+        LEFT JOIN projects p ON p.id = COALESCE(te.project_id, t.project_id) \
+        LEFT JOIN clients c ON c.id = p.client_id \
+        LEFT JOIN LATERAL ( \
+            SELECT r.currency, r.amount_cents FROM hourly_rates r \
+            WHERE r.user_id = te.user_id \
+            AND r.effective_from <= (te.start_time AT TIME ZONE $4)::DATE \
+            AND (r.task_id = te.task_id OR r.project_id = p.id OR r.client_id = c.id) \
+            ORDER BY (r.task_id IS NOT NULL) DESC, (r.project_id IS NOT NULL) DESC, r.effective_from DESC \
+            LIMIT 1 \
+        ) rate ON TRUE \
+        WHERE te.user_id = $1 \
+        AND te.start_time >= $2 AND te.start_time < $3 \
+    ) ";
+
+// CTE `rounded_buckets`, à placer après BILLED_ENTRIES_CTE : les entrées regroupées par
+// unité d'arrondi (l'entrée, ou le jour local pour les projets arrondis par jour, à taux
+// et caractère facturable égaux), avec `seconds` brut et `rounded_seconds` arrondi.
+pub const ROUNDED_BUCKETS_CTE: &str = ", rounded_buckets AS ( \
+        SELECT client_id, client_name, project_id, project_name, is_billable, \
+            currency, hourly_rate_cents, \
+            SUM(seconds)::BIGINT AS seconds, \
+            round_duration_seconds(SUM(seconds)::BIGINT, rounding_increment_minutes, rounding_direction) \
+                AS rounded_seconds \
+        FROM billed_entries \
+        GROUP BY client_id, client_name, project_id, project_name, is_billable, \
+            currency, hourly_rate_cents, rounding_increment_minutes, rounding_direction, work_date, \
+            CASE WHEN rounding_scope = 'day' THEN NULL ELSE entry_id END \
+    ) ";
+
+// Montant en centimes des secondes arrondies au taux horaire, arrondi au centime
+// (à utiliser sur `rounded_buckets`)
+pub const AMOUNT_CENTS_EXPR: &str =
+    "COALESCE(ROUND(SUM(rounded_seconds * hourly_rate_cents) / 3600.0), 0)::BIGINT";
@@ -3,10 +3,16 @@
 // Notre pool est créé dans main.rs et injecté.
 // La gestion d'erreur via `ServiceError` se fera dans les handlers.
 
+use crate::error_handler::ServiceError;
 use diesel::pg::PgConnection;
+use diesel::prelude::*;
 use diesel::r2d2::{self, ConnectionManager}; // Gardez r2d2 ici
+use diesel::sql_query;
+use diesel::sql_types::Text;
 use dotenvy::dotenv;
+use serde_json::json;
 use std::env;
+use uuid::Uuid;
 
 // Type alias pour le pool, comme avant
 pub type DbPool = r2d2::Pool<ConnectionManager<PgConnection>>;
@@ -22,8 +28,148 @@ pub fn establish_connection_pool() -> DbPool {
         .expect("Failed to create database connection pool.")
 }
 
-// Nous n'avons pas besoin d'une fonction `connection()` séparée ici
-// car nous injectons `web::Data<DbPool>` dans les handlers, et
-// les handlers obtiennent une connexion du pool via `pool.get()`.
-// La conversion de `r2d2::Error` en `ServiceError` se fera dans
-// la closure de `web::block` dans chaque handler.
+// Exécute `f` dans une transaction qui endosse l'identité de l'utilisateur :
+// `SET LOCAL ROLE authenticated` et `request.jwt.claims` (lu par `auth.uid()`).
+// Les politiques RLS s'appliquent donc en plus des filtres `user_id` des handlers.
+// Les deux réglages sont locaux à la transaction : la connexion retourne
+// propre dans le pool au commit ou au rollback.
+pub fn with_user_rls<T, F>(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    f: F,
+) -> Result<T, ServiceError>
+where
+    F: FnOnce(&mut PgConnection) -> Result<T, ServiceError>,
+{
+    let claims = json!({
+        "sub": user_uuid.to_string(),
+        "role": "authenticated",
+    })
+    .to_string();
+
+    conn.transaction::<T, ServiceError, _>(|conn| {
+        sql_query("SELECT set_config('request.jwt.claims', $1, true)")
+            .bind::<Text, _>(claims)
+            .execute(conn)?;
+        sql_query("SET LOCAL ROLE authenticated").execute(conn)?;
+        f(conn)
+    })
+}
+
+// Raccourci pour les handlers : récupère une connexion du pool et y exécute `f`
+// sous l'identité de l'utilisateur authentifié (voir `with_user_rls`).
+// À appeler depuis la closure de `web::block`.
+pub fn run_as_user<T, F>(pool: &DbPool, user_uuid: Uuid, f: F) -> Result<T, ServiceError>
+where
+    F: FnOnce(&mut PgConnection) -> Result<T, ServiceError>,
+{
+    let mut conn = pool.get()?;
+    with_user_rls(&mut conn, user_uuid, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{projects, tasks, time_entries};
+    use crate::test_support::{
+        seed_project, seed_task, seed_time_entry, test_connection, test_database_url,
+    };
+
+    // Requêtes volontairement sans filtre `user_id` : seule la RLS sépare les utilisateurs
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn unfiltered_queries_only_see_the_request_user_rows() {
+        let mut conn = test_connection();
+        let (user_a, user_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let project_a = seed_project(&mut conn, user_a);
+        let task_a = seed_task(&mut conn, user_a, Some(project_a));
+        let entry_a = seed_time_entry(&mut conn, user_a, task_a);
+        let project_b = seed_project(&mut conn, user_b);
+        let task_b = seed_task(&mut conn, user_b, Some(project_b));
+        seed_time_entry(&mut conn, user_b, task_b);
+
+        let (visible_projects, visible_tasks, visible_entries) =
+            with_user_rls(&mut conn, user_a, |conn| {
+                Ok((
+                    projects::table.select(projects::id).load::<Uuid>(conn)?,
+                    tasks::table.select(tasks::id).load::<Uuid>(conn)?,
+                    time_entries::table
+                        .select(time_entries::id)
+                        .load::<Uuid>(conn)?,
+                ))
+            })
+            .expect("Unfiltered queries should succeed under RLS");
+
+        assert_eq!(visible_projects, vec![project_a]);
+        assert_eq!(visible_tasks, vec![task_a]);
+        assert_eq!(visible_entries, vec![entry_a]);
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn unfiltered_writes_cannot_touch_other_user_rows() {
+        let mut conn = test_connection();
+        let (user_a, user_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let project_b = seed_project(&mut conn, user_b);
+        let task_b = seed_task(&mut conn, user_b, Some(project_b));
+        let entry_b = seed_time_entry(&mut conn, user_b, task_b);
+
+        let (renamed_projects, deleted_tasks, deleted_entries) =
+            with_user_rls(&mut conn, user_a, |conn| {
+                Ok((
+                    diesel::update(projects::table.filter(projects::id.eq(project_b)))
+                        .set(projects::name.eq("Hijacked"))
+                        .execute(conn)?,
+                    diesel::delete(tasks::table.filter(tasks::id.eq(task_b))).execute(conn)?,
+                    diesel::delete(time_entries::table.filter(time_entries::id.eq(entry_b)))
+                        .execute(conn)?,
+                ))
+            })
+            .expect("Writes on invisible rows should affect nothing");
+        assert_eq!(
+            (renamed_projects, deleted_tasks, deleted_entries),
+            (0, 0, 0)
+        );
+
+        // Insérer une ligne au nom d'un autre utilisateur viole la politique WITH CHECK
+        let foreign_insert = with_user_rls(&mut conn, user_a, |conn| {
+            diesel::insert_into(projects::table)
+                .values((projects::user_id.eq(user_b), projects::name.eq("Forged")))
+                .execute(conn)
+                .map_err(ServiceError::from)
+        });
+        assert!(foreign_insert.is_err());
+    }
+
+    // Les réglages de `with_user_rls` ne survivent pas à sa transaction. Connexion sans
+    // transaction de test (rien n'est écrit) : `with_user_rls` y est la transaction de tête,
+    // comme dans les handlers.
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn identity_does_not_leak_past_the_transaction() {
+        let database_url = test_database_url();
+        let mut conn =
+            PgConnection::establish(&database_url).expect("Failed to connect to TEST_DATABASE_URL");
+        let user_a = Uuid::new_v4();
+        with_user_rls(&mut conn, user_a, |_| Ok(())).expect("Empty transaction should succeed");
+        let _ = with_user_rls(&mut conn, user_a, |_| -> Result<(), ServiceError> {
+            Err(ServiceError::BadRequest("rollback".to_string()))
+        });
+
+        #[derive(QueryableByName)]
+        struct RequestIdentity {
+            #[diesel(sql_type = Text)]
+            role: String,
+            #[diesel(sql_type = Text)]
+            claims: String,
+        }
+        let identity = sql_query(
+            "SELECT current_user::TEXT AS role, \
+             COALESCE(current_setting('request.jwt.claims', true), '') AS claims",
+        )
+        .get_result::<RequestIdentity>(&mut conn)
+        .expect("Failed to read request identity");
+        assert_ne!(identity.role, "authenticated");
+        assert_eq!(identity.claims, "");
+    }
+}
@@ -6,19 +6,53 @@ use r2d2::Error as R2D2Error;
 use serde_json::json;
 use std::fmt;
 
+// Nom de contrainte porté par l'exception du trigger de verrouillage des entrées facturées
+const INVOICE_LOCK_CONSTRAINT: &str = "time_entries_invoice_lock";
+// Idem pour les entrées d'une semaine couverte par une feuille de temps approuvée
+const TIMESHEET_LOCK_CONSTRAINT: &str = "time_entries_timesheet_lock";
+// Levée par le trigger qui refuse l'approbation d'une semaine avec un timer en cours
+const RUNNING_ENTRY_APPROVAL_CONSTRAINT: &str = "timesheets_no_running_entry";
+
 #[derive(Debug)]
 pub enum ServiceError {
     InternalServerError(String),
     BadRequest(String),
     Unauthorized(String),
+    Forbidden(String),
     DatabaseError(String), // Message déjà formaté
     NotFound(String),
-    PoolError(String), // Message déjà formaté
+    Conflict(String),
+    ConflictWithDetails(String, serde_json::Value), // 409 avec un champ "details" (ex: entrées en conflit)
+    PoolError(String),                              // Message déjà formaté
 }
 
 impl ServiceError {
     fn from_diesel_error(error: DieselError) -> ServiceError {
         match error {
+            // Levée par trigger : l'entrée appartient à une facture
+            DieselError::DatabaseError(_, info)
+                if info.constraint_name() == Some(INVOICE_LOCK_CONSTRAINT) =>
+            {
+                ServiceError::Conflict(
+                    "This time entry is locked by an invoice and cannot be changed.".to_string(),
+                )
+            }
+            DieselError::DatabaseError(_, info)
+                if info.constraint_name() == Some(TIMESHEET_LOCK_CONSTRAINT) =>
+            {
+                ServiceError::Conflict(
+                    "This week is covered by an approved timesheet; reopen it before changing its time entries."
+                        .to_string(),
+                )
+            }
+            DieselError::DatabaseError(_, info)
+                if info.constraint_name() == Some(RUNNING_ENTRY_APPROVAL_CONSTRAINT) =>
+            {
+                ServiceError::Conflict(
+                    "A timer of this week is still running; it must be stopped before the week is approved."
+                        .to_string(),
+                )
+            }
             DieselError::DatabaseError(kind, info) => {
                 let detailed_message =
                     format!("Database error: {:?} - Info: {}", kind, info.message());
@@ -59,8 +93,11 @@ impl fmt::Display for ServiceError {
             ServiceError::InternalServerError(msg) => write!(f, "Internal Server Error: {}", msg),
             ServiceError::BadRequest(msg) => write!(f, "Bad Request: {}", msg),
             ServiceError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            ServiceError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             ServiceError::DatabaseError(msg) => write!(f, "Database Error: {}", msg),
             ServiceError::NotFound(msg) => write!(f, "Not Found: {}", msg),
+            ServiceError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            ServiceError::ConflictWithDetails(msg, _) => write!(f, "Conflict: {}", msg),
             ServiceError::PoolError(msg) => write!(f, "Pool Error: {}", msg),
         }
     }
@@ -74,7 +111,10 @@ impl ResponseError for ServiceError {
             ServiceError::PoolError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ServiceError::BadRequest(_) => StatusCode::BAD_REQUEST,
             ServiceError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ServiceError::Forbidden(_) => StatusCode::FORBIDDEN,
             ServiceError::NotFound(_) => StatusCode::NOT_FOUND,
+            ServiceError::Conflict(_) => StatusCode::CONFLICT,
+            ServiceError::ConflictWithDetails(_, _) => StatusCode::CONFLICT,
         }
     }
 
@@ -104,10 +144,15 @@ impl ResponseError for ServiceError {
             );
         }
 
-        HttpResponse::build(status_code).json(json!({
+        let mut body = json!({
             "status": "error",
             "statusCode": status_code.as_u16(),
             "message": user_facing_message
-        }))
+        });
+        if let ServiceError::ConflictWithDetails(_, details) = self {
+            body["details"] = details.clone();
+        }
+
+        HttpResponse::build(status_code).json(body)
     }
 }
@@ -1,21 +1,159 @@
 // OptiTask/backend-api/src/handlers/analytics_handlers.rs
 
 use crate::auth_utils::AuthenticatedUser;
-use crate::db::DbPool;
+use crate::billing::{AMOUNT_CENTS_EXPR, BILLED_ENTRIES_CTE, ROUNDED_BUCKETS_CTE};
+use crate::db::{run_as_user, DbPool};
 use crate::error_handler::ServiceError;
-use crate::models::{AnalyticsQueryPeriod, ProductivityTrendPoint, TimeByProjectStat};
+use crate::handlers::time_entry_handlers::note_search_pattern;
+use crate::handlers::user_handlers::load_user_settings;
+use crate::models::{
+    AnalyticsQueryPeriod, BillableCurrencyTotal, BillableProjectStat, BillableReportResponse,
+    DurationStats, FlowMetricsResponse, ProductivityTrendPoint, ThroughputPoint, TimeByLabelStat,
+    TimeByProjectStat, TimeEntryReportRow, UserSettings,
+};
+use actix_web::http::header;
 use actix_web::{get, web, HttpResponse, Result as ActixResult};
-use chrono::{Datelike, Duration, NaiveDate, TimeZone, Utc, Weekday}; // Pour la gestion des dates
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc, Weekday,
+}; // Pour la gestion des dates
+use chrono_tz::Tz;
+use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use diesel::sql_query; // Pour exécuter des requêtes SQL brutes si nécessaire
-use diesel::sql_types::Uuid as DieselUuid; // Importer les types SQL
+use diesel::sql_types::{BigInt, Bool, Nullable, Text, Timestamptz, Uuid as DieselUuid}; // Importer les types SQL
+use std::collections::HashMap;
 
-// Helper pour déterminer les dates de début et de fin basées sur la période
+// Période d'analyse résolue dans le fuseau de l'utilisateur.
+// `start_utc`/`end_utc` forment un intervalle semi-ouvert [start, end) :
+// de minuit local du premier jour à minuit local du lendemain du dernier jour.
+struct ResolvedPeriod {
+    tz: Tz,
+    week_start: Weekday,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    start_utc: DateTime<Utc>,
+    end_utc: DateTime<Utc>,
+}
+
+// Limites pour éviter des réponses démesurées
+const MAX_ROLLING_DAYS: i64 = 3660;
+const MAX_TREND_BUCKETS: usize = 5000;
+
+// Résolution des points de la tendance de productivité
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TrendGranularity {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl TrendGranularity {
+    fn parse(value: Option<&str>) -> Result<Self, ServiceError> {
+        match value {
+            Some("hour") => Ok(TrendGranularity::Hour),
+            Some("day") | None => Ok(TrendGranularity::Day),
+            Some("week") => Ok(TrendGranularity::Week),
+            Some("month") => Ok(TrendGranularity::Month),
+            Some(other) => Err(ServiceError::BadRequest(format!(
+                "Invalid granularity: {}. Supported: hour, day, week, month.",
+                other
+            ))),
+        }
+    }
+
+    // Instant de début du bucket contenant `instant`. Les buckets sont identifiés par
+    // cet instant UTC et non par l'heure locale : l'heure répétée au passage à l'heure
+    // d'hiver donne deux buckets distincts, l'heure sautée au printemps n'en donne aucun.
+    fn bucket_start(self, instant: DateTime<Utc>, tz: Tz, week_start: Weekday) -> DateTime<Utc> {
+        let local = instant.with_timezone(&tz);
+        let date = local.date_naive();
+        match self {
+            TrendGranularity::Hour => {
+                instant
+                    - Duration::seconds(local.minute() as i64 * 60 + local.second() as i64)
+                    - Duration::nanoseconds(local.nanosecond() as i64)
+            }
+            TrendGranularity::Day => local_midnight_to_utc(date, tz),
+            TrendGranularity::Week => local_midnight_to_utc(date.week(week_start).first_day(), tz),
+            TrendGranularity::Month => {
+                local_midnight_to_utc(first_day_of_month(date.year(), date.month()), tz)
+            }
+        }
+    }
+
+    fn next_bucket(self, bucket: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+        let date = bucket.with_timezone(&tz).date_naive();
+        match self {
+            TrendGranularity::Hour => {
+                self.bucket_start(bucket + Duration::hours(1), tz, Weekday::Mon)
+            }
+            TrendGranularity::Day => local_midnight_to_utc(date + Duration::days(1), tz),
+            TrendGranularity::Week => local_midnight_to_utc(date + Duration::days(7), tz),
+            TrendGranularity::Month => local_midnight_to_utc(add_months(date, 1), tz),
+        }
+    }
+}
+
+// Fuseau des réglages de l'utilisateur
+pub fn settings_timezone(settings: &UserSettings) -> Result<Tz, ServiceError> {
+    settings.timezone.parse::<Tz>().map_err(|_| {
+        log::error!(
+            "Invalid timezone '{}' stored in settings of user {}",
+            settings.timezone,
+            settings.user_id
+        );
+        ServiceError::InternalServerError("Invalid timezone in user settings".to_string())
+    })
+}
+
+// Fuseau à utiliser : paramètre `tz` en priorité, sinon le réglage de l'utilisateur
+fn resolve_timezone(
+    query_params: &AnalyticsQueryPeriod,
+    settings: &UserSettings,
+) -> Result<Tz, ServiceError> {
+    match &query_params.tz {
+        Some(tz_name) => tz_name
+            .parse::<Tz>()
+            .map_err(|_| ServiceError::BadRequest(format!("Unknown IANA timezone: {}", tz_name))),
+        None => settings_timezone(settings),
+    }
+}
+
+// Jour de début de semaine depuis le réglage ISO (1 = lundi, 7 = dimanche)
+fn week_start_weekday(iso_day: i16) -> Weekday {
+    match iso_day {
+        2 => Weekday::Tue,
+        3 => Weekday::Wed,
+        4 => Weekday::Thu,
+        5 => Weekday::Fri,
+        6 => Weekday::Sat,
+        7 => Weekday::Sun,
+        _ => Weekday::Mon,
+    }
+}
+
+// Instant UTC correspondant à minuit local. Si minuit n'existe pas ce jour-là
+// (passage à l'heure d'été à 00:00 dans certains fuseaux), on prend le premier
+// instant local valide de la journée. En cas d'ambiguïté (retour à l'heure
+// d'hiver), on garde la première occurrence.
+pub fn local_midnight_to_utc(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let mut local = date.and_hms_opt(0, 0, 0).unwrap();
+    loop {
+        if let Some(dt) = tz.from_local_datetime(&local).earliest() {
+            return dt.with_timezone(&Utc);
+        }
+        local += Duration::minutes(15);
+    }
+}
+
+// Helper pour déterminer les dates de début et de fin basées sur la période.
+// `today` est la date courante dans le fuseau de l'utilisateur.
 fn calculate_date_range(
     query_params: &AnalyticsQueryPeriod,
+    today: NaiveDate,
+    week_start: Weekday,
 ) -> Result<(NaiveDate, NaiveDate), ServiceError> {
-    let today = Utc::now().date_naive();
-
     if let (Some(start), Some(end)) = (query_params.start_date, query_params.end_date) {
         if start > end {
             return Err(ServiceError::BadRequest(
@@ -25,41 +163,153 @@ fn calculate_date_range(
         return Ok((start, end));
     }
 
+    let start_of_this_week = today.week(week_start).first_day();
+    let start_of_this_month = first_day_of_month(today.year(), today.month());
+
     match query_params.period.as_deref() {
-        Some("this_week") => {
-            // Semaine commence Lundi (iso_week)
-            let start_of_week = today
-                .week(Weekday::Mon)
-                .first_day();
-            let end_of_week = today
-                .week(Weekday::Mon)
-                .last_day();
-            Ok((start_of_week, end_of_week))
+        Some("today") => Ok((today, today)),
+        Some("yesterday") => {
+            let yesterday = today - Duration::days(1);
+            Ok((yesterday, yesterday))
         }
-        Some("last_7_days") => Ok((today - Duration::days(6), today)),
-        Some("this_month") => {
-            let start_of_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap();
-            let end_of_month = NaiveDate::from_ymd_opt(
-                today.year() + if today.month() == 12 { 1 } else { 0 }, // Année suivante si Décembre
-                if today.month() == 12 { 1 } else { today.month() + 1 }, // Mois suivant
-                1,
-            )
-            .unwrap()
-            - Duration::days(1);
-            Ok((start_of_month, end_of_month))
+        // La semaine commence au jour choisi dans les réglages (lundi par défaut)
+        Some("this_week") | None => Ok((start_of_this_week, today.week(week_start).last_day())),
+        Some("last_week") => Ok((
+            start_of_this_week - Duration::days(7),
+            start_of_this_week - Duration::days(1),
+        )),
+        Some("this_month") => Ok((
+            start_of_this_month,
+            add_months(start_of_this_month, 1) - Duration::days(1),
+        )),
+        Some("last_month") => Ok((
+            add_months(start_of_this_month, -1),
+            start_of_this_month - Duration::days(1),
+        )),
+        Some("this_quarter") => {
+            let quarter_first_month = (today.month() - 1) / 3 * 3 + 1;
+            let start_of_quarter = first_day_of_month(today.year(), quarter_first_month);
+            Ok((
+                start_of_quarter,
+                add_months(start_of_quarter, 3) - Duration::days(1),
+            ))
         }
-        Some("last_30_days") => Ok((today - Duration::days(29), today)),
-        None => {
-            // Défaut à "cette semaine" si aucune période n'est fournie
-            let start_of_week = today.week(Weekday::Mon).first_day();
-            let end_of_week = today.week(Weekday::Mon).last_day();
-            Ok((start_of_week, end_of_week))
+        Some("this_year") => Ok((
+            first_day_of_month(today.year(), 1),
+            NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap(),
+        )),
+        // Fenêtres glissantes : last_7_days, last_30_days, ... last_N_days (aujourd'hui inclus)
+        Some(other) => match parse_last_n_days(other) {
+            Some(days) => Ok((today - Duration::days(days - 1), today)),
+            None => Err(ServiceError::BadRequest(format!(
+                "Invalid period specified: {}. Supported: today, yesterday, this_week, last_week, this_month, last_month, this_quarter, this_year, last_N_days or provide start_date & end_date.",
+                other
+            ))),
+        },
+    }
+}
+
+fn first_day_of_month(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap()
+}
+
+// Ajoute (ou retire) des mois à un premier jour de mois
+fn add_months(first_of_month: NaiveDate, months: i32) -> NaiveDate {
+    let total_months = first_of_month.year() * 12 + first_of_month.month0() as i32 + months;
+    first_day_of_month(
+        total_months.div_euclid(12),
+        total_months.rem_euclid(12) as u32 + 1,
+    )
+}
+
+// "last_14_days" -> Some(14)
+fn parse_last_n_days(period: &str) -> Option<i64> {
+    period
+        .strip_prefix("last_")
+        .and_then(|rest| rest.strip_suffix("_days"))
+        .and_then(|n| n.parse::<i64>().ok())
+        .filter(|n| (1..=MAX_ROLLING_DAYS).contains(n))
+}
+
+// Résout la période demandée (dates locales + bornes UTC) à partir des
+// paramètres de requête et des réglages de l'utilisateur.
+fn resolve_period(
+    query_params: &AnalyticsQueryPeriod,
+    settings: &UserSettings,
+) -> Result<ResolvedPeriod, ServiceError> {
+    resolve_period_at(query_params, settings, Utc::now())
+}
+
+// `resolve_period` à l'instant `now`
+fn resolve_period_at(
+    query_params: &AnalyticsQueryPeriod,
+    settings: &UserSettings,
+    now: DateTime<Utc>,
+) -> Result<ResolvedPeriod, ServiceError> {
+    let tz = resolve_timezone(query_params, settings)?;
+    let today = now.with_timezone(&tz).date_naive();
+    let week_start = week_start_weekday(settings.week_start_day);
+    let (start_date, end_date) = calculate_date_range(query_params, today, week_start)?;
+
+    Ok(ResolvedPeriod {
+        tz,
+        week_start,
+        start_date,
+        end_date,
+        start_utc: local_midnight_to_utc(start_date, tz),
+        end_utc: local_midnight_to_utc(end_date + Duration::days(1), tz),
+    })
+}
+
+// Total (durée ou nombre) par heure locale, avant regroupement en buckets.
+// `hour_start` : instant de début de l'heure locale (`date_trunc` avec le fuseau).
+#[derive(QueryableByName, Debug)]
+struct HourlyTotal {
+    #[diesel(sql_type = Timestamptz)]
+    hour_start: DateTime<Utc>,
+    #[diesel(sql_type = BigInt)]
+    total: i64,
+}
+
+// Bucket de tendance : début en heure locale, instant UTC correspondant et total
+#[derive(Debug)]
+struct TrendBucket {
+    local_start: NaiveDateTime,
+    utc_start: DateTime<Utc>,
+    total: i64,
+}
+
+// Regroupe les totaux horaires dans les buckets de la granularité demandée
+// et complète avec des zéros pour que chaque bucket de la période soit présent.
+fn fill_buckets(
+    period: &ResolvedPeriod,
+    granularity: TrendGranularity,
+    hourly: &[HourlyTotal],
+) -> Result<Vec<TrendBucket>, ServiceError> {
+    let mut totals: HashMap<DateTime<Utc>, i64> = HashMap::new();
+    for row in hourly {
+        let bucket = granularity.bucket_start(row.hour_start, period.tz, period.week_start);
+        *totals.entry(bucket).or_insert(0) += row.total;
+    }
+
+    let mut buckets = Vec::new();
+    let mut bucket = granularity.bucket_start(period.start_utc, period.tz, period.week_start);
+    while bucket < period.end_utc {
+        if buckets.len() >= MAX_TREND_BUCKETS {
+            return Err(ServiceError::BadRequest(format!(
+                "Too many data points requested (max {}). Use a shorter period or a coarser granularity.",
+                MAX_TREND_BUCKETS
+            )));
         }
-        Some(other) => Err(ServiceError::BadRequest(format!(
-            "Invalid period specified: {}. Supported: this_week, last_7_days, this_month, last_30_days or provide start_date & end_date.",
-            other
-        ))),
+        buckets.push(TrendBucket {
+            local_start: bucket.with_timezone(&period.tz).naive_local(),
+            utc_start: bucket,
+            total: totals.get(&bucket).copied().unwrap_or(0),
+        });
+        bucket = granularity.next_bucket(bucket, period.tz);
     }
+
+    Ok(buckets)
 }
 
 // === GET /analytics/time-by-project ===
@@ -75,38 +325,58 @@ pub async fn get_time_by_project_handler(
         user_uuid,
         query_params.0 // .0 pour accéder aux données de web::Query
     );
-
-    let (start_date, end_date) = calculate_date_range(&query_params.0)?;
-    // Inclure toute la journée de end_date
-    let start_datetime = Utc.from_utc_datetime(&start_date.and_hms_opt(0, 0, 0).unwrap()); // Convertir en DateTime<Utc> si besoin pour comparer avec TIMESTAMPTZ
-    let end_datetime = Utc.from_utc_datetime(&end_date.and_hms_opt(23, 59, 59).unwrap());
+    let query_options = query_params.into_inner();
 
     let stats = web::block(move || -> Result<Vec<TimeByProjectStat>, ServiceError> {
-        let mut conn = pool.get()?;
+        run_as_user(&pool, user_uuid, |conn| {
+            let settings = load_user_settings(conn, user_uuid)?;
+            let period = resolve_period(&query_options, &settings)?;
 
-        // Utilisation de sql_query pour plus de flexibilité avec JOIN et GROUP BY
-        // Assurez-vous que les noms de colonnes correspondent à votre DB et TimeByProjectStat
-        let query = sql_query(
-            "SELECT p.id as project_id, p.name as project_name, COALESCE(SUM(te.duration_seconds), 0) as total_duration_seconds \
-             FROM time_entries te \
-             JOIN tasks t ON te.task_id = t.id \
-             JOIN projects p ON t.project_id = p.id \
-             WHERE te.user_id = $1 AND t.project_id IS NOT NULL \
-             AND te.start_time >= $2 AND te.start_time <= $3 \
-             GROUP BY p.id, p.name \
-             ORDER BY total_duration_seconds DESC"
-        )
-        .bind::<DieselUuid, _>(user_uuid)
-        .bind::<diesel::sql_types::Timestamptz, _>(start_datetime) // Utiliser Timestamptz si start_time est TIMESTAMPTZ
-        .bind::<diesel::sql_types::Timestamptz, _>(end_datetime);  // Idem
+            // Utilisation de sql_query pour plus de flexibilité avec JOIN et GROUP BY
+            // Assurez-vous que les noms de colonnes correspondent à votre DB et TimeByProjectStat
+            // Les tâches sans projet sont regroupées dans un bucket "No project" (project_id NULL)
+            // Temps arrondi selon la règle de chaque projet : par entrée, ou par jour local
+            let query = sql_query(
+                "WITH project_entries AS ( \
+                    SELECT p.id as project_id, p.name as project_name, te.id as entry_id, \
+                        COALESCE(te.duration_seconds, 0)::BIGINT as seconds, \
+                        COALESCE(p.rounding_increment_minutes, 0) as rounding_increment_minutes, \
+                        COALESCE(p.rounding_direction, 'up') as rounding_direction, \
+                        COALESCE(p.rounding_scope, 'entry') as rounding_scope, \
+                        (te.start_time AT TIME ZONE $4)::DATE as work_date \
+                    FROM time_entries te \
+                    LEFT JOIN tasks t ON te.task_id = t.id \
+                    LEFT JOIN projects p ON COALESCE(te.project_id, t.project_id) = p.id \
+                    WHERE te.user_id = $1 \
+                    AND te.start_time >= $2 AND te.start_time < $3 \
+                 ), buckets AS ( \
+                    SELECT project_id, project_name, SUM(seconds)::BIGINT as seconds, \
+                        round_duration_seconds(SUM(seconds)::BIGINT, rounding_increment_minutes, rounding_direction) as rounded_seconds \
+                    FROM project_entries \
+                    GROUP BY project_id, project_name, rounding_increment_minutes, rounding_direction, work_date, \
+                        CASE WHEN rounding_scope = 'day' THEN NULL ELSE entry_id END \
+                 ) \
+                 SELECT project_id, COALESCE(project_name, 'No project') as project_name, \
+                    COALESCE(SUM(seconds), 0)::BIGINT as total_duration_seconds, \
+                    COALESCE(SUM(seconds), 0)::BIGINT as raw_duration_seconds, \
+                    COALESCE(SUM(rounded_seconds), 0)::BIGINT as rounded_duration_seconds \
+                 FROM buckets \
+                 GROUP BY project_id, project_name \
+                 ORDER BY total_duration_seconds DESC"
+            )
+            .bind::<DieselUuid, _>(user_uuid)
+            .bind::<Timestamptz, _>(period.start_utc)
+            .bind::<Timestamptz, _>(period.end_utc)
+            .bind::<Text, _>(period.tz.name());
 
-        log::debug!("Executing SQL for time_by_project: {:?}", query);
+            log::debug!("Executing SQL for time_by_project: {:?}", query);
 
-        query.load::<TimeByProjectStat>(&mut conn)
-             .map_err(|e| {
-                log::error!("Database error in get_time_by_project_handler: {:?}", e);
-                ServiceError::from(e)
-            })
+            query.load::<TimeByProjectStat>(conn)
+                 .map_err(|e| {
+                    log::error!("Database error in get_time_by_project_handler: {:?}", e);
+                    ServiceError::from(e)
+                })
+        })
     })
     .await
     .map_err(|e| { // BlockingError
@@ -117,6 +387,468 @@ pub async fn get_time_by_project_handler(
     Ok(HttpResponse::Ok().json(stats))
 }
 
+// Répartition du temps des tâches à plusieurs labels
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LabelSplitMode {
+    // La durée d'une entrée est divisée à parts égales entre les labels de la tâche
+    Split,
+    // La durée entière est comptée pour chaque label (le total dépasse alors le temps suivi)
+    DoubleCount,
+}
+
+impl LabelSplitMode {
+    fn parse(value: Option<&str>) -> Result<Self, ServiceError> {
+        match value {
+            Some("split") | None => Ok(LabelSplitMode::Split),
+            Some("double_count") => Ok(LabelSplitMode::DoubleCount),
+            Some(other) => Err(ServiceError::BadRequest(format!(
+                "Invalid mode: {}. Supported: split, double_count.",
+                other
+            ))),
+        }
+    }
+}
+
+// === GET /analytics/time-by-label ===
+#[get("/time-by-label")]
+pub async fn get_time_by_label_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<AnalyticsQueryPeriod>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!(
+        "User {} fetching time_by_label with params: {:?}",
+        user_uuid,
+        query_params.0
+    );
+    let query_options = query_params.into_inner();
+    let split_mode = LabelSplitMode::parse(query_options.mode.as_deref())?;
+
+    let stats = web::block(move || -> Result<Vec<TimeByLabelStat>, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            let settings = load_user_settings(conn, user_uuid)?;
+            let period = resolve_period(&query_options, &settings)?;
+
+            // En mode split, chaque entrée est divisée par le nombre de labels de sa tâche ;
+            // les sommes sont faites en NUMERIC et arrondies à la seconde à la fin.
+            // Les entrées dont la tâche n'a aucun label forment le bucket "Unlabeled".
+            let query_str = "WITH entries AS ( \
+                    SELECT te.task_id, COALESCE(te.duration_seconds, 0)::NUMERIC AS duration \
+                    FROM time_entries te \
+                    WHERE te.user_id = $1 \
+                    AND te.start_time >= $2 AND te.start_time < $3 \
+                ), label_counts AS ( \
+                    SELECT tl.task_id, COUNT(*) AS label_count \
+                    FROM task_labels tl \
+                    JOIN labels l ON tl.label_id = l.id \
+                    WHERE l.user_id = $1 \
+                    GROUP BY tl.task_id \
+                ) \
+                SELECT l.id as label_id, l.name as label_name, l.color as label_color, \
+                    ROUND(SUM(e.duration / CASE WHEN $4 THEN lc.label_count ELSE 1 END))::BIGINT as total_duration_seconds \
+                FROM entries e \
+                JOIN label_counts lc ON lc.task_id = e.task_id \
+                JOIN task_labels tl ON tl.task_id = e.task_id \
+                JOIN labels l ON tl.label_id = l.id AND l.user_id = $1 \
+                GROUP BY l.id, l.name, l.color \
+                UNION ALL \
+                SELECT NULL::UUID as label_id, 'Unlabeled' as label_name, NULL::TEXT as label_color, \
+                    ROUND(SUM(e.duration))::BIGINT as total_duration_seconds \
+                FROM entries e \
+                WHERE NOT EXISTS (SELECT 1 FROM label_counts lc WHERE lc.task_id = e.task_id) \
+                HAVING COUNT(*) > 0 \
+                ORDER BY total_duration_seconds DESC";
+
+            let query = sql_query(query_str)
+                .bind::<DieselUuid, _>(user_uuid)
+                .bind::<Timestamptz, _>(period.start_utc)
+                .bind::<Timestamptz, _>(period.end_utc)
+                .bind::<Bool, _>(split_mode == LabelSplitMode::Split);
+
+            log::debug!("Executing SQL for time_by_label: {:?}", query);
+
+            query.load::<TimeByLabelStat>(conn).map_err(|e| {
+                log::error!("Database error in get_time_by_label_handler: {:?}", e);
+                ServiceError::from(e)
+            })
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_time_by_label): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+// Tâches terminées sur la période (completed_at dans [start, end)), filtrées par
+// projet/label, avec leur premier passage en cours avant la fin.
+// $1 user, $2/$3 bornes UTC, $4 projet optionnel, $5 label optionnel.
+const COMPLETED_TASKS_CTE: &str = "WITH completed AS ( \
+        SELECT t.id, t.created_at, t.completed_at, \
+            (SELECT MIN(tr.transitioned_at) FROM task_status_transitions tr \
+             WHERE tr.task_id = t.id AND tr.to_category = 'in_progress' \
+             AND tr.transitioned_at <= t.completed_at) AS started_at \
+        FROM tasks t \
+        WHERE t.user_id = $1 \
+        AND t.completed_at >= $2 AND t.completed_at < $3 \
+        AND ($4::UUID IS NULL OR t.project_id = $4) \
+        AND ($5::UUID IS NULL OR EXISTS ( \
+            SELECT 1 FROM task_labels tl WHERE tl.task_id = t.id AND tl.label_id = $5)) \
+    ) ";
+
+// Moyenne et percentiles (en secondes) d'une durée calculée sur `completed`
+fn duration_stats_query(duration_expr: &str) -> String {
+    format!(
+        "{cte}SELECT COUNT(d.seconds)::BIGINT as task_count, \
+            AVG(d.seconds) as average_seconds, \
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY d.seconds) as p50_seconds, \
+            percentile_cont(0.85) WITHIN GROUP (ORDER BY d.seconds) as p85_seconds, \
+            percentile_cont(0.95) WITHIN GROUP (ORDER BY d.seconds) as p95_seconds \
+         FROM (SELECT EXTRACT(EPOCH FROM ({expr}))::FLOAT8 AS seconds FROM completed) d",
+        cte = COMPLETED_TASKS_CTE,
+        expr = duration_expr
+    )
+}
+
+// === GET /analytics/flow ===
+// Throughput, lead time (création -> done) et cycle time (premier passage en cours -> done)
+// des tâches terminées sur la période.
+#[get("/flow")]
+pub async fn get_flow_metrics_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<AnalyticsQueryPeriod>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!(
+        "User {} fetching flow metrics with params: {:?}",
+        user_uuid,
+        query_params.0
+    );
+    let query_options = query_params.into_inner();
+    let granularity = TrendGranularity::parse(query_options.granularity.as_deref())?;
+
+    let metrics = web::block(move || -> Result<FlowMetricsResponse, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            let settings = load_user_settings(conn, user_uuid)?;
+            let period = resolve_period(&query_options, &settings)?;
+
+            let load_stats = |conn: &mut PgConnection,
+                              duration_expr: &str|
+             -> Result<DurationStats, ServiceError> {
+                sql_query(duration_stats_query(duration_expr))
+                    .bind::<DieselUuid, _>(user_uuid)
+                    .bind::<Timestamptz, _>(period.start_utc)
+                    .bind::<Timestamptz, _>(period.end_utc)
+                    .bind::<Nullable<DieselUuid>, _>(query_options.project_id)
+                    .bind::<Nullable<DieselUuid>, _>(query_options.label_id)
+                    .get_result::<DurationStats>(conn)
+                    .map_err(|e| {
+                        log::error!("Database error in get_flow_metrics_handler: {:?}", e);
+                        ServiceError::from(e)
+                    })
+            };
+            let lead_time = load_stats(conn, "completed_at - created_at")?;
+            // Les tâches passées directement à 'done' n'ont pas de cycle time (started_at NULL)
+            let cycle_time = load_stats(conn, "completed_at - started_at")?;
+
+            let throughput_query = format!(
+                "{}SELECT date_trunc('hour', completed_at, $6) as hour_start, \
+                    COUNT(*)::BIGINT as total \
+                 FROM completed \
+                 GROUP BY hour_start",
+                COMPLETED_TASKS_CTE
+            );
+            let hourly = sql_query(throughput_query)
+                .bind::<DieselUuid, _>(user_uuid)
+                .bind::<Timestamptz, _>(period.start_utc)
+                .bind::<Timestamptz, _>(period.end_utc)
+                .bind::<Nullable<DieselUuid>, _>(query_options.project_id)
+                .bind::<Nullable<DieselUuid>, _>(query_options.label_id)
+                .bind::<Text, _>(period.tz.name())
+                .load::<HourlyTotal>(conn)
+                .map_err(|e| {
+                    log::error!("Database error in get_flow_metrics_handler: {:?}", e);
+                    ServiceError::from(e)
+                })?;
+
+            let throughput: Vec<ThroughputPoint> = fill_buckets(&period, granularity, &hourly)?
+                .into_iter()
+                .map(|bucket| ThroughputPoint {
+                    date_point: bucket.local_start.date(),
+                    bucket_start: bucket.local_start,
+                    bucket_start_utc: bucket.utc_start,
+                    completed_count: bucket.total,
+                })
+                .collect();
+
+            Ok(FlowMetricsResponse {
+                completed_count: throughput.iter().map(|p| p.completed_count).sum(),
+                throughput,
+                lead_time,
+                cycle_time,
+            })
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_flow_metrics): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(metrics))
+}
+
+// Secondes non facturables de la période (hors rapport par projet)
+#[derive(QueryableByName, Debug)]
+struct NonBillableTotal {
+    #[diesel(sql_type = BigInt)]
+    non_billable_seconds: i64,
+}
+
+// === GET /analytics/billable ===
+// Temps facturable valorisé au taux résolu de chaque entrée, par projet et par devise.
+// Filtres optionnels : project_id, client_id.
+#[get("/billable")]
+pub async fn get_billable_report_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<AnalyticsQueryPeriod>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!(
+        "User {} fetching billable report with params: {:?}",
+        user_uuid,
+        query_params.0
+    );
+    let query_options = query_params.into_inner();
+
+    let report = web::block(move || -> Result<BillableReportResponse, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            let settings = load_user_settings(conn, user_uuid)?;
+            let period = resolve_period(&query_options, &settings)?;
+            let filters = "($5::UUID IS NULL OR project_id = $5) \
+                AND ($6::UUID IS NULL OR client_id = $6)";
+
+            let by_project = sql_query(format!(
+                "{cte}{buckets}SELECT client_id, client_name, project_id, \
+                    COALESCE(project_name, 'No project') as project_name, currency, \
+                    SUM(seconds)::BIGINT as billable_seconds, \
+                    SUM(seconds)::BIGINT as raw_duration_seconds, \
+                    SUM(rounded_seconds)::BIGINT as rounded_duration_seconds, \
+                    {amount} as amount_cents \
+                 FROM rounded_buckets \
+                 WHERE is_billable AND {filters} \
+                 GROUP BY client_id, client_name, project_id, project_name, currency \
+                 ORDER BY client_name NULLS LAST, project_name NULLS LAST, currency NULLS LAST",
+                cte = BILLED_ENTRIES_CTE,
+                buckets = ROUNDED_BUCKETS_CTE,
+                amount = AMOUNT_CENTS_EXPR,
+                filters = filters
+            ))
+            .bind::<DieselUuid, _>(user_uuid)
+            .bind::<Timestamptz, _>(period.start_utc)
+            .bind::<Timestamptz, _>(period.end_utc)
+            .bind::<Text, _>(period.tz.name())
+            .bind::<Nullable<DieselUuid>, _>(query_options.project_id)
+            .bind::<Nullable<DieselUuid>, _>(query_options.client_id)
+            .load::<BillableProjectStat>(conn)
+            .map_err(|e| {
+                log::error!("Database error in get_billable_report_handler: {:?}", e);
+                ServiceError::from(e)
+            })?;
+
+            let non_billable = sql_query(format!(
+                "{cte}SELECT COALESCE(SUM(seconds), 0)::BIGINT as non_billable_seconds \
+                 FROM billed_entries \
+                 WHERE NOT is_billable AND {filters}",
+                cte = BILLED_ENTRIES_CTE,
+                filters = filters
+            ))
+            .bind::<DieselUuid, _>(user_uuid)
+            .bind::<Timestamptz, _>(period.start_utc)
+            .bind::<Timestamptz, _>(period.end_utc)
+            .bind::<Text, _>(period.tz.name())
+            .bind::<Nullable<DieselUuid>, _>(query_options.project_id)
+            .bind::<Nullable<DieselUuid>, _>(query_options.client_id)
+            .get_result::<NonBillableTotal>(conn)
+            .map_err(|e| {
+                log::error!("Database error in get_billable_report_handler: {:?}", e);
+                ServiceError::from(e)
+            })?;
+
+            // Totaux par devise, égaux à la somme des lignes (arrondies) du rapport
+            let mut totals: Vec<BillableCurrencyTotal> = Vec::new();
+            let mut unrated_billable_seconds = 0;
+            for stat in &by_project {
+                let Some(currency) = &stat.currency else {
+                    unrated_billable_seconds += stat.billable_seconds;
+                    continue;
+                };
+                match totals.iter_mut().find(|total| &total.currency == currency) {
+                    Some(total) => {
+                        total.billable_seconds += stat.billable_seconds;
+                        total.rounded_duration_seconds += stat.rounded_duration_seconds;
+                        total.amount_cents += stat.amount_cents;
+                    }
+                    None => totals.push(BillableCurrencyTotal {
+                        currency: currency.clone(),
+                        billable_seconds: stat.billable_seconds,
+                        rounded_duration_seconds: stat.rounded_duration_seconds,
+                        amount_cents: stat.amount_cents,
+                    }),
+                }
+            }
+            totals.sort_by(|a, b| a.currency.cmp(&b.currency));
+
+            Ok(BillableReportResponse {
+                start_date: period.start_date,
+                end_date: period.end_date,
+                billable_seconds: by_project.iter().map(|stat| stat.billable_seconds).sum(),
+                rounded_billable_seconds: by_project
+                    .iter()
+                    .map(|stat| stat.rounded_duration_seconds)
+                    .sum(),
+                non_billable_seconds: non_billable.non_billable_seconds,
+                unrated_billable_seconds,
+                totals,
+                by_project,
+            })
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_billable_report): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+// Échappement d'un champ CSV (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn entries_report_csv(rows: &[TimeEntryReportRow], tz: Tz) -> String {
+    let mut csv = String::from(
+        "date,start,end,duration_seconds,duration_hours,client,project,task,note,billable\r\n",
+    );
+    for row in rows {
+        let local_start = row.start_time.with_timezone(&tz);
+        let fields = [
+            local_start.date_naive().to_string(),
+            local_start.format("%H:%M:%S").to_string(),
+            row.end_time
+                .map(|end| end.with_timezone(&tz).format("%H:%M:%S").to_string())
+                .unwrap_or_default(),
+            row.seconds.to_string(),
+            format!("{:.2}", row.seconds as f64 / 3600.0),
+            row.client_name.clone().unwrap_or_default(),
+            row.project_name.clone().unwrap_or_default(),
+            row.task_title.clone().unwrap_or_default(),
+            row.note.clone().unwrap_or_default(),
+            row.is_billable.to_string(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+// === GET /analytics/entries ===
+// Rapport détaillé : une ligne par entrée avec son projet, sa tâche et sa note.
+// Filtres optionnels : project_id, client_id, q (recherche dans les notes).
+// `format=csv` renvoie le même contenu en export CSV (heures locales).
+#[get("/entries")]
+pub async fn get_entries_report_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<AnalyticsQueryPeriod>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!(
+        "User {} fetching entries report with params: {:?}",
+        user_uuid,
+        query_params.0
+    );
+    let query_options = query_params.into_inner();
+    let as_csv = match query_options.format.as_deref() {
+        Some("json") | None => false,
+        Some("csv") => true,
+        Some(other) => {
+            return Err(ServiceError::BadRequest(format!(
+                "Invalid format: {}. Supported: json, csv.",
+                other
+            )))
+        }
+    };
+    let note_pattern = query_options
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|search| !search.is_empty())
+        .map(note_search_pattern);
+
+    let (rows, period_tz, start_date, end_date) = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let settings = load_user_settings(conn, user_uuid)?;
+            let period = resolve_period(&query_options, &settings)?;
+
+            let rows = sql_query(format!(
+                "{cte}SELECT entry_id, start_time, end_time, seconds, client_id, client_name, \
+                    project_id, project_name, task_id, task_title, note, is_billable \
+                 FROM billed_entries \
+                 WHERE ($5::UUID IS NULL OR project_id = $5) \
+                 AND ($6::UUID IS NULL OR client_id = $6) \
+                 AND ($7::TEXT IS NULL OR note ILIKE $7) \
+                 ORDER BY start_time ASC",
+                cte = BILLED_ENTRIES_CTE
+            ))
+            .bind::<DieselUuid, _>(user_uuid)
+            .bind::<Timestamptz, _>(period.start_utc)
+            .bind::<Timestamptz, _>(period.end_utc)
+            .bind::<Text, _>(period.tz.name())
+            .bind::<Nullable<DieselUuid>, _>(query_options.project_id)
+            .bind::<Nullable<DieselUuid>, _>(query_options.client_id)
+            .bind::<Nullable<Text>, _>(note_pattern)
+            .load::<TimeEntryReportRow>(conn)
+            .map_err(|e| {
+                log::error!("Database error in get_entries_report_handler: {:?}", e);
+                ServiceError::from(e)
+            })?;
+            Ok((rows, period.tz, period.start_date, period.end_date))
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_entries_report): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    if as_csv {
+        return Ok(HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"time-entries-{}-{}.csv\"",
+                    start_date, end_date
+                ),
+            ))
+            .body(entries_report_csv(&rows, period_tz)));
+    }
+    Ok(HttpResponse::Ok().json(rows))
+}
+
 // === GET /analytics/productivity-trend ===
 #[get("/productivity-trend")]
 pub async fn get_productivity_trend_handler(
@@ -130,42 +862,51 @@ pub async fn get_productivity_trend_handler(
         user_uuid,
         query_params.0
     );
+    let query_options = query_params.into_inner();
 
-    let (start_date_range, end_date_range) = calculate_date_range(&query_params.0)?;
-    // Inclure toute la journée de end_date
-    let start_datetime_range =
-        Utc.from_utc_datetime(&start_date_range.and_hms_opt(0, 0, 0).unwrap()); // Convertir en DateTime<Utc> si besoin pour comparer avec TIMESTAMPTZ
-    let end_datetime_range =
-        Utc.from_utc_datetime(&end_date_range.and_hms_opt(23, 59, 59).unwrap());
+    let granularity = TrendGranularity::parse(query_options.granularity.as_deref())?;
 
     let trend_points = web::block(
         move || -> Result<Vec<ProductivityTrendPoint>, ServiceError> {
-            let mut conn = pool.get()?;
+            run_as_user(&pool, user_uuid, |conn| {
+                let settings = load_user_settings(conn, user_uuid)?;
+                let period = resolve_period(&query_options, &settings)?;
 
-            // Grouper par jour. Pour TIMESTAMPTZ, on peut utiliser DATE(start_time AT TIME ZONE 'UTC')
-            // ou une fonction similaire dépendant de votre DB et timezone.
-            // Si start_time est juste TIMESTAMP (sans tz), DATE(start_time) suffit.
-            let query_str = "SELECT DATE(te.start_time AT TIME ZONE 'UTC') as date_point, \
-                    COALESCE(SUM(te.duration_seconds), 0) as total_duration_seconds \
+                // Agrégation par heure locale : `date_trunc` avec le nom IANA gère
+                // les changements d'heure, une entrée à 23h30 locale reste sur son jour.
+                // Les heures sont ensuite regroupées en jours/semaines/mois côté Rust.
+                let query_str = "SELECT date_trunc('hour', te.start_time, $4) as hour_start, \
+                    COALESCE(SUM(te.duration_seconds), 0)::BIGINT as total \
              FROM time_entries te \
              WHERE te.user_id = $1 \
-             AND te.start_time >= $2 AND te.start_time <= $3 \
-             GROUP BY date_point \
-             ORDER BY date_point ASC";
+             AND te.start_time >= $2 AND te.start_time < $3 \
+             GROUP BY hour_start \
+             ORDER BY hour_start ASC";
 
-            let query = sql_query(query_str)
-                .bind::<DieselUuid, _>(user_uuid)
-                .bind::<diesel::sql_types::Timestamptz, _>(start_datetime_range)
-                .bind::<diesel::sql_types::Timestamptz, _>(end_datetime_range);
+                let query = sql_query(query_str)
+                    .bind::<DieselUuid, _>(user_uuid)
+                    .bind::<Timestamptz, _>(period.start_utc)
+                    .bind::<Timestamptz, _>(period.end_utc)
+                    .bind::<Text, _>(period.tz.name());
 
-            log::debug!("Executing SQL for productivity_trend: {:?}", query);
+                log::debug!("Executing SQL for productivity_trend: {:?}", query);
 
-            query
-                .load::<ProductivityTrendPoint>(&mut conn)
-                .map_err(|e| {
+                let hourly = query.load::<HourlyTotal>(conn).map_err(|e| {
                     log::error!("Database error in get_productivity_trend_handler: {:?}", e);
                     ServiceError::from(e)
-                })
+                })?;
+
+                let buckets = fill_buckets(&period, granularity, &hourly)?;
+                Ok(buckets
+                    .into_iter()
+                    .map(|bucket| ProductivityTrendPoint {
+                        date_point: bucket.local_start.date(),
+                        bucket_start: bucket.local_start,
+                        bucket_start_utc: bucket.utc_start,
+                        total_duration_seconds: bucket.total,
+                    })
+                    .collect())
+            })
         },
     )
     .await
@@ -176,3 +917,347 @@ pub async fn get_productivity_trend_handler(
 
     Ok(HttpResponse::Ok().json(trend_points))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse::<DateTime<Utc>>().unwrap()
+    }
+
+    fn local(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn day(value: &str) -> NaiveDate {
+        value.parse::<NaiveDate>().unwrap()
+    }
+
+    // Période [start_date, end_date] à Paris, bornes calculées comme `resolve_period`
+    fn paris_period(start_date: NaiveDate, end_date: NaiveDate) -> ResolvedPeriod {
+        let tz = chrono_tz::Europe::Paris;
+        ResolvedPeriod {
+            tz,
+            week_start: Weekday::Mon,
+            start_date,
+            end_date,
+            start_utc: local_midnight_to_utc(start_date, tz),
+            end_utc: local_midnight_to_utc(end_date + Duration::days(1), tz),
+        }
+    }
+
+    fn hourly(hour_start: &str, total: i64) -> HourlyTotal {
+        HourlyTotal {
+            hour_start: utc(hour_start),
+            total,
+        }
+    }
+
+    fn query(params: Value) -> AnalyticsQueryPeriod {
+        serde_json::from_value(params).unwrap()
+    }
+
+    fn range(period: &str, today: &str, week_start: Weekday) -> (NaiveDate, NaiveDate) {
+        calculate_date_range(&query(json!({ "period": period })), day(today), week_start).unwrap()
+    }
+
+    fn settings(timezone: &str, week_start_day: i16) -> UserSettings {
+        let created = local("2026-01-01 00:00");
+        UserSettings {
+            user_id: Uuid::new_v4(),
+            timezone: timezone.to_string(),
+            week_start_day,
+            pomodoro_work_minutes: 25,
+            pomodoro_short_break_minutes: 5,
+            pomodoro_long_break_minutes: 15,
+            pomodoro_cycles_before_long_break: 4,
+            working_hours_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            working_hours_end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            created_at: created,
+            updated_at: created,
+            max_running_entry_minutes: 600,
+            idle_policy: "cap".to_string(),
+        }
+    }
+
+    #[test]
+    fn quarter_and_year_cover_whole_months() {
+        let cases = [
+            ("2026-01-01", ("2026-01-01", "2026-03-31")),
+            ("2026-03-31", ("2026-01-01", "2026-03-31")),
+            ("2026-05-15", ("2026-04-01", "2026-06-30")),
+            ("2026-08-31", ("2026-07-01", "2026-09-30")),
+            ("2026-12-31", ("2026-10-01", "2026-12-31")),
+        ];
+        for (today, (start, end)) in cases {
+            assert_eq!(
+                range("this_quarter", today, Weekday::Mon),
+                (day(start), day(end)),
+                "{}",
+                today
+            );
+        }
+        assert_eq!(
+            range("this_year", "2028-02-29", Weekday::Mon),
+            (day("2028-01-01"), day("2028-12-31"))
+        );
+        assert_eq!(
+            range("last_month", "2026-01-15", Weekday::Mon),
+            (day("2025-12-01"), day("2025-12-31"))
+        );
+        assert_eq!(
+            range("this_month", "2028-02-10", Weekday::Mon),
+            (day("2028-02-01"), day("2028-02-29"))
+        );
+    }
+
+    #[test]
+    fn rolling_windows_include_today() {
+        assert_eq!(
+            range("last_1_days", "2026-10-17", Weekday::Mon),
+            (day("2026-10-17"), day("2026-10-17"))
+        );
+        assert_eq!(
+            range("last_7_days", "2026-10-17", Weekday::Mon),
+            (day("2026-10-11"), day("2026-10-17"))
+        );
+        assert_eq!(
+            range("last_30_days", "2026-03-01", Weekday::Mon),
+            (day("2026-01-31"), day("2026-03-01"))
+        );
+        let (start, end) = range(
+            &format!("last_{}_days", MAX_ROLLING_DAYS),
+            "2026-10-17",
+            Weekday::Mon,
+        );
+        assert_eq!((end - start).num_days() + 1, MAX_ROLLING_DAYS);
+
+        for period in [
+            "last_0_days",
+            &format!("last_{}_days", MAX_ROLLING_DAYS + 1),
+            "last_-3_days",
+            "last_x_days",
+            "last_7_day",
+            "next_week",
+        ] {
+            let result = calculate_date_range(
+                &query(json!({ "period": period })),
+                day("2026-10-17"),
+                Weekday::Mon,
+            );
+            assert!(
+                matches!(result, Err(ServiceError::BadRequest(_))),
+                "{}",
+                period
+            );
+        }
+    }
+
+    #[test]
+    fn weeks_start_on_the_configured_day() {
+        // Samedi 17 octobre 2026
+        assert_eq!(
+            range("this_week", "2026-10-17", Weekday::Mon),
+            (day("2026-10-12"), day("2026-10-18"))
+        );
+        assert_eq!(
+            range("this_week", "2026-10-17", Weekday::Sun),
+            (day("2026-10-11"), day("2026-10-17"))
+        );
+        assert_eq!(
+            range("this_week", "2026-10-17", Weekday::Sat),
+            (day("2026-10-17"), day("2026-10-23"))
+        );
+        assert_eq!(
+            range("last_week", "2026-10-17", Weekday::Sun),
+            (day("2026-10-04"), day("2026-10-10"))
+        );
+        // Période par défaut : la semaine courante
+        assert_eq!(
+            calculate_date_range(&query(json!({})), day("2026-10-17"), Weekday::Sun).unwrap(),
+            (day("2026-10-11"), day("2026-10-17"))
+        );
+    }
+
+    #[test]
+    fn explicit_dates_take_precedence_over_the_period() {
+        let explicit = query(json!({
+            "period": "this_year",
+            "start_date": "2026-02-03",
+            "end_date": "2026-02-03",
+        }));
+        assert_eq!(
+            calculate_date_range(&explicit, day("2026-10-17"), Weekday::Mon).unwrap(),
+            (day("2026-02-03"), day("2026-02-03"))
+        );
+        let reversed = query(json!({ "start_date": "2026-02-04", "end_date": "2026-02-03" }));
+        assert!(matches!(
+            calculate_date_range(&reversed, day("2026-10-17"), Weekday::Mon),
+            Err(ServiceError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn resolved_period_uses_the_user_day_and_week_start() {
+        // Samedi 23:30 UTC : déjà dimanche à Tokyo, encore samedi à New York
+        let now = utc("2026-10-17T23:30:00Z");
+        let this_week = query(json!({ "period": "this_week" }));
+
+        let tokyo = resolve_period_at(&this_week, &settings("Asia/Tokyo", 7), now).unwrap();
+        assert_eq!(tokyo.week_start, Weekday::Sun);
+        assert_eq!(
+            (tokyo.start_date, tokyo.end_date),
+            (day("2026-10-18"), day("2026-10-24"))
+        );
+        assert_eq!(tokyo.start_utc, utc("2026-10-17T15:00:00Z"));
+        assert_eq!(tokyo.end_utc, utc("2026-10-24T15:00:00Z"));
+
+        let new_york =
+            resolve_period_at(&this_week, &settings("America/New_York", 1), now).unwrap();
+        assert_eq!(new_york.week_start, Weekday::Mon);
+        assert_eq!(
+            (new_york.start_date, new_york.end_date),
+            (day("2026-10-12"), day("2026-10-18"))
+        );
+        assert_eq!(new_york.start_utc, utc("2026-10-12T04:00:00Z"));
+        assert_eq!(new_york.end_utc, utc("2026-10-19T04:00:00Z"));
+
+        // `tz` de la requête prioritaire sur les réglages
+        let today_in_tokyo = query(json!({ "period": "today", "tz": "Asia/Tokyo" }));
+        let period =
+            resolve_period_at(&today_in_tokyo, &settings("America/New_York", 1), now).unwrap();
+        assert_eq!(period.start_date, day("2026-10-18"));
+        let unknown_tz = query(json!({ "period": "today", "tz": "Mars/Olympus" }));
+        assert!(matches!(
+            resolve_period_at(&unknown_tz, &settings("Europe/Paris", 1), now),
+            Err(ServiceError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn resolved_period_end_spans_the_dst_change() {
+        // Semaine du changement d'heure d'octobre à Paris : 7 jours et une heure
+        let now = utc("2026-10-21T12:00:00Z");
+        let period = resolve_period_at(
+            &query(json!({ "period": "this_week" })),
+            &settings("Europe/Paris", 1),
+            now,
+        )
+        .unwrap();
+        assert_eq!(period.start_utc, utc("2026-10-18T22:00:00Z"));
+        assert_eq!(period.end_utc, utc("2026-10-25T23:00:00Z"));
+        assert_eq!((period.end_utc - period.start_utc).num_hours(), 7 * 24 + 1);
+    }
+
+    #[test]
+    fn period_boundaries_follow_dst_day_length() {
+        // 29 mars 2026 : 02:00 -> 03:00, journée de 23 heures
+        let spring = paris_period(day("2026-03-29"), day("2026-03-29"));
+        assert_eq!(spring.start_utc, utc("2026-03-28T23:00:00Z"));
+        assert_eq!(spring.end_utc, utc("2026-03-29T22:00:00Z"));
+        // 25 octobre 2026 : 03:00 -> 02:00, journée de 25 heures
+        let autumn = paris_period(day("2026-10-25"), day("2026-10-25"));
+        assert_eq!(autumn.start_utc, utc("2026-10-24T22:00:00Z"));
+        assert_eq!(autumn.end_utc, utc("2026-10-25T23:00:00Z"));
+    }
+
+    #[test]
+    fn midnight_skipped_by_dst_starts_the_day_at_the_first_valid_instant() {
+        // São Paulo, 4 novembre 2018 : 00:00 -> 01:00
+        let start = local_midnight_to_utc(day("2018-11-04"), chrono_tz::America::Sao_Paulo);
+        assert_eq!(start, utc("2018-11-04T03:00:00Z"));
+    }
+
+    #[test]
+    fn spring_forward_has_no_bucket_for_the_missing_hour() {
+        let period = paris_period(day("2026-03-29"), day("2026-03-29"));
+        let rows = [
+            hourly("2026-03-29T00:00:00Z", 600),  // 01:00 (+01:00)
+            hourly("2026-03-29T01:00:00Z", 1200), // 03:00 (+02:00)
+        ];
+        let buckets = fill_buckets(&period, TrendGranularity::Hour, &rows).unwrap();
+
+        assert_eq!(buckets.len(), 23);
+        assert!(buckets
+            .iter()
+            .all(|bucket| bucket.local_start != local("2026-03-29 02:00")));
+        let after_one = &buckets[1];
+        assert_eq!(after_one.local_start, local("2026-03-29 01:00"));
+        assert_eq!(after_one.total, 600);
+        let after_jump = &buckets[2];
+        assert_eq!(after_jump.local_start, local("2026-03-29 03:00"));
+        assert_eq!(after_jump.utc_start, utc("2026-03-29T01:00:00Z"));
+        assert_eq!(after_jump.total, 1200);
+        assert_eq!(buckets.iter().map(|bucket| bucket.total).sum::<i64>(), 1800);
+    }
+
+    #[test]
+    fn fall_back_keeps_both_occurrences_of_the_repeated_hour() {
+        let period = paris_period(day("2026-10-25"), day("2026-10-25"));
+        let rows = [
+            hourly("2026-10-25T00:00:00Z", 900),  // 02:00 (+02:00)
+            hourly("2026-10-25T01:00:00Z", 1800), // 02:00 (+01:00)
+        ];
+        let buckets = fill_buckets(&period, TrendGranularity::Hour, &rows).unwrap();
+
+        assert_eq!(buckets.len(), 25);
+        let repeated: Vec<&TrendBucket> = buckets
+            .iter()
+            .filter(|bucket| bucket.local_start == local("2026-10-25 02:00"))
+            .collect();
+        assert_eq!(repeated.len(), 2);
+        assert_eq!(repeated[0].utc_start, utc("2026-10-25T00:00:00Z"));
+        assert_eq!(repeated[0].total, 900);
+        assert_eq!(repeated[1].utc_start, utc("2026-10-25T01:00:00Z"));
+        assert_eq!(repeated[1].total, 1800);
+    }
+
+    #[test]
+    fn day_buckets_use_local_days_across_dst_changes() {
+        let period = paris_period(day("2026-10-24"), day("2026-10-26"));
+        let rows = [
+            hourly("2026-10-24T21:00:00Z", 300), // 23:00 le 24 (+02:00)
+            hourly("2026-10-25T01:00:00Z", 400), // 02:00 le 25 (+01:00), heure répétée
+            hourly("2026-10-25T22:00:00Z", 500), // 23:00 le 25 (+01:00)
+            hourly("2026-10-25T23:00:00Z", 700), // 00:00 le 26
+        ];
+        let buckets = fill_buckets(&period, TrendGranularity::Day, &rows).unwrap();
+
+        let summary: Vec<(NaiveDateTime, DateTime<Utc>, i64)> = buckets
+            .iter()
+            .map(|bucket| (bucket.local_start, bucket.utc_start, bucket.total))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (local("2026-10-24 00:00"), utc("2026-10-23T22:00:00Z"), 300),
+                (local("2026-10-25 00:00"), utc("2026-10-24T22:00:00Z"), 900),
+                (local("2026-10-26 00:00"), utc("2026-10-25T23:00:00Z"), 700),
+            ]
+        );
+    }
+
+    #[test]
+    fn week_and_month_buckets_start_at_local_midnight() {
+        let period = paris_period(day("2026-03-01"), day("2026-04-30"));
+        let months = fill_buckets(&period, TrendGranularity::Month, &[]).unwrap();
+        let month_starts: Vec<DateTime<Utc>> =
+            months.iter().map(|bucket| bucket.utc_start).collect();
+        assert_eq!(
+            month_starts,
+            vec![utc("2026-02-28T23:00:00Z"), utc("2026-03-31T22:00:00Z")]
+        );
+
+        let period = paris_period(day("2026-03-23"), day("2026-04-05"));
+        let weeks = fill_buckets(&period, TrendGranularity::Week, &[]).unwrap();
+        let week_starts: Vec<DateTime<Utc>> = weeks.iter().map(|bucket| bucket.utc_start).collect();
+        assert_eq!(
+            week_starts,
+            vec![utc("2026-03-22T23:00:00Z"), utc("2026-03-29T22:00:00Z")]
+        );
+    }
+}
@@ -0,0 +1,192 @@
+// OptiTask/backend-api/src/handlers/client_handlers.rs
+use crate::auth_utils::AuthenticatedUser;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::models::{
+    Client, CreateClientPayload, NewClient, UpdateClientChangeset, UpdateClientPayload,
+};
+use crate::ownership::ensure_client_owned;
+use crate::schema::clients::{self, dsl::*};
+use actix_web::{delete, get, post, put, web, HttpResponse};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use serde_json::json;
+use uuid::Uuid;
+
+// === POST /clients ===
+#[post("")]
+pub async fn create_client_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<CreateClientPayload>,
+) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let new_client_data = NewClient {
+        user_id: user_uuid,
+        name: payload.name.clone(),
+        email: payload.email.clone(),
+        address: payload.address.clone(),
+    };
+
+    let client = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            diesel::insert_into(clients::table)
+                .values(&new_client_data)
+                .get_result::<Client>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (create_client): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    Ok(HttpResponse::Created().json(client))
+}
+
+// === GET /clients ===
+#[get("")]
+pub async fn list_clients_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+
+    let client_list = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            clients
+                .filter(user_id.eq(user_uuid))
+                .order(name.asc())
+                .select(Client::as_select())
+                .load::<Client>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_clients): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(client_list))
+}
+
+// === GET /clients/{client_id_path} ===
+#[get("/{client_id_path}")]
+pub async fn get_client_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    client_id_path: web::Path<Uuid>,
+) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let client_to_find_id = client_id_path.into_inner();
+
+    let client_option = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            clients
+                .filter(user_id.eq(user_uuid))
+                .filter(id.eq(client_to_find_id))
+                .select(Client::as_select())
+                .first::<Client>(conn)
+                .optional()
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_client): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    match client_option {
+        Some(client) => Ok(HttpResponse::Ok().json(client)),
+        None => Err(ServiceError::NotFound(format!(
+            "Client with id {} not found or not owned by user",
+            client_to_find_id
+        ))),
+    }
+}
+
+// === PUT /clients/{client_id_path} ===
+#[put("/{client_id_path}")]
+pub async fn update_client_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    client_id_path: web::Path<Uuid>,
+    payload: web::Json<UpdateClientPayload>,
+) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let client_to_update_id = client_id_path.into_inner();
+
+    let client_changes = UpdateClientChangeset {
+        name: payload.name.clone(),
+        email: payload.email.clone(),
+        address: payload.address.clone(),
+        updated_at: Some(Utc::now().naive_utc()),
+    };
+
+    let updated_client = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_client_owned(conn, user_uuid, client_to_update_id)?;
+            diesel::update(
+                clients
+                    .filter(id.eq(client_to_update_id))
+                    .filter(user_id.eq(user_uuid)),
+            )
+            .set(&client_changes)
+            .get_result::<Client>(conn)
+            .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (update_client): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(updated_client))
+}
+
+// === DELETE /clients/{client_id_path} ===
+// Les projets du client sont conservés (client_id remis à NULL), ses taux sont supprimés.
+#[delete("/{client_id_path}")]
+pub async fn delete_client_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    client_id_path: web::Path<Uuid>,
+) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let client_to_delete_id = client_id_path.into_inner();
+
+    let num_deleted = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_client_owned(conn, user_uuid, client_to_delete_id)?;
+            diesel::delete(
+                clients
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq(client_to_delete_id)),
+            )
+            .execute(conn)
+            .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (delete_client): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    if num_deleted > 0 {
+        Ok(HttpResponse::Ok().json(json!({
+            "status": "success",
+            "message": format!("Client with id {} deleted successfully", client_to_delete_id)
+        })))
+    } else {
+        Err(ServiceError::NotFound(format!(
+            "Client with id {} not found or not owned by user to delete",
+            client_to_delete_id
+        )))
+    }
+}
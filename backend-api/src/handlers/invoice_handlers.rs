@@ -0,0 +1,851 @@
+// OptiTask/backend-api/src/handlers/invoice_handlers.rs
+// Factures générées à partir des time entries facturables et non encore facturées d'un client.
+// Les entrées incluses sont rattachées à la facture (time_entries.invoice_id) et verrouillées
+// par trigger ; supprimer une facture brouillon les libère.
+use crate::auth_utils::AuthenticatedUser;
+use crate::billing::BILLED_ENTRIES_CTE;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::handlers::analytics_handlers::{local_midnight_to_utc, settings_timezone};
+use crate::handlers::user_handlers::load_user_settings;
+use crate::invoice_render::{render_invoice_html, render_invoice_pdf};
+use crate::models::{
+    Client, CreateInvoicePayload, Invoice, InvoiceLineItem, InvoiceWithLineItems, NewInvoice,
+    NewInvoiceLineItem, UpdateInvoiceStatusPayload,
+};
+use crate::rounding::{
+    round_seconds, validate_increment_minutes, RoundingDirection, RoundingScope,
+};
+use crate::schema::{clients, invoice_line_items, invoices, time_entries};
+use actix_web::http::header;
+use actix_web::{delete, get, post, put, web, HttpResponse, Result as ActixResult};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::sql_query;
+use diesel::sql_types::{BigInt, Date, Integer, Nullable, Text, Timestamptz, Uuid as DieselUuid};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+// Regroupement des entrées en lignes de facture
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InvoiceGrouping {
+    Project,
+    Task,
+}
+
+impl InvoiceGrouping {
+    fn parse(value: Option<&str>) -> Result<Self, ServiceError> {
+        match value {
+            Some("project") | None => Ok(InvoiceGrouping::Project),
+            Some("task") => Ok(InvoiceGrouping::Task),
+            Some(other) => Err(ServiceError::BadRequest(format!(
+                "Invalid group_by: {}. Supported: project, task.",
+                other
+            ))),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            InvoiceGrouping::Project => "project",
+            InvoiceGrouping::Task => "task",
+        }
+    }
+}
+
+// Entrée facturable candidate, avec son taux résolu
+#[derive(QueryableByName, Debug)]
+struct InvoiceableEntry {
+    #[diesel(sql_type = DieselUuid)]
+    entry_id: Uuid,
+    #[diesel(sql_type = Nullable<DieselUuid>)]
+    task_id: Option<Uuid>,
+    #[diesel(sql_type = Nullable<Text>)]
+    task_title: Option<String>,
+    #[diesel(sql_type = Nullable<DieselUuid>)]
+    project_id: Option<Uuid>,
+    #[diesel(sql_type = Nullable<Text>)]
+    project_name: Option<String>,
+    #[diesel(sql_type = BigInt)]
+    seconds: i64,
+    #[diesel(sql_type = Nullable<Text>)]
+    currency: Option<String>,
+    #[diesel(sql_type = Nullable<BigInt>)]
+    hourly_rate_cents: Option<i64>,
+    #[diesel(sql_type = Integer)]
+    rounding_increment_minutes: i32,
+    #[diesel(sql_type = Text)]
+    rounding_direction: String,
+    #[diesel(sql_type = Text)]
+    rounding_scope: String,
+    #[diesel(sql_type = Date)]
+    work_date: NaiveDate,
+}
+
+// Ligne en cours de constitution
+struct LineAccumulator {
+    description: String,
+    project_id: Option<Uuid>,
+    task_id: Option<Uuid>,
+    seconds: i64, // Temps déjà arrondi
+    // Temps brut des projets arrondis par jour, par (projet, jour local), avec leur règle
+    day_seconds: BTreeMap<(Option<Uuid>, NaiveDate), (i64, i32, RoundingDirection)>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ListInvoicesQuery {
+    pub status: Option<String>,
+    pub client_id: Option<Uuid>,
+}
+
+fn validate_status(value: &str) -> Result<(), ServiceError> {
+    match value {
+        "draft" | "sent" | "paid" => Ok(()),
+        other => Err(ServiceError::BadRequest(format!(
+            "Invalid invoice status: {}. Supported: draft, sent, paid.",
+            other
+        ))),
+    }
+}
+
+fn load_invoice(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    invoice_uuid: Uuid,
+) -> Result<Invoice, ServiceError> {
+    invoices::table
+        .filter(invoices::id.eq(invoice_uuid))
+        .filter(invoices::user_id.eq(user_uuid))
+        .select(Invoice::as_select())
+        .first::<Invoice>(conn)
+        .optional()?
+        .ok_or_else(|| {
+            ServiceError::NotFound(format!(
+                "Invoice with id {} not found or not owned by user",
+                invoice_uuid
+            ))
+        })
+}
+
+fn load_invoice_with_line_items(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    invoice_uuid: Uuid,
+) -> Result<InvoiceWithLineItems, ServiceError> {
+    let invoice = load_invoice(conn, user_uuid, invoice_uuid)?;
+    let line_items = invoice_line_items::table
+        .filter(invoice_line_items::invoice_id.eq(invoice.id))
+        .order(invoice_line_items::position.asc())
+        .select(InvoiceLineItem::as_select())
+        .load::<InvoiceLineItem>(conn)?;
+    let time_entry_count = time_entries::table
+        .filter(time_entries::invoice_id.eq(invoice.id))
+        .count()
+        .get_result::<i64>(conn)?;
+    Ok(InvoiceWithLineItems {
+        invoice,
+        line_items,
+        time_entry_count,
+    })
+}
+
+// Prochain numéro de l'année d'émission : INV-<année>-<séquence sur 4 chiffres>
+fn next_invoice_number(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    issue_date: NaiveDate,
+) -> Result<(i32, String), ServiceError> {
+    let year = issue_date.year();
+    let year_start = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+    let year_end = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+    let last_sequence = invoices::table
+        .filter(invoices::user_id.eq(user_uuid))
+        .filter(invoices::issue_date.between(year_start, year_end))
+        .select(diesel::dsl::max(invoices::sequence_number))
+        .first::<Option<i32>>(conn)?;
+    let sequence = last_sequence.unwrap_or(0) + 1;
+    Ok((sequence, format!("INV-{}-{:04}", year, sequence)))
+}
+
+// === POST /invoices ===
+#[post("")]
+pub async fn create_invoice_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<CreateInvoicePayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let payload = payload.into_inner();
+    log::info!(
+        "User {} creating invoice with payload: {:?}",
+        user_uuid,
+        payload
+    );
+
+    let grouping = InvoiceGrouping::parse(payload.group_by.as_deref())?;
+    let direction = RoundingDirection::parse(payload.rounding_direction.as_deref())?;
+    // Arrondi imposé à toutes les entrées ; absent : règles d'arrondi de chaque projet
+    let rounding_override = payload.rounding_increment_minutes;
+    if let Some(increment_minutes) = rounding_override {
+        validate_increment_minutes(increment_minutes)?;
+    }
+    if payload.date_from > payload.date_to {
+        return Err(ServiceError::BadRequest(
+            "date_from must be on or before date_to.".to_string(),
+        ));
+    }
+
+    let created_invoice = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let client = clients::table
+                .filter(clients::id.eq(payload.client_id))
+                .filter(clients::user_id.eq(user_uuid))
+                .select(Client::as_select())
+                .first::<Client>(conn)
+                .optional()?
+                .ok_or_else(|| {
+                    ServiceError::NotFound(format!(
+                        "Client with id {} not found or not owned by user",
+                        payload.client_id
+                    ))
+                })?;
+            let settings = load_user_settings(conn, user_uuid)?;
+            let tz = settings_timezone(&settings)?;
+
+            // Entrées terminées, facturables, pas encore facturées, dont le début tombe dans la période locale
+            let entries = sql_query(format!(
+                "{}SELECT entry_id, task_id, task_title, project_id, project_name, seconds, \
+                    currency, hourly_rate_cents, rounding_increment_minutes, rounding_direction, \
+                    rounding_scope, work_date \
+                 FROM billed_entries \
+                 WHERE is_billable AND client_id = $5 \
+                 AND invoice_id IS NULL AND end_time IS NOT NULL \
+                 ORDER BY start_time ASC",
+                BILLED_ENTRIES_CTE
+            ))
+            .bind::<DieselUuid, _>(user_uuid)
+            .bind::<Timestamptz, _>(local_midnight_to_utc(payload.date_from, tz))
+            .bind::<Timestamptz, _>(local_midnight_to_utc(
+                payload.date_to + Duration::days(1),
+                tz,
+            ))
+            .bind::<Text, _>(tz.name())
+            .bind::<DieselUuid, _>(client.id)
+            .load::<InvoiceableEntry>(conn)?;
+
+            if entries.is_empty() {
+                return Err(ServiceError::BadRequest(
+                    "No unbilled billable time entries for this client in the period.".to_string(),
+                ));
+            }
+            let unrated: Vec<Uuid> = entries
+                .iter()
+                .filter(|entry| entry.hourly_rate_cents.is_none())
+                .map(|entry| entry.entry_id)
+                .collect();
+            if !unrated.is_empty() {
+                return Err(ServiceError::BadRequest(format!(
+                    "{} billable time entries have no applicable hourly rate (e.g. {}). Add a rate for the client, project or task first.",
+                    unrated.len(),
+                    unrated[0]
+                )));
+            }
+            let currencies: BTreeSet<&str> = entries
+                .iter()
+                .filter_map(|entry| entry.currency.as_deref())
+                .collect();
+            if currencies.len() > 1 {
+                return Err(ServiceError::BadRequest(format!(
+                    "Time entries are rated in several currencies ({}). Invoice a shorter period or align the rates.",
+                    currencies.into_iter().collect::<Vec<_>>().join(", ")
+                )));
+            }
+            let currency = currencies.into_iter().next().unwrap_or_default().to_string();
+
+            // Une ligne par (projet, tâche, taux) : deux taux différents dans un même groupe
+            // (changement de tarif en cours de période, taux de tâche) donnent deux lignes.
+            // Le libellé n'est qu'affiché : deux projets ou tâches homonymes restent distincts.
+            let mut lines: BTreeMap<(Option<Uuid>, Option<Uuid>, i64), LineAccumulator> =
+                BTreeMap::new();
+            for entry in &entries {
+                let project_label = entry
+                    .project_name
+                    .clone()
+                    .unwrap_or_else(|| "No project".to_string());
+                let (description, line_task_id) = match grouping {
+                    InvoiceGrouping::Project => (project_label, None),
+                    InvoiceGrouping::Task => (
+                        format!(
+                            "{} - {}",
+                            project_label,
+                            entry.task_title.as_deref().unwrap_or("No task")
+                        ),
+                        entry.task_id,
+                    ),
+                };
+                let rate = entry.hourly_rate_cents.unwrap_or(0);
+                let line = lines
+                    .entry((entry.project_id, line_task_id, rate))
+                    .or_insert(LineAccumulator {
+                        description,
+                        project_id: entry.project_id,
+                        task_id: line_task_id,
+                        seconds: 0,
+                        day_seconds: BTreeMap::new(),
+                    });
+                // L'arrondi ne touche que le montant facturé, le temps suivi reste intact
+                let (increment_minutes, entry_direction, scope) = match rounding_override {
+                    Some(increment_minutes) => (increment_minutes, direction, RoundingScope::Entry),
+                    None => (
+                        entry.rounding_increment_minutes,
+                        RoundingDirection::parse(Some(&entry.rounding_direction))?,
+                        RoundingScope::parse(Some(&entry.rounding_scope))?,
+                    ),
+                };
+                match scope {
+                    RoundingScope::Entry => {
+                        line.seconds +=
+                            round_seconds(entry.seconds, increment_minutes, entry_direction);
+                    }
+                    RoundingScope::Day => {
+                        let bucket = line
+                            .day_seconds
+                            .entry((entry.project_id, entry.work_date))
+                            .or_insert((0, increment_minutes, entry_direction));
+                        bucket.0 += entry.seconds;
+                    }
+                }
+            }
+            // Arrondi par jour : le total du jour est arrondi dans chaque ligne
+            for line in lines.values_mut() {
+                for (seconds, increment_minutes, day_direction) in line.day_seconds.values() {
+                    line.seconds += round_seconds(*seconds, *increment_minutes, *day_direction);
+                }
+            }
+            // Lignes dans l'ordre des libellés, puis des taux
+            let mut lines: Vec<(i64, LineAccumulator)> = lines
+                .into_iter()
+                .map(|((_, _, rate), line)| (rate, line))
+                .collect();
+            lines.sort_by(|(rate_a, line_a), (rate_b, line_b)| {
+                (&line_a.description, rate_a).cmp(&(&line_b.description, rate_b))
+            });
+
+            // Montant arrondi au centime (demi-centime au supérieur)
+            let line_amounts: Vec<i64> = lines
+                .iter()
+                .map(|(rate, line)| (line.seconds * rate + 1800) / 3600)
+                .collect();
+            let total_cents: i64 = line_amounts.iter().sum();
+
+            let issue_date = payload
+                .issue_date
+                .unwrap_or_else(|| Utc::now().with_timezone(&tz).date_naive());
+            let (sequence_number, invoice_number) =
+                next_invoice_number(conn, user_uuid, issue_date)?;
+
+            let invoice = diesel::insert_into(invoices::table)
+                .values(&NewInvoice {
+                    user_id: user_uuid,
+                    client_id: Some(client.id),
+                    sequence_number,
+                    invoice_number,
+                    client_name: client.name.clone(),
+                    client_email: client.email.clone(),
+                    client_address: client.address.clone(),
+                    currency,
+                    period_start: payload.date_from,
+                    period_end: payload.date_to,
+                    issue_date,
+                    due_date: payload.due_date,
+                    group_by: grouping.as_str().to_string(),
+                    rounding_increment_minutes: rounding_override.unwrap_or(0),
+                    rounding_direction: direction.as_str().to_string(),
+                    total_cents,
+                    notes: payload.notes.clone(),
+                })
+                .get_result::<Invoice>(conn)
+                .map_err(|error| match error {
+                    // Deux factures créées en même temps : le client peut réessayer
+                    DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        ServiceError::Conflict(
+                            "Invoice number already taken by a concurrent request, please retry."
+                                .to_string(),
+                        )
+                    }
+                    _ => ServiceError::from(error),
+                })?;
+
+            let new_line_items: Vec<NewInvoiceLineItem> = lines
+                .into_iter()
+                .zip(line_amounts)
+                .enumerate()
+                .map(
+                    |(position, ((rate, line), amount_cents))| NewInvoiceLineItem {
+                        invoice_id: invoice.id,
+                        user_id: user_uuid,
+                        project_id: line.project_id,
+                        task_id: line.task_id,
+                        description: line.description,
+                        quantity_seconds: line.seconds,
+                        hourly_rate_cents: rate,
+                        amount_cents,
+                        position: position as i32,
+                    },
+                )
+                .collect();
+            diesel::insert_into(invoice_line_items::table)
+                .values(&new_line_items)
+                .execute(conn)?;
+
+            // Rattachement (et donc verrouillage) des entrées ; une entrée facturée
+            // entre-temps par une autre requête annule tout
+            let entry_ids: Vec<Uuid> = entries.iter().map(|entry| entry.entry_id).collect();
+            let attached = diesel::update(
+                time_entries::table
+                    .filter(time_entries::id.eq_any(&entry_ids))
+                    .filter(time_entries::invoice_id.is_null()),
+            )
+            .set(time_entries::invoice_id.eq(invoice.id))
+            .execute(conn)?;
+            if attached != entry_ids.len() {
+                return Err(ServiceError::Conflict(
+                    "Some time entries were invoiced concurrently, please retry.".to_string(),
+                ));
+            }
+
+            load_invoice_with_line_items(conn, user_uuid, invoice.id)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (create_invoice): {:?}", e);
+        ServiceError::InternalServerError("Error processing create_invoice request".to_string())
+    })??;
+
+    log::info!(
+        "Invoice {} created with {} line items",
+        created_invoice.invoice.invoice_number,
+        created_invoice.line_items.len()
+    );
+    Ok(HttpResponse::Created().json(created_invoice))
+}
+
+// === GET /invoices ===
+#[get("")]
+pub async fn list_invoices_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<ListInvoicesQuery>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let query_options = query_params.into_inner();
+    if let Some(status_filter) = &query_options.status {
+        validate_status(status_filter)?;
+    }
+
+    let invoice_list = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let mut query = invoices::table
+                .filter(invoices::user_id.eq(user_uuid))
+                .select(Invoice::as_select())
+                .into_boxed();
+            if let Some(status_filter) = query_options.status {
+                query = query.filter(invoices::status.eq(status_filter));
+            }
+            if let Some(client_filter) = query_options.client_id {
+                query = query.filter(invoices::client_id.eq(client_filter));
+            }
+            query
+                .order((
+                    invoices::issue_date.desc(),
+                    invoices::sequence_number.desc(),
+                ))
+                .load::<Invoice>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_invoices): {:?}", e);
+        ServiceError::InternalServerError("Error processing list_invoices request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(invoice_list))
+}
+
+// === GET /invoices/{invoice_id_path} ===
+#[get("/{invoice_id_path}")]
+pub async fn get_invoice_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    invoice_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let invoice_to_find_id = invoice_id_path.into_inner();
+
+    let invoice = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            load_invoice_with_line_items(conn, user_uuid, invoice_to_find_id)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_invoice): {:?}", e);
+        ServiceError::InternalServerError("Error processing get_invoice request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(invoice))
+}
+
+// === PUT /invoices/{invoice_id_path}/status ===
+// Transitions : draft -> sent -> paid. Un retour en arrière d'un cran est permis
+// (envoi annulé, paiement rejeté) ; les dates correspondantes sont alors effacées.
+#[put("/{invoice_id_path}/status")]
+pub async fn update_invoice_status_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    invoice_id_path: web::Path<Uuid>,
+    payload: web::Json<UpdateInvoiceStatusPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let invoice_to_update_id = invoice_id_path.into_inner();
+    let new_status = payload.into_inner().status;
+    validate_status(&new_status)?;
+
+    let updated_invoice = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let invoice = load_invoice(conn, user_uuid, invoice_to_update_id)?;
+            let now = Utc::now();
+            let (sent_at, paid_at) = match (invoice.status.as_str(), new_status.as_str()) {
+                (current, requested) if current == requested => (invoice.sent_at, invoice.paid_at),
+                ("draft", "sent") => (Some(now), None),
+                ("sent", "paid") => (invoice.sent_at, Some(now)),
+                ("sent", "draft") => (None, None),
+                ("paid", "sent") => (invoice.sent_at, None),
+                (current, requested) => {
+                    return Err(ServiceError::Conflict(format!(
+                        "Invoice cannot go from '{}' to '{}'.",
+                        current, requested
+                    )))
+                }
+            };
+
+            diesel::update(invoices::table.filter(invoices::id.eq(invoice.id)))
+                .set((
+                    invoices::status.eq(&new_status),
+                    invoices::sent_at.eq(sent_at),
+                    invoices::paid_at.eq(paid_at),
+                ))
+                .execute(conn)?;
+            load_invoice_with_line_items(conn, user_uuid, invoice.id)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (update_invoice_status): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing update_invoice_status request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Ok().json(updated_invoice))
+}
+
+// === DELETE /invoices/{invoice_id_path} ===
+// Brouillons uniquement ; les entrées sont libérées (invoice_id remis à NULL par la FK).
+#[delete("/{invoice_id_path}")]
+pub async fn delete_invoice_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    invoice_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let invoice_to_delete_id = invoice_id_path.into_inner();
+
+    web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let invoice = load_invoice(conn, user_uuid, invoice_to_delete_id)?;
+            if invoice.status != "draft" {
+                return Err(ServiceError::Conflict(format!(
+                    "Invoice {} is '{}': only draft invoices can be deleted.",
+                    invoice.invoice_number, invoice.status
+                )));
+            }
+            diesel::delete(invoices::table.filter(invoices::id.eq(invoice.id)))
+                .execute(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (delete_invoice): {:?}", e);
+        ServiceError::InternalServerError("Error processing delete_invoice request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": "success",
+        "message": format!("Invoice with id {} deleted successfully", invoice_to_delete_id)
+    })))
+}
+
+// === GET /invoices/{invoice_id_path}/html ===
+#[get("/{invoice_id_path}/html")]
+pub async fn get_invoice_html_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    invoice_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let invoice_to_render_id = invoice_id_path.into_inner();
+
+    let html = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let document = load_invoice_with_line_items(conn, user_uuid, invoice_to_render_id)?;
+            Ok(render_invoice_html(&document.invoice, &document.line_items))
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_invoice_html): {:?}", e);
+        ServiceError::InternalServerError("Error processing get_invoice_html request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(html))
+}
+
+// === GET /invoices/{invoice_id_path}/pdf ===
+#[get("/{invoice_id_path}/pdf")]
+pub async fn get_invoice_pdf_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    invoice_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let invoice_to_render_id = invoice_id_path.into_inner();
+
+    let (invoice_number, pdf) = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let document = load_invoice_with_line_items(conn, user_uuid, invoice_to_render_id)?;
+            let pdf = render_invoice_pdf(&document.invoice, &document.line_items);
+            Ok((document.invoice.invoice_number, pdf))
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_invoice_pdf): {:?}", e);
+        ServiceError::InternalServerError("Error processing get_invoice_pdf request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("inline; filename=\"{}.pdf\"", invoice_number),
+        ))
+        .body(pdf))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configure_routes;
+    use crate::handlers::user_handlers::load_user_settings;
+    use crate::models::NewTimesheet;
+    use crate::schema::{clients, hourly_rates, projects, time_entries, timesheets};
+    use crate::test_support::{seed_project, test_auth_config, test_pool};
+    use actix_web::{test, web, App};
+    use chrono::{DateTime, Duration, NaiveDate, Utc};
+    use diesel::pg::PgConnection;
+    use diesel::prelude::*;
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    // Client facturé 100 EUR de l'heure
+    fn seed_rated_client(conn: &mut PgConnection, owner: Uuid) -> Uuid {
+        let client: Uuid = diesel::insert_into(clients::table)
+            .values((clients::user_id.eq(owner), clients::name.eq("Client")))
+            .returning(clients::id)
+            .get_result(conn)
+            .expect("Failed to seed client");
+        diesel::insert_into(hourly_rates::table)
+            .values((
+                hourly_rates::user_id.eq(owner),
+                hourly_rates::client_id.eq(Some(client)),
+                hourly_rates::amount_cents.eq(10_000),
+                hourly_rates::currency.eq("EUR"),
+                hourly_rates::effective_from.eq(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()),
+            ))
+            .execute(conn)
+            .expect("Failed to seed rate");
+        client
+    }
+
+    // Projet facturable du client ; seed_project donne toujours le même nom
+    fn seed_billable_project(conn: &mut PgConnection, owner: Uuid, client: Uuid) -> Uuid {
+        let project = seed_project(conn, owner);
+        diesel::update(projects::table.find(project))
+            .set((
+                projects::client_id.eq(Some(client)),
+                projects::billable.eq(true),
+            ))
+            .execute(conn)
+            .expect("Failed to attach project to client");
+        project
+    }
+
+    // Entrée d'une heure sur le projet
+    fn seed_project_entry(
+        conn: &mut PgConnection,
+        owner: Uuid,
+        project: Uuid,
+        start: DateTime<Utc>,
+    ) -> Uuid {
+        diesel::insert_into(time_entries::table)
+            .values((
+                time_entries::user_id.eq(owner),
+                time_entries::project_id.eq(Some(project)),
+                time_entries::start_time.eq(start),
+                time_entries::end_time.eq(Some(start + Duration::hours(1))),
+                time_entries::duration_seconds.eq(Some(3600)),
+            ))
+            .returning(time_entries::id)
+            .get_result(conn)
+            .expect("Failed to seed time entry")
+    }
+
+    fn create_invoice_request(owner: Uuid, client: Uuid) -> test::TestRequest {
+        test::TestRequest::post()
+            .uri("/invoices")
+            .insert_header(("X-User-Id", owner.to_string()))
+            .set_json(json!({
+                "client_id": client,
+                "date_from": "2026-10-19",
+                "date_to": "2026-10-25",
+            }))
+    }
+
+    // Le verrou des semaines approuvées protège le temps, pas la facturation : une semaine
+    // approuvée se facture, et la suppression du brouillon libère ses entrées
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn approved_weeks_can_be_invoiced() {
+        let pool = test_pool();
+        let (owner, manager) = (Uuid::new_v4(), Uuid::new_v4());
+        let (client, entry) = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            for user in [owner, manager] {
+                load_user_settings(&mut conn, user).expect("Failed to create user");
+            }
+            let client = seed_rated_client(&mut conn, owner);
+            let project = seed_billable_project(&mut conn, owner, client);
+            let monday: DateTime<Utc> = "2026-10-19T09:00:00Z".parse().unwrap();
+            let entry = seed_project_entry(&mut conn, owner, project, monday);
+            diesel::insert_into(timesheets::table)
+                .values(&NewTimesheet {
+                    user_id: owner,
+                    approver_id: manager,
+                    iso_year: 2026,
+                    iso_week: 43,
+                    week_start: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                    week_end: NaiveDate::from_ymd_opt(2026, 10, 25).unwrap(),
+                    status: "approved".to_string(),
+                    total_seconds: 3600,
+                    submit_comment: None,
+                    submitted_at: Some(Utc::now()),
+                })
+                .execute(&mut conn)
+                .expect("Failed to approve timesheet");
+            (client, entry)
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_auth_config()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let response =
+            test::call_service(&app, create_invoice_request(owner, client).to_request()).await;
+        assert_eq!(response.status(), 201);
+        let invoice: Value = test::read_body_json(response).await;
+        assert_eq!(invoice["line_items"][0]["quantity_seconds"], json!(3600));
+
+        let request = test::TestRequest::delete()
+            .uri(&format!("/invoices/{}", invoice["id"].as_str().unwrap()))
+            .insert_header(("X-User-Id", owner.to_string()))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), 200);
+
+        let mut conn = pool.get().expect("Failed to get test connection");
+        diesel::sql_query("RESET ROLE")
+            .execute(&mut conn)
+            .expect("Failed to reset role");
+        let invoice_id = time_entries::table
+            .find(entry)
+            .select(time_entries::invoice_id)
+            .first::<Option<Uuid>>(&mut conn)
+            .expect("Failed to load time entry");
+        assert_eq!(invoice_id, None);
+    }
+
+    // Deux projets homonymes au même taux donnent deux lignes, chacune sur son projet
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn homonymous_projects_get_their_own_lines() {
+        let pool = test_pool();
+        let owner = Uuid::new_v4();
+        let (client, first_project, second_project) = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            load_user_settings(&mut conn, owner).expect("Failed to create user");
+            let client = seed_rated_client(&mut conn, owner);
+            let first_project = seed_billable_project(&mut conn, owner, client);
+            let second_project = seed_billable_project(&mut conn, owner, client);
+            let monday: DateTime<Utc> = "2026-10-19T09:00:00Z".parse().unwrap();
+            seed_project_entry(&mut conn, owner, first_project, monday);
+            seed_project_entry(
+                &mut conn,
+                owner,
+                second_project,
+                monday + Duration::hours(2),
+            );
+            seed_project_entry(
+                &mut conn,
+                owner,
+                second_project,
+                monday + Duration::hours(4),
+            );
+            (client, first_project, second_project)
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_auth_config()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let invoice: Value =
+            test::call_and_read_body_json(&app, create_invoice_request(owner, client).to_request())
+                .await;
+        let mut lines: Vec<(Value, Value)> = invoice["line_items"]
+            .as_array()
+            .expect("Invoice has no line items")
+            .iter()
+            .map(|line| (line["project_id"].clone(), line["quantity_seconds"].clone()))
+            .collect();
+        lines.sort_by_key(|(_, seconds)| seconds.as_i64());
+        assert_eq!(
+            lines,
+            vec![
+                (json!(first_project), json!(3600)),
+                (json!(second_project), json!(7200)),
+            ]
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use crate::auth_utils::AuthenticatedUser;
-use crate::db::DbPool;
+use crate::db::{run_as_user, DbPool};
 use crate::error_handler::ServiceError;
 use crate::models::{
     CreateLabelPayload, Label, NewLabel, UpdateLabelChangeset, UpdateLabelPayload,
 };
+use crate::ownership::ensure_label_owned;
 use crate::schema::labels::{self, dsl::*}; // dsl::* pour user_id, id etc.
 use actix_web::{delete, get, post, put, web, HttpResponse, Result as ActixResult};
 use chrono::Utc;
@@ -21,18 +22,20 @@ pub async fn create_label_handler(
 ) -> ActixResult<HttpResponse, ServiceError> {
     log::info!("Create label payload received: {:?}", payload);
 
+    let user_uuid = authenticated_user.id;
     let new_label_data = NewLabel {
-        user_id: authenticated_user.id,
+        user_id: user_uuid,
         name: payload.name.clone(),
         color: payload.color.clone(),
     };
 
     let created_label = web::block(move || {
-        let mut conn = pool.get()?;
-        diesel::insert_into(labels::table)
-            .values(&new_label_data)
-            .get_result::<Label>(&mut conn)
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            diesel::insert_into(labels::table)
+                .values(&new_label_data)
+                .get_result::<Label>(conn)
+                .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -54,13 +57,14 @@ pub async fn list_labels_handler(
     log::info!("Listing labels for user: {}", user_uuid);
 
     let label_list = web::block(move || {
-        let mut conn = pool.get()?;
-        labels
-            .filter(user_id.eq(user_uuid))
-            .order(name.asc()) // Ordonner par nom par exemple
-            .select(Label::as_select())
-            .load::<Label>(&mut conn)
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            labels
+                .filter(user_id.eq(user_uuid))
+                .order(name.asc()) // Ordonner par nom par exemple
+                .select(Label::as_select())
+                .load::<Label>(conn)
+                .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -84,14 +88,15 @@ pub async fn get_label_handler(
     log::info!("Fetching label {} for user {}", label_to_find_id, user_uuid);
 
     let label_option = web::block(move || {
-        let mut conn = pool.get()?;
-        labels
-            .filter(user_id.eq(user_uuid))
-            .filter(id.eq(label_to_find_id))
-            .select(Label::as_select())
-            .first::<Label>(&mut conn)
-            .optional()
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            labels
+                .filter(user_id.eq(user_uuid))
+                .filter(id.eq(label_to_find_id))
+                .select(Label::as_select())
+                .first::<Label>(conn)
+                .optional()
+                .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -138,15 +143,17 @@ pub async fn update_label_handler(
     );
 
     let updated_label = web::block(move || {
-        let mut conn = pool.get()?;
-        diesel::update(
-            labels
-                .filter(id.eq(label_to_update_id))
-                .filter(user_id.eq(user_uuid)),
-        )
-        .set(&label_changes)
-        .get_result::<Label>(&mut conn)
-        .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_label_owned(conn, user_uuid, label_to_update_id)?;
+            diesel::update(
+                labels
+                    .filter(id.eq(label_to_update_id))
+                    .filter(user_id.eq(user_uuid)),
+            )
+            .set(&label_changes)
+            .get_result::<Label>(conn)
+            .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -178,14 +185,16 @@ pub async fn delete_label_handler(
     // ou supprimer les associations dans task_labels).
     // Pour l'instant, suppression simple.
     let num_deleted = web::block(move || {
-        let mut conn = pool.get()?;
-        diesel::delete(
-            labels
-                .filter(user_id.eq(user_uuid))
-                .filter(id.eq(label_to_delete_id)),
-        )
-        .execute(&mut conn)
-        .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_label_owned(conn, user_uuid, label_to_delete_id)?;
+            diesel::delete(
+                labels
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq(label_to_delete_id)),
+            )
+            .execute(conn)
+            .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
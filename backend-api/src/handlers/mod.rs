@@ -1,7 +1,18 @@
 // OptiTask/backend-api/src/handlers/mod.rs
+pub mod board_handlers;
+pub mod client_handlers;
+pub mod invoice_handlers;
 pub mod label_handlers;
 pub mod project_handlers;
+pub mod rate_handlers;
+pub mod task_dependency_handlers;
 pub mod task_handlers;
 pub mod task_label_handlers;
 pub mod time_entry_handlers;
+pub mod timesheet_handlers;
+pub mod trash_handlers;
+pub mod workflow_handlers;
+pub mod pomodoro_handlers;
+pub mod token_handlers;
+pub mod user_handlers;
 pub mod analytics_handlers;
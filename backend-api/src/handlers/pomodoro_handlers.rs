@@ -0,0 +1,701 @@
+// OptiTask/backend-api/src/handlers/pomodoro_handlers.rs
+// Machine à états Pomodoro côté serveur : travail -> pause courte (ou longue tous les
+// N cycles) -> travail... Les phases de travail créent des time entries liées à la session.
+// L'état est recalculé à chaque appel à partir des horodatages : aucun client n'a besoin
+// de rester ouvert, et n'importe quel appareil peut reprendre la session.
+use crate::auth_utils::AuthenticatedUser;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::handlers::time_entry_handlers::{
+    find_running_entry, map_running_timer_violation, stop_running_entry,
+};
+use crate::handlers::user_handlers::load_user_settings;
+use crate::models::{
+    NewPomodoroInterruption, NewPomodoroSession, NewTimeEntry, PausePomodoroPayload,
+    PomodoroSession, PomodoroSessionStateChangeset, PomodoroStateResponse, StartPomodoroPayload,
+    TimeEntry,
+};
+use crate::ownership::ensure_task_owned;
+use crate::schema::{pomodoro_interruptions, pomodoro_sessions, time_entries};
+use actix_web::{get, post, web, HttpResponse, Result as ActixResult};
+use chrono::{DateTime, Duration, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use uuid::Uuid;
+
+const PHASE_WORK: &str = "work";
+const PHASE_SHORT_BREAK: &str = "short_break";
+const PHASE_LONG_BREAK: &str = "long_break";
+
+const STATUS_ACTIVE: &str = "active";
+const STATUS_COMPLETED: &str = "completed";
+
+fn phase_minutes(session: &PomodoroSession, phase: &str) -> i32 {
+    match phase {
+        PHASE_SHORT_BREAK => session.short_break_minutes,
+        PHASE_LONG_BREAK => session.long_break_minutes,
+        _ => session.work_minutes,
+    }
+}
+
+// Instant de fin de la phase courante, None si elle est en pause
+fn phase_end(session: &PomodoroSession) -> Option<DateTime<Utc>> {
+    if session.paused_at.is_some() {
+        return None;
+    }
+    let remaining = session.phase_duration_seconds - session.phase_elapsed_seconds;
+    Some(session.phase_resumed_at + Duration::seconds(remaining as i64))
+}
+
+fn remaining_seconds(session: &PomodoroSession, now: DateTime<Utc>) -> i64 {
+    let remaining = (session.phase_duration_seconds - session.phase_elapsed_seconds) as i64;
+    match session.paused_at {
+        Some(_) => remaining,
+        None => (remaining - (now - session.phase_resumed_at).num_seconds()).max(0),
+    }
+}
+
+// Passe à `phase` à l'instant `at`, soit en cours, soit en attente de reprise
+fn enter_phase(session: &mut PomodoroSession, phase: &str, at: DateTime<Utc>, paused: bool) {
+    session.phase = phase.to_string();
+    session.phase_duration_seconds = phase_minutes(session, phase) * 60;
+    session.phase_elapsed_seconds = 0;
+    session.phase_resumed_at = at;
+    session.paused_at = if paused { Some(at) } else { None };
+}
+
+// Ouvre la time entry d'une phase de travail. Échoue en 409 si un autre timer tourne.
+fn start_work_entry(
+    conn: &mut PgConnection,
+    session: &PomodoroSession,
+    at: DateTime<Utc>,
+) -> Result<TimeEntry, ServiceError> {
+    diesel::insert_into(time_entries::table)
+        .values(&NewTimeEntry {
+            user_id: session.user_id,
+            task_id: Some(session.task_id),
+            project_id: None,
+            note: None,
+            start_time: at,
+            end_time: None,
+            duration_seconds: None,
+            is_pomodoro_session: Some(true),
+            pomodoro_session_id: Some(session.id),
+            billable: None,
+        })
+        .get_result::<TimeEntry>(conn)
+        .map_err(map_running_timer_violation)
+}
+
+// Ferme la time entry de la session si elle tourne encore (l'utilisateur a pu
+// l'arrêter lui-même via /time-entries/stop entre-temps).
+fn close_work_entry(
+    conn: &mut PgConnection,
+    session: &PomodoroSession,
+    at: DateTime<Utc>,
+) -> Result<(), ServiceError> {
+    let running = find_running_entry(conn, session.user_id)?;
+    if running.is_some_and(|entry| entry.pomodoro_session_id == Some(session.id)) {
+        stop_running_entry(conn, session.user_id, at)?;
+    }
+    Ok(())
+}
+
+// Phase terminée par `advance_phases`, avec son heure de fin exacte
+#[derive(Debug, PartialEq)]
+struct EndedPhase {
+    phase: String,
+    ended_at: DateTime<Utc>,
+}
+
+// Rattrape les transitions dues à `now`, sans toucher à la base :
+// - fin de travail : le cycle compte, et la pause (longue tous les
+//   `cycles_before_long_break` cycles) démarre aussitôt ;
+// - fin de pause : la phase de travail suivante attend une reprise explicite, pour ne
+//   pas enregistrer du temps de travail pendant que l'utilisateur est absent.
+fn advance_phases(session: &mut PomodoroSession, now: DateTime<Utc>) -> Vec<EndedPhase> {
+    let mut ended = Vec::new();
+    while let Some(ended_at) = phase_end(session).filter(|end| *end <= now) {
+        ended.push(EndedPhase {
+            phase: session.phase.clone(),
+            ended_at,
+        });
+        if session.phase == PHASE_WORK {
+            session.completed_work_cycles += 1;
+            let next_phase =
+                if session.completed_work_cycles % session.cycles_before_long_break == 0 {
+                    PHASE_LONG_BREAK
+                } else {
+                    PHASE_SHORT_BREAK
+                };
+            enter_phase(session, next_phase, ended_at, false);
+        } else {
+            enter_phase(session, PHASE_WORK, ended_at, true);
+        }
+    }
+    ended
+}
+
+// Fige la phase courante : le temps écoulé depuis la dernière reprise est cumulé
+fn freeze_phase(session: &mut PomodoroSession, now: DateTime<Utc>) {
+    if session.paused_at.is_none() {
+        session.phase_elapsed_seconds += (now - session.phase_resumed_at).num_seconds() as i32;
+        session.paused_at = Some(now);
+    }
+}
+
+fn pause_phase(session: &mut PomodoroSession, now: DateTime<Utc>) -> Result<(), ServiceError> {
+    if session.paused_at.is_some() {
+        return Err(ServiceError::Conflict(
+            "Pomodoro session is already paused".to_string(),
+        ));
+    }
+    freeze_phase(session, now);
+    session.interruption_count += 1;
+    Ok(())
+}
+
+fn resume_phase(session: &mut PomodoroSession, now: DateTime<Utc>) -> Result<(), ServiceError> {
+    if session.paused_at.is_none() {
+        return Err(ServiceError::Conflict(
+            "Pomodoro session is not paused".to_string(),
+        ));
+    }
+    session.phase_resumed_at = now;
+    session.paused_at = None;
+    Ok(())
+}
+
+// Un travail écourté ne compte pas comme cycle et mène à une pause courte ;
+// une pause écourtée relance directement le travail.
+fn skip_phase(session: &mut PomodoroSession, now: DateTime<Utc>) {
+    if session.phase == PHASE_WORK {
+        enter_phase(session, PHASE_SHORT_BREAK, now, false);
+    } else {
+        enter_phase(session, PHASE_WORK, now, false);
+    }
+}
+
+// Applique `advance_phases` : la time entry d'un travail terminé est fermée à
+// l'heure exacte de fin de la phase.
+fn sync_session(
+    conn: &mut PgConnection,
+    session: &mut PomodoroSession,
+    now: DateTime<Utc>,
+) -> Result<(), ServiceError> {
+    let ended = advance_phases(session, now);
+    for phase in &ended {
+        if phase.phase == PHASE_WORK {
+            close_work_entry(conn, session, phase.ended_at)?;
+        }
+    }
+    if !ended.is_empty() {
+        save_session(conn, session)?;
+    }
+    Ok(())
+}
+
+fn save_session(conn: &mut PgConnection, session: &PomodoroSession) -> Result<(), ServiceError> {
+    diesel::update(pomodoro_sessions::table.filter(pomodoro_sessions::id.eq(session.id)))
+        .set(&PomodoroSessionStateChangeset::from(session))
+        .execute(conn)?;
+    Ok(())
+}
+
+// Session active de l'utilisateur, verrouillée pour la durée de la transaction
+// (deux appareils qui agissent en même temps sont sérialisés).
+fn load_active_session(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+) -> Result<Option<PomodoroSession>, ServiceError> {
+    pomodoro_sessions::table
+        .filter(pomodoro_sessions::user_id.eq(user_uuid))
+        .filter(pomodoro_sessions::status.eq(STATUS_ACTIVE))
+        .select(PomodoroSession::as_select())
+        .for_update()
+        .first::<PomodoroSession>(conn)
+        .optional()
+        .map_err(ServiceError::from)
+}
+
+// Session active, synchronisée à `now` ; 404 si aucune
+fn load_synced_session(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    now: DateTime<Utc>,
+) -> Result<PomodoroSession, ServiceError> {
+    let mut session = load_active_session(conn, user_uuid)?
+        .ok_or_else(|| ServiceError::NotFound("No active pomodoro session".to_string()))?;
+    sync_session(conn, &mut session, now)?;
+    Ok(session)
+}
+
+fn build_state(
+    conn: &mut PgConnection,
+    session: PomodoroSession,
+    now: DateTime<Utc>,
+) -> Result<PomodoroStateResponse, ServiceError> {
+    let current_time_entry_id = find_running_entry(conn, session.user_id)?
+        .filter(|entry| entry.pomodoro_session_id == Some(session.id))
+        .map(|entry| entry.id);
+    Ok(PomodoroStateResponse {
+        is_paused: session.paused_at.is_some(),
+        remaining_seconds: remaining_seconds(&session, now),
+        phase_ends_at: phase_end(&session),
+        current_time_entry_id,
+        session,
+    })
+}
+
+fn validate_start_payload(payload: &StartPomodoroPayload) -> Result<(), ServiceError> {
+    let overrides = [
+        ("work_minutes", payload.work_minutes),
+        ("short_break_minutes", payload.short_break_minutes),
+        ("long_break_minutes", payload.long_break_minutes),
+        ("cycles_before_long_break", payload.cycles_before_long_break),
+    ];
+    for (field, value) in overrides {
+        if value.is_some_and(|v| v <= 0) {
+            return Err(ServiceError::BadRequest(format!(
+                "{} must be greater than 0",
+                field
+            )));
+        }
+    }
+    Ok(())
+}
+
+// === POST /pomodoro/start ===
+#[post("/start")]
+pub async fn start_pomodoro_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<StartPomodoroPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let payload = payload.into_inner();
+    log::info!(
+        "User {} starting pomodoro session with payload: {:?}",
+        user_uuid,
+        payload
+    );
+
+    validate_start_payload(&payload)?;
+
+    let state = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_task_owned(conn, user_uuid, payload.task_id)?;
+
+            if let Some(active) = load_active_session(conn, user_uuid)? {
+                return Err(ServiceError::Conflict(format!(
+                    "A pomodoro session is already active ({}). Stop it before starting a new one.",
+                    active.id
+                )));
+            }
+
+            let settings = load_user_settings(conn, user_uuid)?;
+            let work_minutes = payload
+                .work_minutes
+                .unwrap_or(settings.pomodoro_work_minutes);
+            let now = Utc::now();
+            let new_session_data = NewPomodoroSession {
+                user_id: user_uuid,
+                task_id: payload.task_id,
+                phase: PHASE_WORK.to_string(),
+                work_minutes,
+                short_break_minutes: payload
+                    .short_break_minutes
+                    .unwrap_or(settings.pomodoro_short_break_minutes),
+                long_break_minutes: payload
+                    .long_break_minutes
+                    .unwrap_or(settings.pomodoro_long_break_minutes),
+                cycles_before_long_break: payload
+                    .cycles_before_long_break
+                    .unwrap_or(settings.pomodoro_cycles_before_long_break),
+                phase_duration_seconds: work_minutes * 60,
+                phase_resumed_at: now,
+            };
+
+            let session = diesel::insert_into(pomodoro_sessions::table)
+                .values(&new_session_data)
+                .get_result::<PomodoroSession>(conn)?;
+            start_work_entry(conn, &session, now)?;
+
+            build_state(conn, session, now)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (start_pomodoro): {:?}", e);
+        ServiceError::InternalServerError("Error processing start_pomodoro request".to_string())
+    })??;
+
+    log::info!("Pomodoro session {} started", state.session.id);
+    Ok(HttpResponse::Created().json(state))
+}
+
+// === GET /pomodoro/current ===
+// Renvoie l'état de la session active (phase, temps restant), ou `null`.
+#[get("/current")]
+pub async fn get_current_pomodoro_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!("User {} fetching current pomodoro session", user_uuid);
+
+    let state = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let now = Utc::now();
+            match load_active_session(conn, user_uuid)? {
+                Some(mut session) => {
+                    sync_session(conn, &mut session, now)?;
+                    build_state(conn, session, now).map(Some)
+                }
+                None => Ok(None),
+            }
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_current_pomodoro): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing get_current_pomodoro request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Ok().json(state))
+}
+
+// === POST /pomodoro/pause ===
+// Interruption : la phase est mise en pause et l'interruption enregistrée avec sa raison.
+#[post("/pause")]
+pub async fn pause_pomodoro_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: Option<web::Json<PausePomodoroPayload>>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let payload = payload.map(|p| p.into_inner()).unwrap_or_default();
+    log::info!(
+        "User {} pausing pomodoro session with payload: {:?}",
+        user_uuid,
+        payload
+    );
+
+    let state = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let now = Utc::now();
+            let mut session = load_synced_session(conn, user_uuid, now)?;
+            pause_phase(&mut session, now)?;
+
+            if session.phase == PHASE_WORK {
+                close_work_entry(conn, &session, now)?;
+            }
+            save_session(conn, &session)?;
+
+            diesel::insert_into(pomodoro_interruptions::table)
+                .values(&NewPomodoroInterruption {
+                    session_id: session.id,
+                    user_id: user_uuid,
+                    phase: session.phase.clone(),
+                    reason: payload.reason,
+                })
+                .execute(conn)?;
+
+            build_state(conn, session, now)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (pause_pomodoro): {:?}", e);
+        ServiceError::InternalServerError("Error processing pause_pomodoro request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(state))
+}
+
+// === POST /pomodoro/resume ===
+// Reprend une phase en pause, ou démarre la phase de travail qui suit une pause terminée.
+#[post("/resume")]
+pub async fn resume_pomodoro_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!("User {} resuming pomodoro session", user_uuid);
+
+    let state = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let now = Utc::now();
+            let mut session = load_synced_session(conn, user_uuid, now)?;
+            resume_phase(&mut session, now)?;
+
+            if session.phase == PHASE_WORK {
+                start_work_entry(conn, &session, now)?;
+            }
+            save_session(conn, &session)?;
+
+            build_state(conn, session, now)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (resume_pomodoro): {:?}", e);
+        ServiceError::InternalServerError("Error processing resume_pomodoro request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(state))
+}
+
+// === POST /pomodoro/skip ===
+// Termine la phase courante tout de suite (voir `skip_phase`).
+#[post("/skip")]
+pub async fn skip_pomodoro_phase_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!("User {} skipping pomodoro phase", user_uuid);
+
+    let state = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let now = Utc::now();
+            let mut session = load_synced_session(conn, user_uuid, now)?;
+
+            if session.phase == PHASE_WORK {
+                close_work_entry(conn, &session, now)?;
+            }
+            skip_phase(&mut session, now);
+            if session.phase == PHASE_WORK {
+                start_work_entry(conn, &session, now)?;
+            }
+            save_session(conn, &session)?;
+
+            build_state(conn, session, now)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (skip_pomodoro_phase): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing skip_pomodoro_phase request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Ok().json(state))
+}
+
+// === POST /pomodoro/stop ===
+// Termine la session ; la time entry de travail en cours est fermée maintenant.
+#[post("/stop")]
+pub async fn stop_pomodoro_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!("User {} stopping pomodoro session", user_uuid);
+
+    let state = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let now = Utc::now();
+            let mut session = load_synced_session(conn, user_uuid, now)?;
+
+            if session.phase == PHASE_WORK {
+                close_work_entry(conn, &session, now)?;
+            }
+            freeze_phase(&mut session, now);
+            session.status = STATUS_COMPLETED.to_string();
+            session.ended_at = Some(now);
+            save_session(conn, &session)?;
+
+            build_state(conn, session, now)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (stop_pomodoro): {:?}", e);
+        ServiceError::InternalServerError("Error processing stop_pomodoro request".to_string())
+    })??;
+
+    log::info!("Pomodoro session {} stopped", state.session.id);
+    Ok(HttpResponse::Ok().json(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        "2026-10-05T09:00:00Z".parse::<DateTime<Utc>>().unwrap() + Duration::minutes(minutes)
+    }
+
+    // Session 25/5/15, pause longue tous les 4 cycles, travail démarré à `at(0)`
+    fn session() -> PomodoroSession {
+        PomodoroSession {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            status: STATUS_ACTIVE.to_string(),
+            phase: PHASE_WORK.to_string(),
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            cycles_before_long_break: 4,
+            completed_work_cycles: 0,
+            interruption_count: 0,
+            phase_duration_seconds: 25 * 60,
+            phase_elapsed_seconds: 0,
+            phase_resumed_at: at(0),
+            paused_at: None,
+            ended_at: None,
+            created_at: at(0).naive_utc(),
+            updated_at: at(0).naive_utc(),
+        }
+    }
+
+    fn ended(phase: &str, minutes: i64) -> EndedPhase {
+        EndedPhase {
+            phase: phase.to_string(),
+            ended_at: at(minutes),
+        }
+    }
+
+    #[test]
+    fn nothing_happens_before_the_phase_ends() {
+        let mut session = session();
+        assert!(advance_phases(&mut session, at(24)).is_empty());
+        assert_eq!(remaining_seconds(&session, at(24)), 60);
+        assert_eq!(phase_end(&session), Some(at(25)));
+    }
+
+    #[test]
+    fn catch_up_stops_at_the_work_phase_awaiting_resume() {
+        let mut session = session();
+        // Absent deux heures : travail terminé, pause terminée, puis plus rien
+        let ended_phases = advance_phases(&mut session, at(120));
+        assert_eq!(
+            ended_phases,
+            vec![ended(PHASE_WORK, 25), ended(PHASE_SHORT_BREAK, 30)]
+        );
+        assert_eq!(session.phase, PHASE_WORK);
+        assert_eq!(session.completed_work_cycles, 1);
+        assert_eq!(session.paused_at, Some(at(30)));
+        assert_eq!(session.phase_elapsed_seconds, 0);
+        assert_eq!(remaining_seconds(&session, at(120)), 25 * 60);
+        assert_eq!(phase_end(&session), None);
+    }
+
+    #[test]
+    fn break_starts_at_the_exact_end_of_work() {
+        let mut session = session();
+        assert_eq!(
+            advance_phases(&mut session, at(27)),
+            vec![ended(PHASE_WORK, 25)]
+        );
+        assert_eq!(session.phase, PHASE_SHORT_BREAK);
+        assert_eq!(session.phase_resumed_at, at(25));
+        assert_eq!(remaining_seconds(&session, at(27)), 3 * 60);
+    }
+
+    #[test]
+    fn long_break_every_n_cycles() {
+        let mut session = session();
+        let mut breaks = Vec::new();
+        for _ in 0..8 {
+            // Le travail suivant attend la reprise de l'utilisateur
+            let now = phase_end(&session).unwrap();
+            advance_phases(&mut session, now);
+            breaks.push(session.phase.clone());
+            let now = phase_end(&session).unwrap();
+            advance_phases(&mut session, now);
+            resume_phase(&mut session, now).unwrap();
+        }
+        let short = PHASE_SHORT_BREAK.to_string();
+        let long = PHASE_LONG_BREAK.to_string();
+        assert_eq!(
+            breaks,
+            vec![
+                short.clone(),
+                short.clone(),
+                short.clone(),
+                long.clone(),
+                short.clone(),
+                short.clone(),
+                short,
+                long
+            ]
+        );
+        assert_eq!(session.completed_work_cycles, 8);
+    }
+
+    #[test]
+    fn long_break_lasts_its_own_length() {
+        let mut session = session();
+        session.completed_work_cycles = 3;
+        advance_phases(&mut session, at(25));
+        assert_eq!(session.phase, PHASE_LONG_BREAK);
+        assert_eq!(session.phase_duration_seconds, 15 * 60);
+        assert_eq!(phase_end(&session), Some(at(40)));
+    }
+
+    #[test]
+    fn pause_and_resume_shift_the_phase_end() {
+        let mut session = session();
+        pause_phase(&mut session, at(10)).unwrap();
+        assert_eq!(session.interruption_count, 1);
+        assert_eq!(session.phase_elapsed_seconds, 10 * 60);
+        assert!(matches!(
+            pause_phase(&mut session, at(11)),
+            Err(ServiceError::Conflict(_))
+        ));
+        // Une pause ne se termine jamais d'elle-même
+        assert!(advance_phases(&mut session, at(300)).is_empty());
+        assert_eq!(remaining_seconds(&session, at(300)), 15 * 60);
+
+        resume_phase(&mut session, at(40)).unwrap();
+        assert!(matches!(
+            resume_phase(&mut session, at(41)),
+            Err(ServiceError::Conflict(_))
+        ));
+        assert_eq!(phase_end(&session), Some(at(55)));
+        assert!(advance_phases(&mut session, at(54)).is_empty());
+        assert_eq!(
+            advance_phases(&mut session, at(55)),
+            vec![ended(PHASE_WORK, 55)]
+        );
+    }
+
+    #[test]
+    fn skipping_work_does_not_count_a_cycle() {
+        let mut session = session();
+        session.completed_work_cycles = 3;
+        skip_phase(&mut session, at(10));
+        // Pas de pause longue : le cycle écourté ne compte pas
+        assert_eq!(session.phase, PHASE_SHORT_BREAK);
+        assert_eq!(session.completed_work_cycles, 3);
+        assert_eq!(session.paused_at, None);
+        assert_eq!(phase_end(&session), Some(at(15)));
+    }
+
+    #[test]
+    fn skipping_a_break_starts_work_right_away() {
+        let mut session = session();
+        advance_phases(&mut session, at(25));
+        skip_phase(&mut session, at(26));
+        assert_eq!(session.phase, PHASE_WORK);
+        assert_eq!(session.paused_at, None);
+        assert_eq!(phase_end(&session), Some(at(51)));
+    }
+
+    #[test]
+    fn freeze_keeps_the_elapsed_time_once() {
+        let mut session = session();
+        freeze_phase(&mut session, at(5));
+        freeze_phase(&mut session, at(9));
+        assert_eq!(session.phase_elapsed_seconds, 5 * 60);
+        assert_eq!(session.paused_at, Some(at(5)));
+        assert_eq!(session.interruption_count, 0);
+    }
+}
@@ -1,36 +1,87 @@
 // OptiTask/backend-api/src/project_handlers.rs
 use crate::auth_utils::AuthenticatedUser;
-use crate::db::DbPool;
+use crate::db::{run_as_user, DbPool};
 use crate::error_handler::ServiceError;
 use crate::models::{
     CreateProjectPayload, NewProject, Project, UpdateProjectChangeset, UpdateProjectPayload,
 };
+use crate::ownership::{ensure_client_owned, ensure_project_owned};
+use crate::rounding::{validate_increment_minutes, RoundingDirection, RoundingScope};
 use crate::schema::projects::{self, dsl::*};
+use crate::schema::tasks;
 use actix_web::{delete, get, post, put, web, HttpResponse};
 use chrono::Utc;
 use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use diesel::RunQueryDsl;
 use serde_json::json;
 use uuid::Uuid;
 
+// L'approbateur valide les feuilles de temps du projet : ce ne peut pas être son propriétaire
+fn ensure_approver_not_self(user_uuid: Uuid, approver: Option<Uuid>) -> Result<(), ServiceError> {
+    if approver == Some(user_uuid) {
+        return Err(ServiceError::BadRequest(
+            "A project's approver must be someone other than its owner.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn approver_error(error: DieselError, approver: Option<Uuid>) -> ServiceError {
+    match (error, approver) {
+        (
+            DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, info),
+            Some(approver),
+        ) if info.constraint_name() == Some("projects_approver_id_fkey") => {
+            ServiceError::BadRequest(format!("Approver {} is not a known user.", approver))
+        }
+        (error, _) => ServiceError::from(error),
+    }
+}
+
 #[post("")]
 pub async fn create_project_handler(
     pool: web::Data<DbPool>,
     authenticated_user: AuthenticatedUser,
     payload: web::Json<CreateProjectPayload>,
 ) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    if let Some(increment_minutes) = payload.rounding_increment_minutes {
+        validate_increment_minutes(increment_minutes)?;
+    }
     let new_project_data = NewProject {
-        user_id: authenticated_user.id,
+        user_id: user_uuid,
         name: payload.name.clone(),
         color: payload.color.clone(),
+        client_id: payload.client_id,
+        billable: payload.billable,
+        rounding_increment_minutes: payload.rounding_increment_minutes,
+        rounding_direction: payload
+            .rounding_direction
+            .as_deref()
+            .map(|direction| RoundingDirection::parse(Some(direction)))
+            .transpose()?
+            .map(|direction| direction.as_str().to_string()),
+        rounding_scope: payload
+            .rounding_scope
+            .as_deref()
+            .map(|scope| RoundingScope::parse(Some(scope)))
+            .transpose()?
+            .map(|scope| scope.as_str().to_string()),
+        approver_id: payload.approver_id,
     };
+    ensure_approver_not_self(user_uuid, new_project_data.approver_id)?;
 
     let project = web::block(move || {
-        let mut conn = pool.get()?;
-        diesel::insert_into(projects::table)
-            .values(&new_project_data)
-            .get_result::<Project>(&mut conn)
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            if let Some(client_to_attach_id) = new_project_data.client_id {
+                ensure_client_owned(conn, user_uuid, client_to_attach_id)?;
+            }
+            diesel::insert_into(projects::table)
+                .values(&new_project_data)
+                .get_result::<Project>(conn)
+                .map_err(|error| approver_error(error, new_project_data.approver_id))
+        })
     })
     .await
     .map_err(|blocking_error| {
@@ -49,12 +100,14 @@ pub async fn list_projects_handler(
     let user_uuid = authenticated_user.id;
 
     let project_list = web::block(move || {
-        let mut conn = pool.get()?;
-        projects
-            .filter(user_id.eq(user_uuid))
-            .select(Project::as_select())
-            .load::<Project>(&mut conn)
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            projects
+                .filter(user_id.eq(user_uuid))
+                .filter(deleted_at.is_null())
+                .select(Project::as_select())
+                .load::<Project>(conn)
+                .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -75,14 +128,16 @@ pub async fn get_project_handler(
     let project_to_find_id = project_id_path.into_inner();
 
     let project_option = web::block(move || {
-        let mut conn = pool.get()?;
-        projects
-            .filter(user_id.eq(user_uuid))
-            .filter(id.eq(project_to_find_id))
-            .select(Project::as_select())
-            .first::<Project>(&mut conn)
-            .optional()
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            projects
+                .filter(user_id.eq(user_uuid))
+                .filter(id.eq(project_to_find_id))
+                .filter(deleted_at.is_null())
+                .select(Project::as_select())
+                .first::<Project>(conn)
+                .optional()
+                .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -108,23 +163,48 @@ pub async fn update_project_handler(
 ) -> Result<HttpResponse, ServiceError> {
     let user_uuid = authenticated_user.id;
     let project_to_update_id = project_id_path.into_inner();
+    if let Some(increment_minutes) = payload.rounding_increment_minutes {
+        validate_increment_minutes(increment_minutes)?;
+    }
 
     let project_changes = UpdateProjectChangeset {
         name: payload.name.clone(),
         color: payload.color.clone(),
+        client_id: payload.client_id,
+        billable: payload.billable,
+        rounding_increment_minutes: payload.rounding_increment_minutes,
+        rounding_direction: payload
+            .rounding_direction
+            .as_deref()
+            .map(|direction| RoundingDirection::parse(Some(direction)))
+            .transpose()?
+            .map(|direction| direction.as_str().to_string()),
+        rounding_scope: payload
+            .rounding_scope
+            .as_deref()
+            .map(|scope| RoundingScope::parse(Some(scope)))
+            .transpose()?
+            .map(|scope| scope.as_str().to_string()),
+        approver_id: payload.approver_id,
         updated_at: Some(Utc::now().naive_utc()),
     };
+    ensure_approver_not_self(user_uuid, project_changes.approver_id.flatten())?;
 
     let updated_project = web::block(move || {
-        let mut conn = pool.get()?;
-        diesel::update(
-            projects
-                .filter(id.eq(project_to_update_id))
-                .filter(user_id.eq(user_uuid)),
-        )
-        .set(&project_changes)
-        .get_result::<Project>(&mut conn)
-        .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_project_owned(conn, user_uuid, project_to_update_id)?;
+            if let Some(Some(client_to_attach_id)) = project_changes.client_id {
+                ensure_client_owned(conn, user_uuid, client_to_attach_id)?;
+            }
+            diesel::update(
+                projects
+                    .filter(id.eq(project_to_update_id))
+                    .filter(user_id.eq(user_uuid)),
+            )
+            .set(&project_changes)
+            .get_result::<Project>(conn)
+            .map_err(|error| approver_error(error, project_changes.approver_id.flatten()))
+        })
     })
     .await
     .map_err(|e| {
@@ -135,6 +215,8 @@ pub async fn update_project_handler(
     Ok(HttpResponse::Ok().json(updated_project))
 }
 
+// Met le projet et ses tâches dans la corbeille, avec la même date de suppression :
+// la restauration du projet ramène exactement ces tâches.
 #[delete("/{project_id_path}")]
 pub async fn delete_project_handler(
     pool: web::Data<DbPool>,
@@ -145,14 +227,27 @@ pub async fn delete_project_handler(
     let project_to_delete_id = project_id_path.into_inner();
 
     let num_deleted = web::block(move || {
-        let mut conn = pool.get()?;
-        diesel::delete(
-            projects
-                .filter(user_id.eq(user_uuid))
-                .filter(id.eq(project_to_delete_id)),
-        )
-        .execute(&mut conn)
-        .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_project_owned(conn, user_uuid, project_to_delete_id)?;
+            let trashed_at = Utc::now().naive_utc();
+            let num_trashed = diesel::update(
+                projects
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq(project_to_delete_id))
+                    .filter(deleted_at.is_null()),
+            )
+            .set(deleted_at.eq(Some(trashed_at)))
+            .execute(conn)?;
+            diesel::update(
+                tasks::table
+                    .filter(tasks::user_id.eq(user_uuid))
+                    .filter(tasks::project_id.eq(project_to_delete_id))
+                    .filter(tasks::deleted_at.is_null()),
+            )
+            .set(tasks::deleted_at.eq(Some(trashed_at)))
+            .execute(conn)?;
+            Ok(num_trashed)
+        })
     })
     .await
     .map_err(|e| {
@@ -163,7 +258,7 @@ pub async fn delete_project_handler(
     if num_deleted > 0 {
         Ok(HttpResponse::Ok().json(json!({
             "status": "success",
-            "message": format!("Project with id {} deleted successfully", project_to_delete_id)
+            "message": format!("Project with id {} moved to trash", project_to_delete_id)
         })))
     } else {
         Err(ServiceError::NotFound(format!(
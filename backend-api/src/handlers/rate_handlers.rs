@@ -0,0 +1,201 @@
+// OptiTask/backend-api/src/handlers/rate_handlers.rs
+// Taux horaires. Un taux n'est jamais modifié : un changement de tarif est un
+// nouveau taux avec une date d'effet, pour que les périodes passées restent valorisées
+// au tarif de l'époque.
+use crate::auth_utils::AuthenticatedUser;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::models::{CreateHourlyRatePayload, HourlyRate, NewHourlyRate};
+use crate::ownership::{
+    ensure_client_owned, ensure_hourly_rate_owned, ensure_project_owned, ensure_task_owned,
+};
+use crate::schema::hourly_rates::{self, dsl::*};
+use actix_web::{delete, get, post, web, HttpResponse};
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::RunQueryDsl;
+use serde_json::json;
+use uuid::Uuid;
+
+// Filtres du listage : la cible du taux
+#[derive(serde::Deserialize, Debug)]
+pub struct ListHourlyRatesQuery {
+    pub client_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+}
+
+// === POST /rates ===
+#[post("")]
+pub async fn create_hourly_rate_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<CreateHourlyRatePayload>,
+) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let payload = payload.into_inner();
+    log::info!(
+        "User {} creating hourly rate with payload: {:?}",
+        user_uuid,
+        payload
+    );
+
+    let target_count = [
+        payload.client_id.is_some(),
+        payload.project_id.is_some(),
+        payload.task_id.is_some(),
+    ]
+    .iter()
+    .filter(|is_set| **is_set)
+    .count();
+    if target_count != 1 {
+        return Err(ServiceError::BadRequest(
+            "Exactly one of client_id, project_id or task_id is required.".to_string(),
+        ));
+    }
+    if payload.amount_cents < 0 {
+        return Err(ServiceError::BadRequest(
+            "amount_cents must be zero or positive.".to_string(),
+        ));
+    }
+    let normalized_currency = payload.currency.trim().to_uppercase();
+    if normalized_currency.len() != 3
+        || !normalized_currency.chars().all(|c| c.is_ascii_uppercase())
+    {
+        return Err(ServiceError::BadRequest(format!(
+            "Invalid currency: {}. Expected an ISO 4217 code such as EUR or USD.",
+            payload.currency
+        )));
+    }
+
+    let new_rate_data = NewHourlyRate {
+        user_id: user_uuid,
+        client_id: payload.client_id,
+        project_id: payload.project_id,
+        task_id: payload.task_id,
+        amount_cents: payload.amount_cents,
+        currency: normalized_currency,
+        effective_from: payload.effective_from,
+    };
+
+    let rate = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            if let Some(target_id) = new_rate_data.client_id {
+                ensure_client_owned(conn, user_uuid, target_id)?;
+            }
+            if let Some(target_id) = new_rate_data.project_id {
+                ensure_project_owned(conn, user_uuid, target_id)?;
+            }
+            if let Some(target_id) = new_rate_data.task_id {
+                ensure_task_owned(conn, user_uuid, target_id)?;
+            }
+
+            diesel::insert_into(hourly_rates::table)
+                .values(&new_rate_data)
+                .get_result::<HourlyRate>(conn)
+                .map_err(|error| match error {
+                    DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        ServiceError::Conflict(format!(
+                            "A rate already takes effect on {} for this target. Delete it first.",
+                            new_rate_data.effective_from
+                        ))
+                    }
+                    _ => ServiceError::from(error),
+                })
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (create_hourly_rate): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    Ok(HttpResponse::Created().json(rate))
+}
+
+// === GET /rates ===
+// Historique des taux, par cible puis par date d'effet décroissante
+#[get("")]
+pub async fn list_hourly_rates_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<ListHourlyRatesQuery>,
+) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let query_options = query_params.into_inner();
+
+    let rate_list = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let mut query = hourly_rates
+                .filter(user_id.eq(user_uuid))
+                .select(HourlyRate::as_select())
+                .into_boxed();
+            if let Some(target_id) = query_options.client_id {
+                query = query.filter(client_id.eq(target_id));
+            }
+            if let Some(target_id) = query_options.project_id {
+                query = query.filter(project_id.eq(target_id));
+            }
+            if let Some(target_id) = query_options.task_id {
+                query = query.filter(task_id.eq(target_id));
+            }
+            query
+                .order((
+                    client_id.asc(),
+                    project_id.asc(),
+                    task_id.asc(),
+                    effective_from.desc(),
+                ))
+                .load::<HourlyRate>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_hourly_rates): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(rate_list))
+}
+
+// === DELETE /rates/{rate_id_path} ===
+#[delete("/{rate_id_path}")]
+pub async fn delete_hourly_rate_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    rate_id_path: web::Path<Uuid>,
+) -> Result<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let rate_to_delete_id = rate_id_path.into_inner();
+
+    let num_deleted = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_hourly_rate_owned(conn, user_uuid, rate_to_delete_id)?;
+            diesel::delete(
+                hourly_rates
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq(rate_to_delete_id)),
+            )
+            .execute(conn)
+            .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (delete_hourly_rate): {:?}", e);
+        ServiceError::InternalServerError("Error processing request".to_string())
+    })??;
+
+    if num_deleted > 0 {
+        Ok(HttpResponse::Ok().json(json!({
+            "status": "success",
+            "message": format!("HourlyRate with id {} deleted successfully", rate_to_delete_id)
+        })))
+    } else {
+        Err(ServiceError::NotFound(format!(
+            "HourlyRate with id {} not found or not owned by user to delete",
+            rate_to_delete_id
+        )))
+    }
+}
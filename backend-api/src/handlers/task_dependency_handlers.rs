@@ -0,0 +1,101 @@
+// OptiTask/backend-api/src/handlers/task_dependency_handlers.rs
+// Dépendances entre tâches : "la tâche {task_id} est bloquée par blocked_by_task_id".
+use crate::auth_utils::AuthenticatedUser;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::ownership::ensure_task_owned;
+use crate::schema::task_dependencies;
+use crate::task_graph::add_dependency;
+use actix_web::{delete, post, web, HttpResponse, Result as ActixResult};
+use diesel::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+// DTO pour le payload de POST /tasks/{taskId}/dependencies
+#[derive(Deserialize, Debug)]
+pub struct AddTaskDependencyPayload {
+    pub blocked_by_task_id: Uuid,
+}
+
+// === POST /tasks/{task_id_path}/dependencies ===
+// 409 avec le cycle en "details" si la dépendance fermerait un cycle
+#[post("/{task_id_path}/dependencies")]
+pub async fn add_task_dependency_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    task_id_path: web::Path<Uuid>,
+    payload: web::Json<AddTaskDependencyPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let task_id_from_path = task_id_path.into_inner();
+    let user_uuid = authenticated_user.id;
+    let blocker_id = payload.blocked_by_task_id;
+
+    log::info!(
+        "User {} adding dependency: task {} blocked by {}",
+        user_uuid,
+        task_id_from_path,
+        blocker_id
+    );
+
+    let dependency = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_task_owned(conn, user_uuid, task_id_from_path)?;
+            ensure_task_owned(conn, user_uuid, blocker_id)?;
+            add_dependency(conn, user_uuid, task_id_from_path, blocker_id)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (add_task_dependency): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing add_task_dependency request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Created().json(dependency))
+}
+
+// === DELETE /tasks/{task_id_path}/dependencies/{blocked_by_path} ===
+#[delete("/{task_id_path}/dependencies/{blocked_by_path}")]
+pub async fn remove_task_dependency_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    path_params: web::Path<(Uuid, Uuid)>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let (task_id_from_path, blocker_id) = path_params.into_inner();
+    let user_uuid = authenticated_user.id;
+
+    let num_deleted = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_task_owned(conn, user_uuid, task_id_from_path)?;
+            diesel::delete(
+                task_dependencies::table
+                    .filter(task_dependencies::user_id.eq(user_uuid))
+                    .filter(task_dependencies::task_id.eq(task_id_from_path))
+                    .filter(task_dependencies::blocked_by_task_id.eq(blocker_id)),
+            )
+            .execute(conn)
+            .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (remove_task_dependency): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing remove_task_dependency request".to_string(),
+        )
+    })??;
+
+    if num_deleted > 0 {
+        Ok(HttpResponse::Ok().json(json!({
+            "status": "success",
+            "message": format!("Task {} is no longer blocked by task {}", task_id_from_path, blocker_id)
+        })))
+    } else {
+        Err(ServiceError::NotFound(format!(
+            "Task {} is not blocked by task {}",
+            task_id_from_path, blocker_id
+        )))
+    }
+}
@@ -1,18 +1,26 @@
 // OptiTask/backend-api/src/handlers/task_handlers.rs
 
 use crate::auth_utils::AuthenticatedUser;
-use crate::db::DbPool;
+use crate::db::{run_as_user, DbPool};
 use crate::error_handler::ServiceError;
 use crate::models::{
     CreateTaskPayload, Label, NewTask, Task, TaskApiResponse, TaskLabel, UpdateTaskChangeset,
-    UpdateTaskPayload,
+    UpdateTaskPayload, UpdateTaskResponse,
 };
+use crate::ownership::{ensure_project_owned, ensure_task_owned};
+use crate::recurrence::{normalize_rule, spawn_next_occurrence};
 use crate::schema::{
     labels, task_labels,
     tasks::{self, dsl::*},
 };
+use crate::task_graph::apply_dependencies;
+use crate::task_tree::{apply_rollups, descendant_ids, ensure_valid_parent};
+use crate::workflows::{
+    check_wip_limit, fit_tasks_to_workflow, load_workflow, normalize_status_key,
+};
 use actix_web::{delete, get, post, put, web, HttpResponse, Result as ActixResult};
 use chrono::Utc;
+use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use diesel::RunQueryDsl;
 use serde_json::json;
@@ -23,6 +31,65 @@ use uuid::Uuid;
 pub struct ListTasksQuery {
     pub project_id: Option<Uuid>,
     pub status: Option<String>,
+    // true : tâches non terminées sans bloqueur ouvert ; false : toutes les autres
+    // (bloquées ou terminées)
+    pub actionable: Option<bool>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct DeleteTaskQuery {
+    // Sort des sous-tâches : "trash" (corbeille avec la tâche) ou "promote" (remontent d'un niveau)
+    pub children: Option<String>,
+}
+
+// Labels, cumuls des sous-tâches et dépendances des tâches chargées
+pub fn to_api_responses(
+    conn: &mut PgConnection,
+    fetched_tasks: Vec<Task>,
+) -> Result<Vec<TaskApiResponse>, ServiceError> {
+    if fetched_tasks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let task_ids: Vec<Uuid> = fetched_tasks.iter().map(|t| t.id).collect();
+
+    let task_label_associations_with_labels: Vec<(TaskLabel, Label)> = task_labels::table
+        .filter(task_labels::task_id.eq_any(&task_ids))
+        .inner_join(labels::table.on(labels::id.eq(task_labels::label_id)))
+        .select((TaskLabel::as_select(), Label::as_select()))
+        .load::<(TaskLabel, Label)>(conn)?;
+
+    let mut labels_by_task_id: HashMap<Uuid, Vec<Label>> = HashMap::new();
+    for (task_label_assoc, label_data) in task_label_associations_with_labels {
+        labels_by_task_id
+            .entry(task_label_assoc.task_id)
+            .or_default()
+            .push(label_data);
+    }
+
+    let mut result_api_responses: Vec<TaskApiResponse> = fetched_tasks
+        .into_iter()
+        .map(|task_db| {
+            let mut api_response = TaskApiResponse::from(task_db.clone());
+            if let Some(associated_labels) = labels_by_task_id.get(&task_db.id) {
+                api_response.labels = associated_labels.clone();
+            }
+            api_response
+        })
+        .collect();
+    apply_rollups(conn, &mut result_api_responses)?;
+    apply_dependencies(conn, &mut result_api_responses)?;
+
+    Ok(result_api_responses)
+}
+
+pub fn to_api_response(
+    conn: &mut PgConnection,
+    task_db: Task,
+) -> Result<TaskApiResponse, ServiceError> {
+    to_api_responses(conn, vec![task_db])?
+        .pop()
+        .ok_or_else(|| ServiceError::InternalServerError("Task response missing".to_string()))
 }
 
 // === POST /tasks ===
@@ -34,23 +101,62 @@ pub async fn create_task_handler(
 ) -> ActixResult<HttpResponse, ServiceError> {
     log::info!("Create task payload received: {:?}", payload);
 
-    let new_task_data = NewTask {
-        user_id: authenticated_user.id,
+    let user_uuid = authenticated_user.id;
+    let project_to_attach_id = payload.project_id;
+    let requested_status = payload.status.clone();
+    let mut new_task_data = NewTask {
+        user_id: user_uuid,
         project_id: payload.project_id,
         title: payload.title.clone(),
         description: payload.description.clone(),
-        status: payload.status.clone().or_else(|| Some("todo".to_string())),
+        status: None, // Statut et catégorie résolus par le workflow du projet
+        status_category: String::new(),
         due_date: payload.due_date,
         order: payload.order,
+        billable: payload.billable,
+        parent_task_id: payload.parent_task_id,
+        recurrence_rule: payload
+            .recurrence_rule
+            .as_deref()
+            .map(normalize_rule)
+            .transpose()?,
+        recurrence_series_id: None,
+        recurrence_index: None,
     };
+    if new_task_data.recurrence_rule.is_some() && new_task_data.due_date.is_none() {
+        return Err(ServiceError::BadRequest(
+            "A recurring task needs a due_date.".to_string(),
+        ));
+    }
 
-    let created_task_db: Task = web::block(move || -> Result<Task, ServiceError> {
-        let mut conn = pool.get()?; // Propage ServiceError
-        diesel::insert_into(tasks::table)
-            .values(&new_task_data)
-            .get_result::<Task>(&mut conn)
-            .map_err(ServiceError::from) // Convertit DieselError en ServiceError
-                                         // Ou simplement .get_result::<Task>(&mut conn)? si From<DieselError> est bien géré
+    let api_response = web::block(move || -> Result<TaskApiResponse, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            if let Some(p_id) = project_to_attach_id {
+                ensure_project_owned(conn, user_uuid, p_id)?;
+            }
+            // Une sous-tâche hérite du projet de sa tâche parente
+            if let Some(parent_id) = new_task_data.parent_task_id {
+                let parent = ensure_valid_parent(conn, user_uuid, None, parent_id)?;
+                if project_to_attach_id.is_some() && project_to_attach_id != parent.project_id {
+                    return Err(ServiceError::BadRequest(
+                        "A subtask belongs to the project of its parent task.".to_string(),
+                    ));
+                }
+                new_task_data.project_id = parent.project_id;
+            }
+            let workflow = load_workflow(conn, user_uuid, new_task_data.project_id)?;
+            let initial_status = match &requested_status {
+                Some(requested) => workflow.resolve_status(requested)?,
+                None => workflow.initial_status()?,
+            };
+            new_task_data.status = Some(initial_status.key.clone());
+            new_task_data.status_category = initial_status.category.clone();
+
+            let created_task_db = diesel::insert_into(tasks::table)
+                .values(&new_task_data)
+                .get_result::<Task>(conn)?;
+            to_api_response(conn, created_task_db)
+        })
     })
     .await
     .map_err(|e| {
@@ -58,8 +164,6 @@ pub async fn create_task_handler(
         ServiceError::InternalServerError("Error processing create_task request".to_string())
     })??;
 
-    let api_response = TaskApiResponse::from(created_task_db);
-
     log::info!("Task created successfully: {:?}", api_response);
     Ok(HttpResponse::Created().json(api_response))
 }
@@ -82,55 +186,30 @@ pub async fn list_tasks_handler(
 
     let tasks_with_labels_response: Vec<TaskApiResponse> =
         web::block(move || -> Result<Vec<TaskApiResponse>, ServiceError> {
-            let mut conn = pool.get()?;
-
-            let mut query_builder = tasks
-                .filter(user_id.eq(user_uuid))
-                .order(task_order.asc().nulls_last())
-                .then_order_by(created_at.desc())
-                .select(Task::as_select())
-                .into_boxed();
-
-            if let Some(p_id) = query_options.project_id {
-                query_builder = query_builder.filter(project_id.eq(p_id));
-            }
-            if let Some(s) = query_options.status {
-                query_builder = query_builder.filter(status.eq(s));
-            }
-            let fetched_tasks: Vec<Task> = query_builder.load::<Task>(&mut conn)?;
-
-            if fetched_tasks.is_empty() {
-                return Ok(Vec::new());
-            }
-
-            let task_ids: Vec<Uuid> = fetched_tasks.iter().map(|t| t.id).collect();
-
-            let task_label_associations_with_labels: Vec<(TaskLabel, Label)> = task_labels::table
-                .filter(task_labels::task_id.eq_any(&task_ids))
-                .inner_join(labels::table.on(labels::id.eq(task_labels::label_id)))
-                .select((TaskLabel::as_select(), Label::as_select()))
-                .load::<(TaskLabel, Label)>(&mut conn)?;
-
-            let mut labels_by_task_id: HashMap<Uuid, Vec<Label>> = HashMap::new();
-            for (task_label_assoc, label_data) in task_label_associations_with_labels {
-                labels_by_task_id
-                    .entry(task_label_assoc.task_id)
-                    .or_default()
-                    .push(label_data);
-            }
-
-            let result_api_responses: Vec<TaskApiResponse> = fetched_tasks
-                .into_iter()
-                .map(|task_db| {
-                    let mut api_response = TaskApiResponse::from(task_db.clone());
-                    if let Some(associated_labels) = labels_by_task_id.get(&task_db.id) {
-                        api_response.labels = associated_labels.clone();
-                    }
-                    api_response
-                })
-                .collect();
-
-            Ok(result_api_responses)
+            run_as_user(&pool, user_uuid, |conn| {
+                let mut query_builder = tasks
+                    .filter(user_id.eq(user_uuid))
+                    .filter(deleted_at.is_null())
+                    .order(task_order.asc().nulls_last())
+                    .then_order_by(created_at.desc())
+                    .select(Task::as_select())
+                    .into_boxed();
+
+                if let Some(p_id) = query_options.project_id {
+                    query_builder = query_builder.filter(project_id.eq(p_id));
+                }
+                if let Some(s) = query_options.status {
+                    query_builder = query_builder.filter(status.eq(normalize_status_key(&s)));
+                }
+                let fetched_tasks: Vec<Task> = query_builder.load::<Task>(conn)?;
+                let mut api_responses = to_api_responses(conn, fetched_tasks)?;
+                if let Some(actionable) = query_options.actionable {
+                    api_responses.retain(|task| {
+                        (!task.is_blocked && task.status_category != "done") == actionable
+                    });
+                }
+                Ok(api_responses)
+            })
         })
         .await
         .map_err(|e| {
@@ -155,29 +234,20 @@ pub async fn get_task_handler(
 
     let task_api_response_option: Option<TaskApiResponse> =
         web::block(move || -> Result<Option<TaskApiResponse>, ServiceError> {
-            let mut conn = pool.get()?;
-
-            let task_db_option: Option<Task> = tasks
-                .filter(user_id.eq(user_uuid))
-                .filter(id.eq(task_to_find_id))
-                .select(Task::as_select())
-                .first::<Task>(&mut conn)
-                .optional()?; // Gère Err(NotFound) en Ok(None), propage les autres erreurs
-
-            match task_db_option {
-                Some(task_db) => {
-                    let associated_labels: Vec<Label> = task_labels::table
-                        .filter(task_labels::task_id.eq(task_db.id))
-                        .inner_join(labels::table.on(labels::id.eq(task_labels::label_id)))
-                        .select(Label::as_select())
-                        .load::<Label>(&mut conn)?;
-
-                    let mut api_response = TaskApiResponse::from(task_db);
-                    api_response.labels = associated_labels;
-                    Ok(Some(api_response))
+            run_as_user(&pool, user_uuid, |conn| {
+                let task_db_option: Option<Task> = tasks
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq(task_to_find_id))
+                    .filter(deleted_at.is_null())
+                    .select(Task::as_select())
+                    .first::<Task>(conn)
+                    .optional()?; // Gère Err(NotFound) en Ok(None), propage les autres erreurs
+
+                match task_db_option {
+                    Some(task_db) => Ok(Some(to_api_response(conn, task_db)?)),
+                    None => Ok(None),
                 }
-                None => Ok(None),
-            }
+            })
         })
         .await
         .map_err(|e| {
@@ -211,13 +281,22 @@ pub async fn update_task_handler(
         payload
     );
 
-    let task_changes = UpdateTaskChangeset {
+    let project_to_attach_id = payload.project_id.flatten();
+    let mut task_changes = UpdateTaskChangeset {
         project_id: payload.project_id.clone(),
+        parent_task_id: payload.parent_task_id,
         title: payload.title.clone(),
         description: payload.description.clone(),
         status: payload.status.clone(),
+        status_category: None,
         due_date: payload.due_date.clone(),
         order: payload.order.clone(),
+        billable: payload.billable,
+        recurrence_rule: match &payload.recurrence_rule {
+            Some(Some(rule)) => Some(Some(normalize_rule(rule)?)),
+            Some(None) => Some(None),
+            None => None,
+        },
         updated_at: Some(Utc::now().naive_utc()),
     };
 
@@ -227,27 +306,143 @@ pub async fn update_task_handler(
         task_changes
     );
 
-    let updated_task_api_response: TaskApiResponse =
-        web::block(move || -> Result<TaskApiResponse, ServiceError> {
-            let mut conn = pool.get()?;
-
-            let updated_task_db: Task = diesel::update(
-                tasks
+    let updated_task_api_response: UpdateTaskResponse =
+        web::block(move || -> Result<UpdateTaskResponse, ServiceError> {
+            run_as_user(&pool, user_uuid, |conn| {
+                ensure_task_owned(conn, user_uuid, task_to_update_id)?;
+                if let Some(p_id) = project_to_attach_id {
+                    ensure_project_owned(conn, user_uuid, p_id)?;
+                }
+                let current_task: Task = tasks
                     .filter(id.eq(task_to_update_id))
-                    .filter(user_id.eq(user_uuid)),
-            )
-            .set(&task_changes)
-            .get_result::<Task>(&mut conn)?; // Gère DieselError::NotFound via From
-
-            let associated_labels: Vec<Label> = task_labels::table
-                .filter(task_labels::task_id.eq(updated_task_db.id))
-                .inner_join(labels::table.on(labels::id.eq(task_labels::label_id)))
-                .select(Label::as_select())
-                .load::<Label>(&mut conn)?;
-
-            let mut api_response = TaskApiResponse::from(updated_task_db);
-            api_response.labels = associated_labels;
-            Ok(api_response)
+                    .select(Task::as_select())
+                    .first::<Task>(conn)?;
+
+                // Une sous-tâche reste dans le projet de sa tâche parente : déplacer la tâche
+                // sous un parent lui donne le projet de ce parent
+                let parent_project_id = match task_changes.parent_task_id {
+                    Some(Some(new_parent_id)) => Some(
+                        ensure_valid_parent(conn, user_uuid, Some(task_to_update_id), new_parent_id)?
+                            .project_id,
+                    ),
+                    Some(None) => None,
+                    None => match current_task.parent_task_id {
+                        Some(current_parent_id) => Some(
+                            tasks
+                                .filter(id.eq(current_parent_id))
+                                .select(project_id)
+                                .first::<Option<Uuid>>(conn)?,
+                        ),
+                        None => None,
+                    },
+                };
+                if let Some(parent_project_id) = parent_project_id {
+                    if let Some(requested_project_id) = task_changes.project_id {
+                        if requested_project_id != parent_project_id {
+                            return Err(ServiceError::BadRequest(
+                                "A subtask belongs to the project of its parent task; move it out of its parent first.".to_string(),
+                            ));
+                        }
+                    }
+                    task_changes.project_id = Some(parent_project_id);
+                }
+
+                // Statut validé par le workflow du projet final de la tâche ; les transitions
+                // ne s'appliquent qu'à l'intérieur d'un même projet
+                let target_project_id = task_changes.project_id.unwrap_or(current_task.project_id);
+                let workflow = load_workflow(conn, user_uuid, target_project_id)?;
+                let next_status = match task_changes.status.clone() {
+                    Some(requested) => {
+                        let next_status = workflow.resolve_status(&requested)?;
+                        if target_project_id == current_task.project_id {
+                            workflow.ensure_transition(&current_task.status, &next_status.key)?;
+                        }
+                        Some(next_status)
+                    }
+                    // Statut absent du workflow du nouveau projet : premier de même catégorie
+                    None if workflow.status(&current_task.status).is_none() => {
+                        Some(workflow.fallback_status(&current_task.status_category)?)
+                    }
+                    None => None,
+                };
+                if let Some(next_status) = next_status {
+                    task_changes.status = Some(next_status.key.clone());
+                    task_changes.status_category = Some(next_status.category.clone());
+                }
+
+                // Entrer dans une colonne pleine du tableau du projet : refus en politique
+                // 'reject', avertissement renvoyé avec la tâche en 'warn'
+                let mut warnings = Vec::new();
+                if let Some(board_project_id) = target_project_id {
+                    let final_status = task_changes
+                        .status
+                        .clone()
+                        .unwrap_or_else(|| current_task.status.clone());
+                    // Colonne actuelle de la tâche sur ce tableau (statut hors workflow compris)
+                    let current_column = if target_project_id == current_task.project_id {
+                        Some(
+                            workflow
+                                .column_for(&current_task.status, &current_task.status_category)?
+                                .key
+                                .clone(),
+                        )
+                    } else {
+                        None
+                    };
+                    if let Some(column) = workflow.status(&final_status) {
+                        if current_column.as_deref() != Some(column.key.as_str()) {
+                            warnings.extend(check_wip_limit(
+                                conn,
+                                board_project_id,
+                                &workflow,
+                                column,
+                                task_to_update_id,
+                            )?);
+                        }
+                    }
+                }
+
+                let updated_task_db: Task = diesel::update(
+                    tasks
+                        .filter(id.eq(task_to_update_id))
+                        .filter(user_id.eq(user_uuid)),
+                )
+                .set(&task_changes)
+                .get_result::<Task>(conn)?; // Gère DieselError::NotFound via From
+                if updated_task_db.recurrence_rule.is_some() && updated_task_db.due_date.is_none() {
+                    return Err(ServiceError::BadRequest(
+                        "A recurring task needs a due_date.".to_string(),
+                    ));
+                }
+
+                // Les sous-tâches suivent la tâche dans son nouveau projet
+                if updated_task_db.project_id != current_task.project_id {
+                    let subtask_ids = descendant_ids(conn, task_to_update_id, false)?;
+                    diesel::update(tasks.filter(id.eq_any(&subtask_ids)))
+                        .set(project_id.eq(updated_task_db.project_id))
+                        .execute(conn)?;
+                    fit_tasks_to_workflow(conn, &workflow, &subtask_ids)?;
+                }
+
+                // Terminer une occurrence récurrente crée la suivante
+                if current_task.status_category != "done" && updated_task_db.status_category == "done" {
+                    if let Some(next_occurrence) =
+                        spawn_next_occurrence(conn, &updated_task_db, None)?
+                    {
+                        log::info!(
+                            "Task {} completed, next occurrence {} due {:?}",
+                            task_to_update_id,
+                            next_occurrence.id,
+                            next_occurrence.due_date
+                        );
+                    }
+                }
+
+                Ok(UpdateTaskResponse {
+                    task: to_api_response(conn, updated_task_db)?,
+                    warnings,
+                })
+            })
         })
         .await
         .map_err(|e| {
@@ -259,26 +454,75 @@ pub async fn update_task_handler(
 }
 
 // === DELETE /tasks/{task_id_path} ===
+// Met la tâche dans la corbeille : ses time entries restent dans les analytics,
+// la restauration et la purge passent par /trash.
+// Une tâche qui a des sous-tâches exige `?children=trash` ou `?children=promote`.
 #[delete("/{task_id_path}")]
 pub async fn delete_task_handler(
     pool: web::Data<DbPool>,
     authenticated_user: AuthenticatedUser,
     task_id_path: web::Path<Uuid>,
+    query_params: web::Query<DeleteTaskQuery>,
 ) -> ActixResult<HttpResponse, ServiceError> {
     let user_uuid = authenticated_user.id;
     let task_to_delete_id = task_id_path.into_inner();
+    let children_mode = query_params.into_inner().children;
+    if let Some(mode) = children_mode.as_deref() {
+        if mode != "trash" && mode != "promote" {
+            return Err(ServiceError::BadRequest(format!(
+                "Invalid children mode: {}. Supported: trash, promote.",
+                mode
+            )));
+        }
+    }
 
     log::info!("Deleting task {} for user {}", task_to_delete_id, user_uuid);
 
     let num_deleted = web::block(move || -> Result<usize, ServiceError> {
-        let mut conn = pool.get()?;
-        diesel::delete(
-            tasks
-                .filter(user_id.eq(user_uuid))
-                .filter(id.eq(task_to_delete_id)),
-        )
-        .execute(&mut conn)
-        .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_task_owned(conn, user_uuid, task_to_delete_id)?;
+            let trashed_at = Utc::now().naive_utc();
+            let subtask_ids = descendant_ids(conn, task_to_delete_id, false)?;
+            if !subtask_ids.is_empty() {
+                match children_mode.as_deref() {
+                    // Même date de suppression : la restauration de la tâche les ramène
+                    Some("trash") => {
+                        diesel::update(tasks.filter(id.eq_any(&subtask_ids)))
+                            .set(deleted_at.eq(Some(trashed_at)))
+                            .execute(conn)?;
+                    }
+                    Some("promote") => {
+                        let grandparent_id = tasks
+                            .filter(id.eq(task_to_delete_id))
+                            .select(parent_task_id)
+                            .first::<Option<Uuid>>(conn)?;
+                        diesel::update(
+                            tasks
+                                .filter(parent_task_id.eq(task_to_delete_id))
+                                .filter(deleted_at.is_null()),
+                        )
+                        .set(parent_task_id.eq(grandparent_id))
+                        .execute(conn)?;
+                    }
+                    _ => {
+                        return Err(ServiceError::Conflict(format!(
+                            "Task {} has {} subtasks; pass children=trash to delete them too or children=promote to move them up one level.",
+                            task_to_delete_id,
+                            subtask_ids.len()
+                        )))
+                    }
+                }
+            }
+            diesel::update(
+                tasks
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq(task_to_delete_id))
+                    .filter(deleted_at.is_null()),
+            )
+            .set(deleted_at.eq(Some(trashed_at)))
+            .execute(conn)
+            .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -289,7 +533,7 @@ pub async fn delete_task_handler(
     if num_deleted > 0 {
         Ok(HttpResponse::Ok().json(json!({
             "status": "success",
-            "message": format!("Task with id {} deleted successfully", task_to_delete_id)
+            "message": format!("Task with id {} moved to trash", task_to_delete_id)
         })))
     } else {
         Err(ServiceError::NotFound(format!(
@@ -298,3 +542,137 @@ pub async fn delete_task_handler(
         )))
     }
 }
+
+// === GET /tasks/{task_id_path}/subtasks ===
+// Sous-tâches directes, avec leurs labels et leurs propres cumuls
+#[get("/{task_id_path}/subtasks")]
+pub async fn list_subtasks_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    task_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let parent_id = task_id_path.into_inner();
+
+    let subtasks = web::block(move || -> Result<Vec<TaskApiResponse>, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_task_owned(conn, user_uuid, parent_id)?;
+            let fetched_tasks: Vec<Task> = tasks
+                .filter(user_id.eq(user_uuid))
+                .filter(parent_task_id.eq(parent_id))
+                .filter(deleted_at.is_null())
+                .order(task_order.asc().nulls_last())
+                .then_order_by(created_at.asc())
+                .select(Task::as_select())
+                .load::<Task>(conn)?;
+            to_api_responses(conn, fetched_tasks)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_subtasks): {:?}", e);
+        ServiceError::InternalServerError("Error processing list_subtasks request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(subtasks))
+}
+
+// === GET /tasks/{task_id_path}/occurrences ===
+// Toutes les occurrences de la série de la tâche récurrente (terminées comprises),
+// de la première à la plus récente
+#[get("/{task_id_path}/occurrences")]
+pub async fn list_occurrences_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    task_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let occurrence_id = task_id_path.into_inner();
+
+    let occurrences = web::block(move || -> Result<Vec<TaskApiResponse>, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_task_owned(conn, user_uuid, occurrence_id)?;
+            let series_id: Uuid = tasks
+                .filter(id.eq(occurrence_id))
+                .select(recurrence_series_id)
+                .first::<Option<Uuid>>(conn)?
+                .unwrap_or(occurrence_id);
+            let fetched_tasks: Vec<Task> = tasks
+                .filter(user_id.eq(user_uuid))
+                .filter(id.eq(series_id).or(recurrence_series_id.eq(series_id)))
+                .filter(deleted_at.is_null())
+                .order(recurrence_index.asc())
+                .select(Task::as_select())
+                .load::<Task>(conn)?;
+            to_api_responses(conn, fetched_tasks)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_occurrences): {:?}", e);
+        ServiceError::InternalServerError("Error processing list_occurrences request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(occurrences))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configure_routes;
+    use crate::schema::{task_dependencies, tasks};
+    use crate::test_support::{seed_task, test_auth_config, test_pool};
+    use actix_web::{test, web, App};
+    use diesel::prelude::*;
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    // actionable=false renvoie le complément exact de actionable=true
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn actionable_filter_splits_tasks_in_two() {
+        let pool = test_pool();
+        let owner = Uuid::new_v4();
+        let (open, blocked, done) = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            let open = seed_task(&mut conn, owner, None);
+            let blocked = seed_task(&mut conn, owner, None);
+            let done = seed_task(&mut conn, owner, None);
+            diesel::update(tasks::table.find(done))
+                .set((tasks::status.eq("done"), tasks::status_category.eq("done")))
+                .execute(&mut conn)
+                .expect("Failed to complete task");
+            diesel::insert_into(task_dependencies::table)
+                .values((
+                    task_dependencies::task_id.eq(blocked),
+                    task_dependencies::blocked_by_task_id.eq(open),
+                    task_dependencies::user_id.eq(owner),
+                ))
+                .execute(&mut conn)
+                .expect("Failed to seed dependency");
+            (open, blocked, done)
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_auth_config()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        for (actionable, expected) in [("true", vec![open]), ("false", vec![blocked, done])] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/tasks?actionable={}", actionable))
+                .insert_header(("X-User-Id", owner.to_string()))
+                .to_request();
+            let response: Vec<Value> = test::call_and_read_body_json(&app, request).await;
+            let mut ids: Vec<Uuid> = response
+                .iter()
+                .map(|task| task["id"].as_str().unwrap().parse().unwrap())
+                .collect();
+            ids.sort();
+            let mut expected = expected;
+            expected.sort();
+            assert_eq!(ids, expected, "actionable={}", actionable);
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use crate::auth_utils::AuthenticatedUser;
-use crate::db::DbPool;
+use crate::db::{run_as_user, DbPool};
 use crate::error_handler::ServiceError;
 use crate::models::{Label, NewTaskLabelAssociation}; // TaskLabel pour la suppression, Label pour le listage
-use crate::schema::{labels, task_labels, tasks}; // tasks est nécessaire pour vérifier la propriété de la tâche
+use crate::ownership::{ensure_label_owned, ensure_task_owned};
+use crate::schema::{labels, task_labels};
 use actix_web::{delete, get, post, web, HttpResponse, Result as ActixResult};
 use diesel::prelude::*;
 use diesel::RunQueryDsl; // Pour .execute()
@@ -37,45 +38,25 @@ pub async fn add_label_to_task_handler(
     );
 
     let _association = web::block(move || {
-        let mut conn = pool.get()?;
-
-        // 1. Vérifier que la tâche appartient à l'utilisateur
-        tasks::table
-            .filter(tasks::id.eq(task_id_from_path))
-            .filter(tasks::user_id.eq(user_uuid))
-            .select(tasks::id)
-            .first::<Uuid>(&mut conn)
-            .map_err(|_| {
-                ServiceError::NotFound(format!(
-                    "Task with id {} not found or not owned by user",
-                    task_id_from_path
-                ))
-            })?;
-
-        // 2. Vérifier que le label appartient à l'utilisateur (ou est public, si vous avez cette notion)
-        labels::table
-            .filter(labels::id.eq(label_to_add_id))
-            .filter(labels::user_id.eq(user_uuid)) // Assumant que les labels sont aussi par utilisateur
-            .select(labels::id)
-            .first::<Uuid>(&mut conn)
-            .map_err(|_| {
-                ServiceError::NotFound(format!(
-                    "Label with id {} not found or not owned by user",
-                    label_to_add_id
-                ))
-            })?;
-
-        // 3. Créer l'association
-        let new_association = NewTaskLabelAssociation {
-            task_id: task_id_from_path,
-            label_id: label_to_add_id,
-        };
-
-        diesel::insert_into(task_labels::table)
-            .values(&new_association)
-            // .get_result::<TaskLabel>(&mut conn) // Peut retourner l'association si besoin
-            .execute(&mut conn) // Ou juste exécuter pour un statut 201/204
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            // 1. Vérifier que la tâche appartient à l'utilisateur
+            ensure_task_owned(conn, user_uuid, task_id_from_path)?;
+
+            // 2. Vérifier que le label appartient à l'utilisateur (ou est public, si vous avez cette notion)
+            ensure_label_owned(conn, user_uuid, label_to_add_id)?;
+
+            // 3. Créer l'association
+            let new_association = NewTaskLabelAssociation {
+                task_id: task_id_from_path,
+                label_id: label_to_add_id,
+            };
+
+            diesel::insert_into(task_labels::table)
+                .values(&new_association)
+                // .get_result::<TaskLabel>(conn) // Peut retourner l'association si besoin
+                .execute(conn) // Ou juste exécuter pour un statut 201/204
+                .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e: actix_web::error::BlockingError| {
@@ -112,29 +93,19 @@ pub async fn list_labels_for_task_handler(
     );
 
     let labels_for_task = web::block(move || {
-        let mut conn = pool.get()?;
-
-        // 1. Vérifier que la tâche appartient à l'utilisateur
-        tasks::table
-            .filter(tasks::id.eq(task_id_from_path))
-            .filter(tasks::user_id.eq(user_uuid))
-            .select(tasks::id)
-            .first::<Uuid>(&mut conn)
-            .map_err(|_| {
-                ServiceError::NotFound(format!(
-                    "Task with id {} not found or not owned by user",
-                    task_id_from_path
-                ))
-            })?;
-
-        // 2. Récupérer les labels associés
-        // Utilise une jointure implicite ou explicite
-        task_labels::table
-            .filter(task_labels::task_id.eq(task_id_from_path))
-            .inner_join(labels::table.on(labels::id.eq(task_labels::label_id)))
-            .select(Label::as_select()) // Sélectionne tous les champs du Label
-            .load::<Label>(&mut conn)
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            // 1. Vérifier que la tâche appartient à l'utilisateur
+            ensure_task_owned(conn, user_uuid, task_id_from_path)?;
+
+            // 2. Récupérer les labels associés
+            // Utilise une jointure implicite ou explicite
+            task_labels::table
+                .filter(task_labels::task_id.eq(task_id_from_path))
+                .inner_join(labels::table.on(labels::id.eq(task_labels::label_id)))
+                .select(Label::as_select()) // Sélectionne tous les champs du Label
+                .load::<Label>(conn)
+                .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e: actix_web::error::BlockingError| {
@@ -166,31 +137,22 @@ pub async fn remove_label_from_task_handler(
     );
 
     let num_deleted = web::block(move || {
-        let mut conn = pool.get()?;
-
-        // 1. Vérifier que la tâche appartient à l'utilisateur (important pour la sécurité)
-        // Ceci empêche un utilisateur de manipuler les labels d'une tâche qui ne lui appartient pas
-        // même s'il connaît l'ID de la tâche et du label.
-        let _task_owner_check = tasks::table
-            .filter(tasks::id.eq(task_id_from_path))
-            .filter(tasks::user_id.eq(user_uuid))
-            .select(tasks::id)
-            .first::<Uuid>(&mut conn)
-            .map_err(|_| {
-                ServiceError::NotFound(format!(
-                    "Task with id {} not found or not owned by user",
-                    task_id_from_path
-                ))
-            })?;
-
-        // 2. Supprimer l'association
-        diesel::delete(
-            task_labels::table
-                .filter(task_labels::task_id.eq(task_id_from_path))
-                .filter(task_labels::label_id.eq(label_id_to_remove)),
-        )
-        .execute(&mut conn)
-        .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            // 1. Vérifier que la tâche appartient à l'utilisateur (important pour la sécurité)
+            // Ceci empêche un utilisateur de manipuler les labels d'une tâche qui ne lui appartient pas
+            // même s'il connaît l'ID de la tâche et du label.
+            ensure_task_owned(conn, user_uuid, task_id_from_path)?;
+            ensure_label_owned(conn, user_uuid, label_id_to_remove)?;
+
+            // 2. Supprimer l'association
+            diesel::delete(
+                task_labels::table
+                    .filter(task_labels::task_id.eq(task_id_from_path))
+                    .filter(task_labels::label_id.eq(label_id_to_remove)),
+            )
+            .execute(conn)
+            .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e: actix_web::error::BlockingError| {
@@ -1,29 +1,600 @@
 use crate::auth_utils::AuthenticatedUser;
-use crate::db::DbPool;
+use crate::db::{run_as_user, DbPool};
 use crate::error_handler::ServiceError;
 use crate::models::{
-    CreateTimeEntryPayload, NewTimeEntry, TimeEntry, UpdateTimeEntryChangeset,
+    CreateTimeEntryPayload, MergeTimeEntriesPayload, NewTimeEntry, SplitTimeEntryPayload,
+    StartTimerPayload, TimeEntry, TimeEntryOverlap, UpdateTimeEntryChangeset,
     UpdateTimeEntryPayload,
 };
-use crate::schema::{
-    tasks,                        // Importez tasks pour la vérification de propriété
-    time_entries::{self, dsl::*}, // dsl::* pour les filtres etc.
+use crate::overlaps::{
+    duration_between, find_overlapping_entries, resolve_overlaps, OverlapResolution,
 };
+use crate::ownership::{
+    ensure_task_owned, ensure_time_entry_owned, ensure_time_entry_target_owned,
+};
+use crate::schema::tasks;
+use crate::schema::time_entries::{self, dsl::*}; // dsl::* pour les filtres etc.
+use crate::timesheets::ensure_week_not_approved;
 use actix_web::{delete, get, post, put, web, HttpResponse, Result as ActixResult};
-use chrono::{NaiveDateTime, Utc}; // Utc pour Utc::now()
+use chrono::{DateTime, NaiveDateTime, Utc}; // Utc pour Utc::now()
+use diesel::pg::PgConnection;
 use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use diesel::RunQueryDsl; // Pour .execute() etc.
 use serde_json::json; // Pour les réponses JSON personnalisées
+use std::collections::HashMap;
 use uuid::Uuid;
 
 // DTO pour les query parameters du listage
 #[derive(serde::Deserialize, Debug)]
 pub struct ListTimeEntriesQuery {
     pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>, // Entrées du projet et de ses tâches
+    pub q: Option<String>,        // Recherche (insensible à la casse) dans les notes
     pub date_from: Option<NaiveDateTime>, // Format ISO8601: YYYY-MM-DDTHH:MM:SS
-    pub date_to: Option<NaiveDateTime>,   // Format ISO8601: YYYY-MM-DDTHH:MM:SS
-                                          // pub page: Option<i64>, // Pour la pagination future
-                                          // pub per_page: Option<i64>,
+    pub date_to: Option<NaiveDateTime>, // Format ISO8601: YYYY-MM-DDTHH:MM:SS
+    pub auto_stopped: Option<bool>, // true : uniquement les entrées fermées/annotées par le sweeper
+                                  // pub page: Option<i64>, // Pour la pagination future
+                                  // pub per_page: Option<i64>,
+}
+
+// Motif ILIKE "contient" : les jokers saisis par l'utilisateur sont pris littéralement
+pub fn note_search_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+// Mode de résolution des chevauchements sur création/mise à jour : ?overlap=reject|trim_existing|split|allow
+#[derive(serde::Deserialize, Debug)]
+pub struct OverlapQuery {
+    pub overlap: Option<String>,
+}
+
+// Corps de réponse après résolution : l'entrée seule, ou la liste de ses parties en mode split
+fn resolved_entries_body(
+    resolution: OverlapResolution,
+    mut pieces: Vec<TimeEntry>,
+) -> serde_json::Value {
+    if resolution == OverlapResolution::Split {
+        json!(pieces)
+    } else {
+        json!(pieces.remove(0))
+    }
+}
+
+// Index unique partiel garantissant au plus une entrée ouverte (end_time NULL) par utilisateur
+const RUNNING_TIMER_INDEX: &str = "idx_time_entries_one_running_per_user";
+
+// Traduit la violation de l'index "un seul timer en cours" en 409, les autres erreurs comme d'habitude
+pub fn map_running_timer_violation(error: DieselError) -> ServiceError {
+    match &error {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, info)
+            if info.constraint_name() == Some(RUNNING_TIMER_INDEX) =>
+        {
+            ServiceError::Conflict(
+                "A timer is already running. Stop it before starting a new one.".to_string(),
+            )
+        }
+        _ => ServiceError::from(error),
+    }
+}
+
+// Entrée en cours (sans end_time) de l'utilisateur, s'il y en a une
+pub fn find_running_entry(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+) -> Result<Option<TimeEntry>, ServiceError> {
+    time_entries
+        .filter(user_id.eq(user_uuid))
+        .filter(end_time.is_null())
+        .select(TimeEntry::as_select())
+        .first::<TimeEntry>(conn)
+        .optional()
+        .map_err(ServiceError::from)
+}
+
+// Arrête le timer en cours à `stopped_at` et calcule sa durée côté serveur.
+// Renvoie None si aucun timer ne tourne.
+pub fn stop_running_entry(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    stopped_at: DateTime<Utc>,
+) -> Result<Option<TimeEntry>, ServiceError> {
+    let running = match find_running_entry(conn, user_uuid)? {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+    // Une horloge client en avance peut donner un start_time futur : pas de durée négative
+    let stopped_at = stopped_at.max(running.start_time);
+    let elapsed_seconds = (stopped_at - running.start_time).num_seconds() as i32;
+
+    diesel::update(time_entries.filter(id.eq(running.id)))
+        .set((
+            end_time.eq(Some(stopped_at)),
+            duration_seconds.eq(Some(elapsed_seconds)),
+        ))
+        .get_result::<TimeEntry>(conn)
+        .map(Some)
+        .map_err(ServiceError::from)
+}
+
+// === POST /time-entries/start ===
+#[post("/start")]
+pub async fn start_timer_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<StartTimerPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let payload = payload.into_inner();
+    log::info!(
+        "User {} starting timer with payload: {:?}",
+        user_uuid,
+        payload
+    );
+
+    let started_entry = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_time_entry_target_owned(conn, user_uuid, payload.task_id, payload.project_id)?;
+
+            if let Some(running) = find_running_entry(conn, user_uuid)? {
+                return Err(ServiceError::Conflict(format!(
+                    "A timer is already running (time entry {}). Stop it before starting a new one.",
+                    running.id
+                )));
+            }
+
+            let new_time_entry_data = NewTimeEntry {
+                user_id: user_uuid,
+                task_id: payload.task_id,
+                project_id: payload.project_id,
+                note: payload.note,
+                start_time: Utc::now(),
+                end_time: None,
+                duration_seconds: None,
+                is_pomodoro_session: payload.is_pomodoro_session,
+                pomodoro_session_id: None,
+                billable: None,
+            };
+
+            // L'index unique couvre la course entre deux appareils qui démarrent en même temps
+            diesel::insert_into(time_entries::table)
+                .values(&new_time_entry_data)
+                .get_result::<TimeEntry>(conn)
+                .map_err(map_running_timer_violation)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (start_timer): {:?}", e);
+        ServiceError::InternalServerError("Error processing start_timer request".to_string())
+    })??;
+
+    log::info!("Timer started: {:?}", started_entry);
+    Ok(HttpResponse::Created().json(started_entry))
+}
+
+// === POST /time-entries/stop ===
+#[post("/stop")]
+pub async fn stop_timer_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!("User {} stopping running timer", user_uuid);
+
+    let stopped_entry = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            stop_running_entry(conn, user_uuid, Utc::now())
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (stop_timer): {:?}", e);
+        ServiceError::InternalServerError("Error processing stop_timer request".to_string())
+    })??;
+
+    match stopped_entry {
+        Some(entry) => Ok(HttpResponse::Ok().json(entry)),
+        None => Err(ServiceError::NotFound(
+            "No running timer to stop".to_string(),
+        )),
+    }
+}
+
+// === GET /time-entries/current ===
+// Renvoie l'entrée en cours, ou `null` si aucun timer ne tourne.
+#[get("/current")]
+pub async fn get_current_timer_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!("User {} fetching current timer", user_uuid);
+
+    let running_entry = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| find_running_entry(conn, user_uuid))
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_current_timer): {:?}", e);
+        ServiceError::InternalServerError("Error processing get_current_timer request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(running_entry))
+}
+
+// DTO pour le rapport de chevauchements (bornes optionnelles, ISO 8601)
+#[derive(serde::Deserialize, Debug)]
+pub struct ListOverlapsQuery {
+    pub date_from: Option<DateTime<Utc>>,
+    pub date_to: Option<DateTime<Utc>>,
+}
+
+// Paire d'entrées qui se chevauchent, telle que renvoyée par le SQL du rapport
+#[derive(QueryableByName, Debug)]
+struct OverlapPairRow {
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    first_entry_id: Uuid,
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    second_entry_id: Uuid,
+    #[diesel(sql_type = diesel::sql_types::Timestamptz)]
+    overlap_start: DateTime<Utc>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Timestamptz>)]
+    overlap_end: Option<DateTime<Utc>>,
+}
+
+// === GET /time-entries/overlaps ===
+// Rapport des chevauchements existants, pour nettoyer l'historique.
+#[get("/overlaps")]
+pub async fn list_time_entry_overlaps_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<ListOverlapsQuery>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let query_options = query_params.into_inner();
+    log::info!(
+        "User {} listing time entry overlaps with options: {:?}",
+        user_uuid,
+        query_options
+    );
+
+    let overlaps = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            // Une entrée ouverte (end_time NULL) s'étend jusqu'à 'infinity'
+            let pairs = diesel::sql_query(
+                "SELECT a.id as first_entry_id, b.id as second_entry_id, \
+                    GREATEST(a.start_time, b.start_time) as overlap_start, \
+                    NULLIF(LEAST(COALESCE(a.end_time, 'infinity'::timestamptz), COALESCE(b.end_time, 'infinity'::timestamptz)), 'infinity'::timestamptz) as overlap_end \
+                 FROM time_entries a \
+                 JOIN time_entries b ON b.user_id = a.user_id AND (a.start_time, a.id) < (b.start_time, b.id) \
+                 WHERE a.user_id = $1 \
+                 AND b.start_time < COALESCE(a.end_time, 'infinity'::timestamptz) \
+                 AND a.start_time < COALESCE(b.end_time, 'infinity'::timestamptz) \
+                 AND COALESCE(a.end_time, 'infinity'::timestamptz) > a.start_time \
+                 AND COALESCE(b.end_time, 'infinity'::timestamptz) > b.start_time \
+                 AND ($2::timestamptz IS NULL OR LEAST(COALESCE(a.end_time, 'infinity'::timestamptz), COALESCE(b.end_time, 'infinity'::timestamptz)) > $2) \
+                 AND ($3::timestamptz IS NULL OR GREATEST(a.start_time, b.start_time) < $3) \
+                 ORDER BY overlap_start ASC",
+            )
+            .bind::<diesel::sql_types::Uuid, _>(user_uuid)
+            .bind::<diesel::sql_types::Nullable<diesel::sql_types::Timestamptz>, _>(
+                query_options.date_from,
+            )
+            .bind::<diesel::sql_types::Nullable<diesel::sql_types::Timestamptz>, _>(
+                query_options.date_to,
+            )
+            .load::<OverlapPairRow>(conn)?;
+
+            let entry_ids: Vec<Uuid> = pairs
+                .iter()
+                .flat_map(|pair| [pair.first_entry_id, pair.second_entry_id])
+                .collect();
+            let entries_by_id: HashMap<Uuid, TimeEntry> = time_entries
+                .filter(id.eq_any(&entry_ids))
+                .select(TimeEntry::as_select())
+                .load::<TimeEntry>(conn)?
+                .into_iter()
+                .map(|entry| (entry.id, entry))
+                .collect();
+
+            Ok(pairs
+                .into_iter()
+                .filter_map(|pair| {
+                    Some(TimeEntryOverlap {
+                        first_entry: entries_by_id.get(&pair.first_entry_id)?.clone(),
+                        second_entry: entries_by_id.get(&pair.second_entry_id)?.clone(),
+                        overlap_start: pair.overlap_start,
+                        overlap_end: pair.overlap_end,
+                        overlap_seconds: pair
+                            .overlap_end
+                            .map(|end| (end - pair.overlap_start).num_seconds()),
+                    })
+                })
+                .collect::<Vec<TimeEntryOverlap>>())
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_time_entry_overlaps): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing list_time_entry_overlaps request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Ok().json(overlaps))
+}
+
+// Trou maximal toléré par défaut entre deux entrées fusionnées (start/stop répétés)
+const MERGE_DEFAULT_MAX_GAP_SECONDS: i64 = 300;
+
+// Entrée de l'utilisateur verrouillée pour une opération de découpe/fusion
+fn load_entry_for_update(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    entry_uuid: Uuid,
+) -> Result<TimeEntry, ServiceError> {
+    ensure_time_entry_owned(conn, user_uuid, entry_uuid)?;
+    let entry = time_entries
+        .filter(id.eq(entry_uuid))
+        .filter(user_id.eq(user_uuid))
+        .select(TimeEntry::as_select())
+        .for_update()
+        .first::<TimeEntry>(conn)?;
+    ensure_week_not_approved(conn, user_uuid, &[entry.start_time])?;
+    Ok(entry)
+}
+
+// === POST /time-entries/merge ===
+// Fusionne des entrées consécutives d'une même tâche dans la première ; les autres sont supprimées.
+// Le trou éventuel entre deux entrées est absorbé par l'entrée fusionnée.
+// Les entrées doivent avoir le même drapeau `billable` (sinon 400).
+#[post("/merge")]
+pub async fn merge_time_entries_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<MergeTimeEntriesPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let payload = payload.into_inner();
+    log::info!(
+        "User {} merging time entries with payload: {:?}",
+        user_uuid,
+        payload
+    );
+
+    let max_gap_seconds = payload
+        .max_gap_seconds
+        .unwrap_or(MERGE_DEFAULT_MAX_GAP_SECONDS);
+    if max_gap_seconds < 0 {
+        return Err(ServiceError::BadRequest(
+            "max_gap_seconds must be zero or positive.".to_string(),
+        ));
+    }
+    let mut requested_ids = payload.entry_ids.clone();
+    requested_ids.sort();
+    requested_ids.dedup();
+    if requested_ids.len() != payload.entry_ids.len() {
+        return Err(ServiceError::BadRequest(
+            "entry_ids must not contain duplicates.".to_string(),
+        ));
+    }
+    if requested_ids.len() < 2 {
+        return Err(ServiceError::BadRequest(
+            "At least two time entries are required to merge.".to_string(),
+        ));
+    }
+
+    let merged_entry = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let mut entries = requested_ids
+                .iter()
+                .map(|entry_uuid| load_entry_for_update(conn, user_uuid, *entry_uuid))
+                .collect::<Result<Vec<TimeEntry>, ServiceError>>()?;
+            entries.sort_by_key(|entry| (entry.start_time, entry.id));
+
+            let first = entries[0].clone();
+            let last = entries[entries.len() - 1].clone();
+            if entries
+                .iter()
+                .any(|entry| entry.task_id != first.task_id || entry.project_id != first.project_id)
+            {
+                return Err(ServiceError::BadRequest(
+                    "Only time entries of the same task (or project) can be merged.".to_string(),
+                ));
+            }
+            // Une phase Pomodoro ne se fusionne qu'avec les fragments de la même session
+            if entries.iter().any(|entry| {
+                entry.is_pomodoro_session != first.is_pomodoro_session
+                    || entry.pomodoro_session_id != first.pomodoro_session_id
+            }) {
+                return Err(ServiceError::BadRequest(
+                    "Pomodoro and regular time entries, or entries of different Pomodoro sessions, cannot be merged.".to_string(),
+                ));
+            }
+            // Le drapeau facturable explicite de chaque fragment doit survivre à la fusion
+            if entries.iter().any(|entry| entry.billable != first.billable) {
+                return Err(ServiceError::BadRequest(
+                    "Time entries with different billable flags cannot be merged; align them first.".to_string(),
+                ));
+            }
+            for pair in entries.windows(2) {
+                let (previous, next) = (&pair[0], &pair[1]);
+                let previous_end = previous.end_time.ok_or_else(|| {
+                    ServiceError::BadRequest(format!(
+                        "Time entry {} is still running, only the last entry may be running.",
+                        previous.id
+                    ))
+                })?;
+                if previous_end > next.start_time {
+                    return Err(ServiceError::BadRequest(format!(
+                        "Time entries {} and {} overlap and cannot be merged.",
+                        previous.id, next.id
+                    )));
+                }
+                if (next.start_time - previous_end).num_seconds() > max_gap_seconds {
+                    return Err(ServiceError::BadRequest(format!(
+                        "Time entries {} and {} are more than {} seconds apart.",
+                        previous.id, next.id, max_gap_seconds
+                    )));
+                }
+            }
+
+            // Suppression d'abord : une éventuelle entrée ouverte disparaît avant que la première
+            // ne le devienne (index "un seul timer en cours")
+            let absorbed_ids: Vec<Uuid> = entries.iter().skip(1).map(|entry| entry.id).collect();
+            diesel::delete(
+                time_entries
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq_any(&absorbed_ids)),
+            )
+            .execute(conn)?;
+
+            // Les notes distinctes sont conservées, une par ligne, dans l'ordre chronologique
+            let mut merged_notes: Vec<&str> = Vec::new();
+            for entry_note in entries.iter().filter_map(|entry| entry.note.as_deref()) {
+                if !entry_note.trim().is_empty() && !merged_notes.contains(&entry_note) {
+                    merged_notes.push(entry_note);
+                }
+            }
+            let merged_note = if merged_notes.is_empty() {
+                None
+            } else {
+                Some(merged_notes.join("\n"))
+            };
+
+            // L'annotation d'arrêt automatique suit la fin de l'entrée fusionnée
+            let merged = diesel::update(time_entries.filter(id.eq(first.id)))
+                .set((
+                    note.eq(merged_note),
+                    end_time.eq(last.end_time),
+                    duration_seconds.eq(duration_between(first.start_time, last.end_time)),
+                    auto_stop_reason.eq(last.auto_stop_reason.clone()),
+                    auto_stopped_at.eq(last.auto_stopped_at),
+                ))
+                .get_result::<TimeEntry>(conn)
+                .map_err(map_running_timer_violation)?;
+
+            // Le trou absorbé ne doit pas recouvrir une autre entrée
+            let clashing = find_overlapping_entries(conn, &merged)?;
+            if !clashing.is_empty() {
+                return Err(ServiceError::ConflictWithDetails(
+                    format!(
+                        "Merged time entry would overlap {} other entr{}.",
+                        clashing.len(),
+                        if clashing.len() > 1 { "ies" } else { "y" }
+                    ),
+                    json!({ "overlapping_entries": clashing }),
+                ));
+            }
+
+            Ok(merged)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (merge_time_entries): {:?}", e);
+        ServiceError::InternalServerError("Error processing merge_time_entries request".to_string())
+    })??;
+
+    log::info!("Time entries merged into: {:?}", merged_entry);
+    Ok(HttpResponse::Ok().json(merged_entry))
+}
+
+// === POST /time-entries/{entry_id_path}/split ===
+// Coupe une entrée en deux à l'instant `at` ; renvoie les deux parties.
+// Une entrée en cours est coupée avant maintenant et sa seconde partie reste ouverte.
+#[post("/{entry_id_path}/split")]
+pub async fn split_time_entry_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    entry_id_path: web::Path<Uuid>,
+    payload: web::Json<SplitTimeEntryPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let entry_to_split_id = entry_id_path.into_inner();
+    let payload = payload.into_inner();
+    log::info!(
+        "User {} splitting time_entry {} with payload: {:?}",
+        user_uuid,
+        entry_to_split_id,
+        payload
+    );
+
+    let parts = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let entry = load_entry_for_update(conn, user_uuid, entry_to_split_id)?;
+            let split_at = payload.at;
+            let effective_end = entry.end_time.unwrap_or_else(Utc::now);
+            if split_at <= entry.start_time || split_at >= effective_end {
+                return Err(ServiceError::BadRequest(
+                    "Split time must be strictly between the start and the end of the time entry."
+                        .to_string(),
+                ));
+            }
+            if let Some(new_task_id) = payload.task_id {
+                ensure_task_owned(conn, user_uuid, new_task_id)?;
+            }
+            // La seconde partie peut tomber dans la semaine suivante
+            ensure_week_not_approved(conn, user_uuid, &[split_at])?;
+
+            // La première partie est fermée avant d'insérer la seconde (index "un seul timer en cours")
+            let first_part = diesel::update(time_entries.filter(id.eq(entry.id)))
+                .set((
+                    end_time.eq(Some(split_at)),
+                    duration_seconds.eq(duration_between(entry.start_time, Some(split_at))),
+                    auto_stop_reason.eq(None::<String>),
+                    auto_stopped_at.eq(None::<DateTime<Utc>>),
+                ))
+                .get_result::<TimeEntry>(conn)?;
+
+            // Les deux parties gardent le drapeau et le lien Pomodoro de l'entrée d'origine
+            let mut second_part = diesel::insert_into(time_entries::table)
+                .values(&NewTimeEntry {
+                    user_id: user_uuid,
+                    // Réassignée à une tâche, la seconde partie quitte le projet direct
+                    task_id: payload.task_id.or(entry.task_id),
+                    project_id: if payload.task_id.is_some() {
+                        None
+                    } else {
+                        entry.project_id
+                    },
+                    note: entry.note.clone(),
+                    start_time: split_at,
+                    end_time: entry.end_time,
+                    duration_seconds: duration_between(split_at, entry.end_time),
+                    is_pomodoro_session: Some(entry.is_pomodoro_session),
+                    pomodoro_session_id: entry.pomodoro_session_id,
+                    billable: entry.billable,
+                })
+                .get_result::<TimeEntry>(conn)
+                .map_err(map_running_timer_violation)?;
+
+            // L'annotation d'arrêt automatique concerne la fin, donc la seconde partie
+            if entry.auto_stop_reason.is_some() {
+                second_part = diesel::update(time_entries.filter(id.eq(second_part.id)))
+                    .set((
+                        auto_stop_reason.eq(entry.auto_stop_reason.clone()),
+                        auto_stopped_at.eq(entry.auto_stopped_at),
+                    ))
+                    .get_result::<TimeEntry>(conn)?;
+            }
+
+            Ok(vec![first_part, second_part])
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (split_time_entry): {:?}", e);
+        ServiceError::InternalServerError("Error processing split_time_entry request".to_string())
+    })??;
+
+    log::info!("Time entry {} split: {:?}", entry_to_split_id, parts);
+    Ok(HttpResponse::Ok().json(parts))
 }
 
 // === POST /time-entries ===
@@ -32,16 +603,21 @@ pub async fn create_time_entry_handler(
     pool: web::Data<DbPool>,
     authenticated_user: AuthenticatedUser,
     payload: web::Json<CreateTimeEntryPayload>,
+    overlap_query: web::Query<OverlapQuery>,
 ) -> ActixResult<HttpResponse, ServiceError> {
     let user_uuid = authenticated_user.id; // Uuid est Copy
+    let resolution = OverlapResolution::parse(overlap_query.overlap.as_deref())?;
 
     // Cloner les données du payload qui seront utilisées dans la closure web::block
     // Les types simples comme Uuid, NaiveDateTime, Option<T> (si T est simple) sont souvent Copy ou facilement clonables.
     let task_id_payload = payload.task_id;
+    let project_id_payload = payload.project_id;
+    let note_payload = payload.note.clone();
     let start_time_payload = payload.start_time;
     let end_time_payload = payload.end_time;
     let duration_seconds_payload = payload.duration_seconds;
     let is_pomodoro_payload = payload.is_pomodoro_session;
+    let billable_payload = payload.billable;
 
     log::info!(
         "User {} creating time entry with payload: {:?}",
@@ -49,49 +625,43 @@ pub async fn create_time_entry_handler(
         payload.0 // Accéder aux données internes de web::Json pour le log
     );
 
-    let created_entry = web::block(move || {
-        let mut conn = pool.get().map_err(ServiceError::from)?;
-
-        // 1. Vérifier que la tâche associée appartient à l'utilisateur
-        tasks::table
-            .filter(tasks::id.eq(task_id_payload))
-            .filter(tasks::user_id.eq(user_uuid))
-            .select(tasks::id)
-            .first::<Uuid>(&mut conn)
-            .map_err(|db_err| {
-                // Gestion plus fine de l'erreur NotFound
-                match db_err {
-                    diesel::result::Error::NotFound => ServiceError::NotFound(format!(
-                        "Task with id {} not found or not owned by user",
-                        task_id_payload
-                    )),
-                    _ => ServiceError::from(db_err),
-                }
-            })?;
+    let created_entries = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            // 1. Vérifier que la tâche (ou le projet) associé appartient à l'utilisateur
+            ensure_time_entry_target_owned(conn, user_uuid, task_id_payload, project_id_payload)?;
+            ensure_week_not_approved(conn, user_uuid, &[start_time_payload])?;
 
-        // 2. Calculer duration_seconds si end_time est fourni et duration_seconds ne l'est pas
-        let mut final_duration_seconds = duration_seconds_payload;
-        if let Some(end) = end_time_payload {
-            if final_duration_seconds.is_none() && end > start_time_payload {
-                final_duration_seconds = Some((end - start_time_payload).num_seconds() as i32);
+            // 2. Calculer duration_seconds si end_time est fourni et duration_seconds ne l'est pas
+            let mut final_duration_seconds = duration_seconds_payload;
+            if let Some(end) = end_time_payload {
+                if final_duration_seconds.is_none() && end > start_time_payload {
+                    final_duration_seconds = Some((end - start_time_payload).num_seconds() as i32);
+                }
             }
-        }
 
-        let new_time_entry_data = NewTimeEntry {
-            user_id: user_uuid,
-            task_id: task_id_payload,
-            start_time: start_time_payload,
-            end_time: end_time_payload,
-            duration_seconds: final_duration_seconds,
-            is_pomodoro_session: is_pomodoro_payload, // NewTimeEntry.is_pomodoro_session est Option<bool>
-                                                      // La DB a DEFAULT FALSE, donc None ici est ok.
-        };
+            let new_time_entry_data = NewTimeEntry {
+                user_id: user_uuid,
+                task_id: task_id_payload,
+                project_id: project_id_payload,
+                note: note_payload,
+                start_time: start_time_payload,
+                end_time: end_time_payload,
+                duration_seconds: final_duration_seconds,
+                is_pomodoro_session: is_pomodoro_payload, // NewTimeEntry.is_pomodoro_session est Option<bool>
+                // La DB a DEFAULT FALSE, donc None ici est ok.
+                pomodoro_session_id: None,
+                billable: billable_payload,
+            };
 
-        // 3. Insérer
-        diesel::insert_into(time_entries::table)
-            .values(&new_time_entry_data)
-            .get_result::<TimeEntry>(&mut conn)
-            .map_err(ServiceError::from)
+            // 3. Insérer (une entrée sans end_time est un timer en cours : un seul à la fois)
+            let created_entry = diesel::insert_into(time_entries::table)
+                .values(&new_time_entry_data)
+                .get_result::<TimeEntry>(conn)
+                .map_err(map_running_timer_violation)?;
+
+            // 4. Chevauchements avec les entrées existantes (un rejet annule l'insertion)
+            resolve_overlaps(conn, created_entry, resolution)
+        })
     })
     .await
     .map_err(|e: actix_web::error::BlockingError| {
@@ -100,8 +670,8 @@ pub async fn create_time_entry_handler(
         ServiceError::InternalServerError("Error processing create_time_entry request".to_string())
     })??; // Double '??' pour déballer Result<Result<_, ServiceError>, BlockingError>
 
-    log::info!("Time entry created successfully: {:?}", created_entry);
-    Ok(HttpResponse::Created().json(created_entry))
+    log::info!("Time entry created successfully: {:?}", created_entries);
+    Ok(HttpResponse::Created().json(resolved_entries_body(resolution, created_entries)))
 }
 
 // === GET /time-entries ===
@@ -120,26 +690,41 @@ pub async fn list_time_entries_handler(
     );
 
     let entries = web::block(move || {
-        let mut conn = pool.get()?;
-        let mut query = time_entries
-            .filter(user_id.eq(user_uuid))
-            .order(start_time.desc()) // Plus récent en premier
-            .select(TimeEntry::as_select())
-            .into_boxed();
-
-        if let Some(t_id) = query_options.task_id {
-            query = query.filter(task_id.eq(t_id));
-        }
-        if let Some(from_date) = query_options.date_from {
-            query = query.filter(start_time.ge(from_date));
-        }
-        if let Some(to_date) = query_options.date_to {
-            query = query.filter(start_time.le(to_date));
-        }
+        run_as_user(&pool, user_uuid, |conn| {
+            let mut query = time_entries
+                .filter(user_id.eq(user_uuid))
+                .order(start_time.desc()) // Plus récent en premier
+                .select(TimeEntry::as_select())
+                .into_boxed();
 
-        query
-            .load::<TimeEntry>(&mut conn)
-            .map_err(ServiceError::from)
+            if let Some(t_id) = query_options.task_id {
+                query = query.filter(task_id.eq(t_id));
+            }
+            if let Some(p_id) = query_options.project_id {
+                let project_tasks = tasks::table
+                    .filter(tasks::project_id.eq(p_id))
+                    .select(tasks::id.nullable());
+                query = query.filter(project_id.eq(p_id).or(task_id.eq_any(project_tasks)));
+            }
+            if let Some(search) = query_options.q.as_deref().map(str::trim) {
+                if !search.is_empty() {
+                    query = query.filter(note.ilike(note_search_pattern(search)));
+                }
+            }
+            if let Some(from_date) = query_options.date_from {
+                query = query.filter(start_time.ge(from_date));
+            }
+            if let Some(to_date) = query_options.date_to {
+                query = query.filter(start_time.le(to_date));
+            }
+            match query_options.auto_stopped {
+                Some(true) => query = query.filter(auto_stop_reason.is_not_null()),
+                Some(false) => query = query.filter(auto_stop_reason.is_null()),
+                None => {}
+            }
+
+            query.load::<TimeEntry>(conn).map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -166,14 +751,15 @@ pub async fn get_time_entry_handler(
     );
 
     let entry_option = web::block(move || {
-        let mut conn = pool.get()?;
-        time_entries
-            .filter(user_id.eq(user_uuid))
-            .filter(id.eq(entry_to_find_id))
-            .select(TimeEntry::as_select())
-            .first::<TimeEntry>(&mut conn)
-            .optional()
-            .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            time_entries
+                .filter(user_id.eq(user_uuid))
+                .filter(id.eq(entry_to_find_id))
+                .select(TimeEntry::as_select())
+                .first::<TimeEntry>(conn)
+                .optional()
+                .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -197,8 +783,10 @@ pub async fn update_time_entry_handler(
     authenticated_user: AuthenticatedUser,
     entry_id_path: web::Path<Uuid>,
     payload: web::Json<UpdateTimeEntryPayload>,
+    overlap_query: web::Query<OverlapQuery>,
 ) -> ActixResult<HttpResponse, ServiceError> {
     let user_uuid = authenticated_user.id;
+    let resolution = OverlapResolution::parse(overlap_query.overlap.as_deref())?;
     let entry_to_update_id = entry_id_path.into_inner();
     log::info!(
         "User {} updating time_entry {} with payload: {:?}",
@@ -213,20 +801,22 @@ pub async fn update_time_entry_handler(
     let user_id_clone_for_fetch = user_uuid; // Uuid est Copy
 
     let current_entry_start_time_naive = web::block(move || {
-        let mut conn = pool_clone_for_fetch.get().map_err(ServiceError::from)?;
-        time_entries
-            .filter(id.eq(entry_id_clone_for_fetch))
-            .filter(user_id.eq(user_id_clone_for_fetch))
-            .select(start_time)
-            .first::<NaiveDateTime>(&mut conn)
-            .map_err(|db_err| match db_err {
-                // Gestion plus fine de NotFound
-                diesel::result::Error::NotFound => ServiceError::NotFound(format!(
-                    "TimeEntry with id {} not found or not owned by user for update",
-                    entry_id_clone_for_fetch
-                )),
-                _ => ServiceError::from(db_err),
-            })
+        run_as_user(&pool_clone_for_fetch, user_id_clone_for_fetch, |conn| {
+            ensure_time_entry_owned(conn, user_id_clone_for_fetch, entry_id_clone_for_fetch)?;
+            time_entries
+                .filter(id.eq(entry_id_clone_for_fetch))
+                .filter(user_id.eq(user_id_clone_for_fetch))
+                .select(start_time)
+                .first::<NaiveDateTime>(conn)
+                .map_err(|db_err| match db_err {
+                    // Gestion plus fine de NotFound
+                    diesel::result::Error::NotFound => ServiceError::NotFound(format!(
+                        "TimeEntry with id {} not found or not owned by user for update",
+                        entry_id_clone_for_fetch
+                    )),
+                    _ => ServiceError::from(db_err),
+                })
+        })
     })
     .await
     .map_err(|e: actix_web::error::BlockingError| {
@@ -257,6 +847,8 @@ pub async fn update_time_entry_handler(
         end_time: payload.end_time.clone(),
         duration_seconds: changeset_duration,
         is_pomodoro_session: payload.is_pomodoro_session,
+        billable: payload.billable,
+        note: payload.note.clone(),
         updated_at: Some(Utc::now().naive_utc()),
     };
 
@@ -266,17 +858,26 @@ pub async fn update_time_entry_handler(
         entry_changes
     );
 
-    let updated_entry = web::block(move || {
+    // Semaine actuelle de l'entrée et, si elle est déplacée, semaine d'arrivée
+    let mut touched_starts = vec![current_entry_start_time_naive.and_utc()];
+    touched_starts.extend(entry_changes.start_time);
+
+    let updated_entries = web::block(move || {
         // pool (l'original) est déplacé ici
-        let mut conn = pool.get().map_err(ServiceError::from)?;
-        diesel::update(
-            time_entries
-                .filter(id.eq(entry_to_update_id))
-                .filter(user_id.eq(user_uuid)), // user_uuid est copié
-        )
-        .set(&entry_changes)
-        .get_result::<TimeEntry>(&mut conn)
-        .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_week_not_approved(conn, user_uuid, &touched_starts)?;
+            let updated_entry = diesel::update(
+                time_entries
+                    .filter(id.eq(entry_to_update_id))
+                    .filter(user_id.eq(user_uuid)), // user_uuid est copié
+            )
+            .set(&entry_changes)
+            .get_result::<TimeEntry>(conn)
+            .map_err(map_running_timer_violation)?;
+
+            // Chevauchements sur le nouvel intervalle (un rejet annule la mise à jour)
+            resolve_overlaps(conn, updated_entry, resolution)
+        })
     })
     .await
     .map_err(|e| {
@@ -284,7 +885,7 @@ pub async fn update_time_entry_handler(
         ServiceError::InternalServerError("Error processing update_time_entry request".to_string())
     })??;
 
-    Ok(HttpResponse::Ok().json(updated_entry))
+    Ok(HttpResponse::Ok().json(resolved_entries_body(resolution, updated_entries)))
 }
 
 // === DELETE /time-entries/{entry_id_path} ===
@@ -303,14 +904,21 @@ pub async fn delete_time_entry_handler(
     );
 
     let num_deleted = web::block(move || {
-        let mut conn = pool.get()?;
-        diesel::delete(
-            time_entries
-                .filter(user_id.eq(user_uuid))
-                .filter(id.eq(entry_to_delete_id)),
-        )
-        .execute(&mut conn)
-        .map_err(ServiceError::from)
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_time_entry_owned(conn, user_uuid, entry_to_delete_id)?;
+            let entry_start = time_entries
+                .filter(id.eq(entry_to_delete_id))
+                .select(start_time)
+                .first::<DateTime<Utc>>(conn)?;
+            ensure_week_not_approved(conn, user_uuid, &[entry_start])?;
+            diesel::delete(
+                time_entries
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq(entry_to_delete_id)),
+            )
+            .execute(conn)
+            .map_err(ServiceError::from)
+        })
     })
     .await
     .map_err(|e| {
@@ -330,3 +938,60 @@ pub async fn delete_time_entry_handler(
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::configure_routes;
+    use crate::schema::time_entries;
+    use crate::test_support::{seed_task, test_auth_config, test_pool};
+    use actix_web::{test, web, App};
+    use chrono::{DateTime, Duration, Utc};
+    use diesel::prelude::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    // Un fragment explicitement non facturable ne devient pas facturable par fusion
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn merge_refuses_entries_with_different_billable_flags() {
+        let pool = test_pool();
+        let owner = Uuid::new_v4();
+        let entry_ids: Vec<Uuid> = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            let task = seed_task(&mut conn, owner, None);
+            let start: DateTime<Utc> = "2026-10-05T09:00:00Z".parse().unwrap();
+            [(0, Some(true)), (1, Some(false))]
+                .into_iter()
+                .map(|(hour, entry_billable)| {
+                    let entry_start = start + Duration::hours(hour);
+                    diesel::insert_into(time_entries::table)
+                        .values((
+                            time_entries::user_id.eq(owner),
+                            time_entries::task_id.eq(Some(task)),
+                            time_entries::start_time.eq(entry_start),
+                            time_entries::end_time.eq(Some(entry_start + Duration::hours(1))),
+                            time_entries::duration_seconds.eq(Some(3600)),
+                            time_entries::billable.eq(entry_billable),
+                        ))
+                        .returning(time_entries::id)
+                        .get_result(&mut conn)
+                        .expect("Failed to seed time entry")
+                })
+                .collect()
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_auth_config()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/time-entries/merge")
+            .insert_header(("X-User-Id", owner.to_string()))
+            .set_json(json!({ "entry_ids": entry_ids }))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), 400);
+    }
+}
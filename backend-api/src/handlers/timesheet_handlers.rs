@@ -0,0 +1,594 @@
+// OptiTask/backend-api/src/handlers/timesheet_handlers.rs
+// Feuilles de temps hebdomadaires : l'utilisateur soumet sa semaine ISO à un approbateur,
+// qui l'approuve ou la rejette. Les time entries d'une semaine approuvée sont figées
+// (voir timesheets::ensure_week_not_approved) jusqu'à réouverture.
+use crate::auth_utils::AuthenticatedUser;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::handlers::analytics_handlers::{local_midnight_to_utc, settings_timezone};
+use crate::handlers::user_handlers::load_user_settings;
+use crate::models::{
+    NewTimesheet, NewTimesheetLine, ReviewTimesheetPayload, SubmitTimesheetPayload, Timesheet,
+    TimesheetDayTotal, TimesheetWeekResponse, TimesheetWithLines,
+};
+use crate::schema::{time_entries, timesheet_lines, timesheets};
+use crate::timesheets::{load_week_totals, resolve_week_approver, IsoWeek};
+use actix_web::{get, post, web, HttpResponse, Result as ActixResult};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use uuid::Uuid;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct TimesheetWeekQuery {
+    pub date: Option<NaiveDate>, // Un jour de la semaine voulue, défaut : aujourd'hui
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ListTimesheetsQuery {
+    pub status: Option<String>,
+    pub role: Option<String>, // "mine" (défaut) ou "review" (feuilles à approuver)
+}
+
+fn validate_status(value: &str) -> Result<(), ServiceError> {
+    match value {
+        "open" | "submitted" | "approved" | "rejected" => Ok(()),
+        other => Err(ServiceError::BadRequest(format!(
+            "Invalid timesheet status: {}. Supported: open, submitted, approved, rejected.",
+            other
+        ))),
+    }
+}
+
+// Feuille visible par l'utilisateur : la sienne ou une feuille qu'il doit approuver
+fn load_visible_timesheet(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    timesheet_uuid: Uuid,
+) -> Result<Timesheet, ServiceError> {
+    timesheets::table
+        .filter(timesheets::id.eq(timesheet_uuid))
+        .filter(
+            timesheets::user_id
+                .eq(user_uuid)
+                .or(timesheets::approver_id.eq(user_uuid)),
+        )
+        .select(Timesheet::as_select())
+        .for_update()
+        .first::<Timesheet>(conn)
+        .optional()?
+        .ok_or_else(|| {
+            ServiceError::NotFound(format!(
+                "Timesheet with id {} not found or not visible to user",
+                timesheet_uuid
+            ))
+        })
+}
+
+fn with_lines(
+    conn: &mut PgConnection,
+    timesheet: Timesheet,
+) -> Result<TimesheetWithLines, ServiceError> {
+    let lines = timesheet_lines::table
+        .filter(timesheet_lines::timesheet_id.eq(timesheet.id))
+        .order((
+            timesheet_lines::project_name.asc(),
+            timesheet_lines::work_date.asc(),
+        ))
+        .select(TimesheetDayTotal::as_select())
+        .load::<TimesheetDayTotal>(conn)?;
+    Ok(TimesheetWithLines { timesheet, lines })
+}
+
+// Passage de statut réservé à l'approbateur de la feuille
+fn review_timesheet(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    timesheet_uuid: Uuid,
+    new_status: &str,
+    comment: Option<String>,
+) -> Result<TimesheetWithLines, ServiceError> {
+    let timesheet = load_visible_timesheet(conn, user_uuid, timesheet_uuid)?;
+    if timesheet.approver_id != user_uuid {
+        return Err(ServiceError::Forbidden(
+            "Only the approver of this timesheet can review it.".to_string(),
+        ));
+    }
+    // Feuilles auto-désignées d'avant la contrainte timesheets_approver_not_owner
+    if timesheet.user_id == user_uuid {
+        return Err(ServiceError::Forbidden(
+            "You cannot review your own timesheet.".to_string(),
+        ));
+    }
+    if timesheet.status != "submitted" {
+        return Err(ServiceError::Conflict(format!(
+            "Timesheet is '{}': only submitted timesheets can be reviewed.",
+            timesheet.status
+        )));
+    }
+    // Approuver une semaine dont un timer tourne encore : 409 levé par le trigger
+    // refuse_approval_with_running_entries (l'approbateur ne voit pas les entrées)
+    let reviewed = diesel::update(timesheets::table.filter(timesheets::id.eq(timesheet.id)))
+        .set((
+            timesheets::status.eq(new_status),
+            timesheets::review_comment.eq(comment),
+            timesheets::reviewed_at.eq(Some(Utc::now())),
+        ))
+        .get_result::<Timesheet>(conn)?;
+    with_lines(conn, reviewed)
+}
+
+// === GET /timesheets/week ===
+// Agrégat en direct de la semaine ISO et feuille existante éventuelle
+#[get("/week")]
+pub async fn get_timesheet_week_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<TimesheetWeekQuery>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let requested_date = query_params.into_inner().date;
+
+    let week_response = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let settings = load_user_settings(conn, user_uuid)?;
+            let tz = settings_timezone(&settings)?;
+            let week = IsoWeek::containing(
+                requested_date.unwrap_or_else(|| Utc::now().with_timezone(&tz).date_naive()),
+            );
+            let lines = load_week_totals(conn, user_uuid, week, tz)?;
+            let timesheet = timesheets::table
+                .filter(timesheets::user_id.eq(user_uuid))
+                .filter(timesheets::iso_year.eq(week.year))
+                .filter(timesheets::iso_week.eq(week.week))
+                .select(Timesheet::as_select())
+                .first::<Timesheet>(conn)
+                .optional()?;
+            Ok(TimesheetWeekResponse {
+                iso_year: week.year,
+                iso_week: week.week,
+                week_start: week.start,
+                week_end: week.end,
+                total_seconds: lines.iter().map(|line| line.seconds).sum(),
+                lines,
+                timesheet,
+            })
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_timesheet_week): {:?}", e);
+        ServiceError::InternalServerError("Error processing get_timesheet_week request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(week_response))
+}
+
+// === GET /timesheets ===
+#[get("")]
+pub async fn list_timesheets_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    query_params: web::Query<ListTimesheetsQuery>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let query_options = query_params.into_inner();
+    if let Some(status_filter) = &query_options.status {
+        validate_status(status_filter)?;
+    }
+    let review_mode = match query_options.role.as_deref() {
+        Some("mine") | None => false,
+        Some("review") => true,
+        Some(other) => {
+            return Err(ServiceError::BadRequest(format!(
+                "Invalid role: {}. Supported: mine, review.",
+                other
+            )))
+        }
+    };
+
+    let timesheet_list = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let mut query = timesheets::table
+                .select(Timesheet::as_select())
+                .into_boxed();
+            query = if review_mode {
+                query.filter(timesheets::approver_id.eq(user_uuid))
+            } else {
+                query.filter(timesheets::user_id.eq(user_uuid))
+            };
+            if let Some(status_filter) = query_options.status {
+                query = query.filter(timesheets::status.eq(status_filter));
+            }
+            query
+                .order(timesheets::week_start.desc())
+                .load::<Timesheet>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_timesheets): {:?}", e);
+        ServiceError::InternalServerError("Error processing list_timesheets request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(timesheet_list))
+}
+
+// === POST /timesheets/submit ===
+// Crée la feuille de la semaine, ou resoumet une feuille rouverte ou rejetée.
+// L'agrégat est copié dans timesheet_lines pour l'approbateur.
+#[post("/submit")]
+pub async fn submit_timesheet_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<SubmitTimesheetPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let payload = payload.into_inner();
+    log::info!(
+        "User {} submitting timesheet with payload: {:?}",
+        user_uuid,
+        payload
+    );
+
+    let submitted = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let settings = load_user_settings(conn, user_uuid)?;
+            let tz = settings_timezone(&settings)?;
+            let week = IsoWeek::containing(payload.week_of);
+
+            let existing = timesheets::table
+                .filter(timesheets::user_id.eq(user_uuid))
+                .filter(timesheets::iso_year.eq(week.year))
+                .filter(timesheets::iso_week.eq(week.week))
+                .select(Timesheet::as_select())
+                .for_update()
+                .first::<Timesheet>(conn)
+                .optional()?;
+            if let Some(current) = &existing {
+                if current.status == "submitted" || current.status == "approved" {
+                    return Err(ServiceError::Conflict(format!(
+                        "Timesheet for {}-W{:02} is already '{}'.",
+                        week.year, week.week, current.status
+                    )));
+                }
+            }
+
+            // Un timer en cours rendrait le total faux
+            let running_entries = time_entries::table
+                .filter(time_entries::user_id.eq(user_uuid))
+                .filter(time_entries::end_time.is_null())
+                .filter(time_entries::start_time.ge(local_midnight_to_utc(week.start, tz)))
+                .filter(
+                    time_entries::start_time
+                        .lt(local_midnight_to_utc(week.end + Duration::days(1), tz)),
+                )
+                .count()
+                .get_result::<i64>(conn)?;
+            if running_entries > 0 {
+                return Err(ServiceError::BadRequest(
+                    "Stop the running timer of this week before submitting it.".to_string(),
+                ));
+            }
+
+            let lines = load_week_totals(conn, user_uuid, week, tz)?;
+            let total_seconds: i64 = lines.iter().map(|line| line.seconds).sum();
+            let approver = resolve_week_approver(conn, user_uuid, &lines, payload.approver_id)?;
+            let now = Utc::now();
+
+            let timesheet = match existing {
+                Some(current) => {
+                    diesel::update(timesheets::table.filter(timesheets::id.eq(current.id)))
+                        .set((
+                            timesheets::approver_id.eq(approver),
+                            timesheets::status.eq("submitted"),
+                            timesheets::total_seconds.eq(total_seconds),
+                            timesheets::submit_comment.eq(payload.comment.clone()),
+                            timesheets::submitted_at.eq(Some(now)),
+                            timesheets::review_comment.eq(None::<String>),
+                            timesheets::reviewed_at.eq(None::<DateTime<Utc>>),
+                        ))
+                        .get_result::<Timesheet>(conn)
+                }
+                None => diesel::insert_into(timesheets::table)
+                    .values(&NewTimesheet {
+                        user_id: user_uuid,
+                        approver_id: approver,
+                        iso_year: week.year,
+                        iso_week: week.week,
+                        week_start: week.start,
+                        week_end: week.end,
+                        status: "submitted".to_string(),
+                        total_seconds,
+                        submit_comment: payload.comment.clone(),
+                        submitted_at: Some(now),
+                    })
+                    .get_result::<Timesheet>(conn),
+            }
+            .map_err(|error| match error {
+                DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+                    ServiceError::BadRequest(format!("Approver {} is not a known user.", approver))
+                }
+                _ => ServiceError::from(error),
+            })?;
+
+            diesel::delete(
+                timesheet_lines::table.filter(timesheet_lines::timesheet_id.eq(timesheet.id)),
+            )
+            .execute(conn)?;
+            let new_lines: Vec<NewTimesheetLine> = lines
+                .into_iter()
+                .map(|line| NewTimesheetLine {
+                    timesheet_id: timesheet.id,
+                    project_id: line.project_id,
+                    project_name: line.project_name,
+                    work_date: line.work_date,
+                    seconds: line.seconds,
+                })
+                .collect();
+            diesel::insert_into(timesheet_lines::table)
+                .values(&new_lines)
+                .execute(conn)?;
+
+            with_lines(conn, timesheet)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (submit_timesheet): {:?}", e);
+        ServiceError::InternalServerError("Error processing submit_timesheet request".to_string())
+    })??;
+
+    log::info!(
+        "Timesheet {} submitted to {}",
+        submitted.timesheet.id,
+        submitted.timesheet.approver_id
+    );
+    Ok(HttpResponse::Ok().json(submitted))
+}
+
+// === GET /timesheets/{timesheet_id_path} ===
+#[get("/{timesheet_id_path}")]
+pub async fn get_timesheet_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    timesheet_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let timesheet_to_find_id = timesheet_id_path.into_inner();
+
+    let timesheet = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let timesheet = load_visible_timesheet(conn, user_uuid, timesheet_to_find_id)?;
+            with_lines(conn, timesheet)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_timesheet): {:?}", e);
+        ServiceError::InternalServerError("Error processing get_timesheet request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(timesheet))
+}
+
+// === POST /timesheets/{timesheet_id_path}/approve ===
+#[post("/{timesheet_id_path}/approve")]
+pub async fn approve_timesheet_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    timesheet_id_path: web::Path<Uuid>,
+    payload: web::Json<ReviewTimesheetPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let timesheet_to_approve_id = timesheet_id_path.into_inner();
+    let comment = payload.into_inner().comment;
+
+    let approved = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            review_timesheet(
+                conn,
+                user_uuid,
+                timesheet_to_approve_id,
+                "approved",
+                comment,
+            )
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (approve_timesheet): {:?}", e);
+        ServiceError::InternalServerError("Error processing approve_timesheet request".to_string())
+    })??;
+
+    log::info!(
+        "Timesheet {} approved by {}",
+        timesheet_to_approve_id,
+        user_uuid
+    );
+    Ok(HttpResponse::Ok().json(approved))
+}
+
+// === POST /timesheets/{timesheet_id_path}/reject ===
+// Le commentaire est obligatoire : il explique ce qu'il faut corriger avant de resoumettre.
+#[post("/{timesheet_id_path}/reject")]
+pub async fn reject_timesheet_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    timesheet_id_path: web::Path<Uuid>,
+    payload: web::Json<ReviewTimesheetPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let timesheet_to_reject_id = timesheet_id_path.into_inner();
+    let comment = payload
+        .into_inner()
+        .comment
+        .filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| {
+            ServiceError::BadRequest("A comment is required to reject a timesheet.".to_string())
+        })?;
+
+    let rejected = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            review_timesheet(
+                conn,
+                user_uuid,
+                timesheet_to_reject_id,
+                "rejected",
+                Some(comment),
+            )
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (reject_timesheet): {:?}", e);
+        ServiceError::InternalServerError("Error processing reject_timesheet request".to_string())
+    })??;
+
+    log::info!(
+        "Timesheet {} rejected by {}",
+        timesheet_to_reject_id,
+        user_uuid
+    );
+    Ok(HttpResponse::Ok().json(rejected))
+}
+
+// === POST /timesheets/{timesheet_id_path}/reopen ===
+// L'approbateur peut rouvrir une feuille soumise ou approuvée (la semaine redevient
+// modifiable) ; l'utilisateur peut seulement retirer une feuille encore en attente.
+#[post("/{timesheet_id_path}/reopen")]
+pub async fn reopen_timesheet_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    timesheet_id_path: web::Path<Uuid>,
+    payload: web::Json<ReviewTimesheetPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let timesheet_to_reopen_id = timesheet_id_path.into_inner();
+    let comment = payload.into_inner().comment;
+
+    let reopened = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let timesheet = load_visible_timesheet(conn, user_uuid, timesheet_to_reopen_id)?;
+            let allowed = match timesheet.status.as_str() {
+                "submitted" => true,
+                "approved" => timesheet.approver_id == user_uuid,
+                _ => false,
+            };
+            if !allowed {
+                return Err(ServiceError::Conflict(format!(
+                    "Timesheet is '{}' and cannot be reopened by this user.",
+                    timesheet.status
+                )));
+            }
+            let reopened =
+                diesel::update(timesheets::table.filter(timesheets::id.eq(timesheet.id)))
+                    .set((
+                        timesheets::status.eq("open"),
+                        timesheets::review_comment.eq(comment.or(timesheet.review_comment)),
+                    ))
+                    .get_result::<Timesheet>(conn)?;
+            with_lines(conn, reopened)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (reopen_timesheet): {:?}", e);
+        ServiceError::InternalServerError("Error processing reopen_timesheet request".to_string())
+    })??;
+
+    log::info!(
+        "Timesheet {} reopened by {}",
+        timesheet_to_reopen_id,
+        user_uuid
+    );
+    Ok(HttpResponse::Ok().json(reopened))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configure_routes;
+    use crate::handlers::user_handlers::load_user_settings;
+    use crate::models::NewTimesheet;
+    use crate::schema::{time_entries, timesheets};
+    use crate::test_support::{seed_task, test_auth_config, test_pool};
+    use actix_web::{test, web, App};
+    use chrono::{DateTime, NaiveDate, Utc};
+    use diesel::pg::PgConnection;
+    use diesel::prelude::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    // Feuille 2026-W43 soumise par un nouvel utilisateur, avec une entrée lundi à 09:00
+    // (en cours si `running`)
+    fn submitted_week(conn: &mut PgConnection, manager: Uuid, running: bool) -> (Uuid, Uuid) {
+        let owner = Uuid::new_v4();
+        load_user_settings(conn, owner).expect("Failed to create user");
+        let task = seed_task(conn, owner, None);
+        let monday: DateTime<Utc> = "2026-10-19T09:00:00Z".parse().unwrap();
+        let end = (!running).then(|| monday + chrono::Duration::hours(1));
+        diesel::insert_into(time_entries::table)
+            .values((
+                time_entries::user_id.eq(owner),
+                time_entries::task_id.eq(Some(task)),
+                time_entries::start_time.eq(monday),
+                time_entries::end_time.eq(end),
+                time_entries::duration_seconds.eq(end.map(|_| 3600)),
+            ))
+            .execute(conn)
+            .expect("Failed to seed time entry");
+        let timesheet = diesel::insert_into(timesheets::table)
+            .values(&NewTimesheet {
+                user_id: owner,
+                approver_id: manager,
+                iso_year: 2026,
+                iso_week: 43,
+                week_start: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                week_end: NaiveDate::from_ymd_opt(2026, 10, 25).unwrap(),
+                status: "submitted".to_string(),
+                total_seconds: 3600,
+                submit_comment: None,
+                submitted_at: Some(Utc::now()),
+            })
+            .returning(timesheets::id)
+            .get_result(conn)
+            .expect("Failed to seed timesheet");
+        (owner, timesheet)
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn approval_is_refused_while_a_timer_of_the_week_runs() {
+        let pool = test_pool();
+        let manager = Uuid::new_v4();
+        let (running_timesheet, stopped_timesheet) = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            load_user_settings(&mut conn, manager).expect("Failed to create approver");
+            (
+                submitted_week(&mut conn, manager, true).1,
+                submitted_week(&mut conn, manager, false).1,
+            )
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_auth_config()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        for (timesheet, expected_status) in [(running_timesheet, 409), (stopped_timesheet, 200)] {
+            let request = test::TestRequest::post()
+                .uri(&format!("/timesheets/{}/approve", timesheet))
+                .insert_header(("X-User-Id", manager.to_string()))
+                .set_json(json!({}))
+                .to_request();
+            assert_eq!(
+                test::call_service(&app, request).await.status(),
+                expected_status
+            );
+        }
+    }
+}
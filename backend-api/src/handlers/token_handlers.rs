@@ -0,0 +1,178 @@
+// OptiTask/backend-api/src/handlers/token_handlers.rs
+use crate::auth_utils::{generate_api_token, AuthenticatedUser, API_TOKEN_SCOPES};
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::models::{ApiToken, CreateApiTokenPayload, CreatedApiTokenResponse, NewApiToken};
+use crate::schema::api_tokens::{self, dsl::*};
+use actix_web::{delete, get, post, web, HttpResponse, Result as ActixResult};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use serde_json::json;
+use uuid::Uuid;
+
+// Nombre de caractères du token en clair conservés pour l'identifier (préfixe "opt_" inclus)
+const TOKEN_DISPLAY_PREFIX_LEN: usize = 12;
+
+// === POST /tokens ===
+#[post("")]
+pub async fn create_api_token_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<CreateApiTokenPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let payload = payload.into_inner();
+
+    log::info!(
+        "User {} creating API token '{}' with scopes {:?}",
+        user_uuid,
+        payload.name,
+        payload.scopes
+    );
+
+    if payload.name.trim().is_empty() {
+        return Err(ServiceError::BadRequest(
+            "Token name cannot be empty.".to_string(),
+        ));
+    }
+    if payload.scopes.is_empty() {
+        return Err(ServiceError::BadRequest(
+            "At least one scope is required.".to_string(),
+        ));
+    }
+    if let Some(unknown_scope) = payload
+        .scopes
+        .iter()
+        .find(|scope| !API_TOKEN_SCOPES.contains(&scope.as_str()))
+    {
+        return Err(ServiceError::BadRequest(format!(
+            "Unknown scope: {}. Supported: {}",
+            unknown_scope,
+            API_TOKEN_SCOPES.join(", ")
+        )));
+    }
+    if payload
+        .expires_at
+        .is_some_and(|expiry| expiry <= Utc::now())
+    {
+        return Err(ServiceError::BadRequest(
+            "expires_at must be in the future.".to_string(),
+        ));
+    }
+
+    let mut requested_scopes = payload.scopes;
+    requested_scopes.sort();
+    requested_scopes.dedup();
+
+    let (plain_token, hashed_token) = generate_api_token();
+    let new_token_data = NewApiToken {
+        user_id: user_uuid,
+        name: payload.name.trim().to_string(),
+        token_prefix: plain_token[..TOKEN_DISPLAY_PREFIX_LEN].to_string(),
+        token_hash: hashed_token,
+        scopes: requested_scopes,
+        expires_at: payload.expires_at,
+    };
+
+    let created_token = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            diesel::insert_into(api_tokens::table)
+                .values(&new_token_data)
+                .get_result::<ApiToken>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (create_api_token): {:?}", e);
+        ServiceError::InternalServerError("Error processing create_api_token request".to_string())
+    })??;
+
+    log::info!(
+        "API token {} created for user {}",
+        created_token.id,
+        user_uuid
+    );
+    Ok(HttpResponse::Created().json(CreatedApiTokenResponse {
+        api_token: created_token,
+        token: plain_token,
+    }))
+}
+
+// === GET /tokens ===
+#[get("")]
+pub async fn list_api_tokens_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!("Listing API tokens for user: {}", user_uuid);
+
+    let token_list = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            api_tokens
+                .filter(user_id.eq(user_uuid))
+                .order(created_at.desc())
+                .select(ApiToken::as_select())
+                .load::<ApiToken>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_api_tokens): {:?}", e);
+        ServiceError::InternalServerError("Error processing list_api_tokens request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(token_list))
+}
+
+// === DELETE /tokens/{token_id_path} ===
+// Révoque le token sans supprimer la ligne, pour garder l'historique d'utilisation.
+#[delete("/{token_id_path}")]
+pub async fn revoke_api_token_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    token_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let token_to_revoke_id = token_id_path.into_inner();
+
+    log::info!(
+        "User {} revoking API token {}",
+        user_uuid,
+        token_to_revoke_id
+    );
+
+    let num_revoked = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            diesel::update(
+                api_tokens
+                    .filter(user_id.eq(user_uuid))
+                    .filter(id.eq(token_to_revoke_id))
+                    .filter(revoked_at.is_null()),
+            )
+            .set(revoked_at.eq(Some(Utc::now())))
+            .execute(conn)
+            .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (revoke_api_token): {:?}", e);
+        ServiceError::InternalServerError("Error processing revoke_api_token request".to_string())
+    })??;
+
+    if num_revoked > 0 {
+        Ok(HttpResponse::Ok().json(json!({
+            "status": "success",
+            "message": format!("API token with id {} revoked successfully", token_to_revoke_id)
+        })))
+    } else {
+        Err(ServiceError::NotFound(format!(
+            "API token with id {} not found, already revoked or not owned by user",
+            token_to_revoke_id
+        )))
+    }
+}
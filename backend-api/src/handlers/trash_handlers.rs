@@ -0,0 +1,281 @@
+// OptiTask/backend-api/src/handlers/trash_handlers.rs
+// Corbeille des tâches et projets supprimés : consultation, restauration et purge définitive.
+// La purge automatique après la rétention est faite par jobs::spawn_trash_purger.
+use crate::auth_utils::AuthenticatedUser;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::jobs::trash_retention_days;
+use crate::models::{Project, Task, TrashResponse, TrashedProject, TrashedTask};
+use crate::schema::{projects, tasks};
+use crate::task_tree::descendant_ids;
+use actix_web::{delete, get, post, web, HttpResponse, Result as ActixResult};
+use chrono::{Duration, NaiveDateTime};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+
+fn load_trashed_task(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    task_id: Uuid,
+) -> Result<Task, ServiceError> {
+    tasks::table
+        .filter(tasks::id.eq(task_id))
+        .filter(tasks::user_id.eq(user_uuid))
+        .filter(tasks::deleted_at.is_not_null())
+        .select(Task::as_select())
+        .first::<Task>(conn)
+        .optional()?
+        .ok_or_else(|| {
+            ServiceError::NotFound(format!("Task with id {} not found in trash", task_id))
+        })
+}
+
+fn load_trashed_project(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    project_id: Uuid,
+) -> Result<Project, ServiceError> {
+    projects::table
+        .filter(projects::id.eq(project_id))
+        .filter(projects::user_id.eq(user_uuid))
+        .filter(projects::deleted_at.is_not_null())
+        .select(Project::as_select())
+        .first::<Project>(conn)
+        .optional()?
+        .ok_or_else(|| {
+            ServiceError::NotFound(format!("Project with id {} not found in trash", project_id))
+        })
+}
+
+// === GET /trash ===
+#[get("")]
+pub async fn list_trash_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let retention_days = trash_retention_days();
+    let purge_at = move |deleted_at: Option<NaiveDateTime>| {
+        deleted_at.map(|deleted_at| deleted_at + Duration::days(retention_days))
+    };
+
+    let trash = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let trashed_projects = projects::table
+                .filter(projects::user_id.eq(user_uuid))
+                .filter(projects::deleted_at.is_not_null())
+                .order(projects::deleted_at.desc())
+                .select(Project::as_select())
+                .load::<Project>(conn)?;
+            let trashed_tasks = tasks::table
+                .filter(tasks::user_id.eq(user_uuid))
+                .filter(tasks::deleted_at.is_not_null())
+                .order(tasks::deleted_at.desc())
+                .select(Task::as_select())
+                .load::<Task>(conn)?;
+
+            Ok(TrashResponse {
+                retention_days,
+                projects: trashed_projects
+                    .into_iter()
+                    .map(|project| TrashedProject {
+                        purge_at: purge_at(project.deleted_at),
+                        project,
+                    })
+                    .collect(),
+                tasks: trashed_tasks
+                    .into_iter()
+                    .map(|task| TrashedTask {
+                        purge_at: purge_at(task.deleted_at),
+                        task,
+                    })
+                    .collect(),
+            })
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (list_trash): {:?}", e);
+        ServiceError::InternalServerError("Error processing list_trash request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(trash))
+}
+
+// === POST /trash/tasks/{task_id}/restore ===
+// Une tâche dont le projet ou la tâche parente est encore dans la corbeille se restaure
+// avec eux. Les sous-tâches supprimées en même temps que la tâche reviennent avec elle.
+#[post("/tasks/{task_id_path}/restore")]
+pub async fn restore_task_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    task_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let task_to_restore_id = task_id_path.into_inner();
+
+    let restored_task = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let task = load_trashed_task(conn, user_uuid, task_to_restore_id)?;
+            if let Some(task_project_id) = task.project_id {
+                let project_trashed = projects::table
+                    .filter(projects::id.eq(task_project_id))
+                    .filter(projects::deleted_at.is_not_null())
+                    .select(projects::id)
+                    .first::<Uuid>(conn)
+                    .optional()?
+                    .is_some();
+                if project_trashed {
+                    return Err(ServiceError::Conflict(format!(
+                        "Task {} belongs to project {} which is in the trash; restore the project first.",
+                        task_to_restore_id, task_project_id
+                    )));
+                }
+            }
+            if let Some(parent_id) = task.parent_task_id {
+                let parent_trashed = tasks::table
+                    .filter(tasks::id.eq(parent_id))
+                    .filter(tasks::deleted_at.is_not_null())
+                    .select(tasks::id)
+                    .first::<Uuid>(conn)
+                    .optional()?
+                    .is_some();
+                if parent_trashed {
+                    return Err(ServiceError::Conflict(format!(
+                        "Task {} is a subtask of task {} which is in the trash; restore the parent first.",
+                        task_to_restore_id, parent_id
+                    )));
+                }
+            }
+            let subtask_ids = descendant_ids(conn, task_to_restore_id, true)?;
+            diesel::update(
+                tasks::table
+                    .filter(tasks::id.eq_any(&subtask_ids))
+                    .filter(tasks::deleted_at.eq(task.deleted_at)),
+            )
+            .set(tasks::deleted_at.eq(None::<NaiveDateTime>))
+            .execute(conn)?;
+            diesel::update(tasks::table.filter(tasks::id.eq(task_to_restore_id)))
+                .set(tasks::deleted_at.eq(None::<NaiveDateTime>))
+                .get_result::<Task>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (restore_task): {:?}", e);
+        ServiceError::InternalServerError("Error processing restore_task request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(restored_task))
+}
+
+// === POST /trash/projects/{project_id}/restore ===
+// Restaure aussi les tâches supprimées avec le projet (même deleted_at), pas celles
+// supprimées avant lui.
+#[post("/projects/{project_id_path}/restore")]
+pub async fn restore_project_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    project_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let project_to_restore_id = project_id_path.into_inner();
+
+    let restored_project = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let project = load_trashed_project(conn, user_uuid, project_to_restore_id)?;
+            diesel::update(
+                tasks::table
+                    .filter(tasks::user_id.eq(user_uuid))
+                    .filter(tasks::project_id.eq(project_to_restore_id))
+                    .filter(tasks::deleted_at.eq(project.deleted_at)),
+            )
+            .set(tasks::deleted_at.eq(None::<NaiveDateTime>))
+            .execute(conn)?;
+            diesel::update(projects::table.filter(projects::id.eq(project_to_restore_id)))
+                .set(projects::deleted_at.eq(None::<NaiveDateTime>))
+                .get_result::<Project>(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (restore_project): {:?}", e);
+        ServiceError::InternalServerError("Error processing restore_project request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(restored_project))
+}
+
+// === DELETE /trash/tasks/{task_id} ===
+// Purge définitive ; les time entries de la tâche passent sur son projet.
+#[delete("/tasks/{task_id_path}")]
+pub async fn purge_task_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    task_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let task_to_purge_id = task_id_path.into_inner();
+
+    web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            load_trashed_task(conn, user_uuid, task_to_purge_id)?;
+            diesel::delete(tasks::table.filter(tasks::id.eq(task_to_purge_id)))
+                .execute(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (purge_task): {:?}", e);
+        ServiceError::InternalServerError("Error processing purge_task request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": "success",
+        "message": format!("Task with id {} permanently deleted", task_to_purge_id)
+    })))
+}
+
+// === DELETE /trash/projects/{project_id} ===
+// Purge définitive du projet et de ses tâches dans la corbeille ; leurs time entries
+// sont conservées sans projet.
+#[delete("/projects/{project_id_path}")]
+pub async fn purge_project_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    project_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let project_to_purge_id = project_id_path.into_inner();
+
+    web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            load_trashed_project(conn, user_uuid, project_to_purge_id)?;
+            diesel::delete(
+                tasks::table
+                    .filter(tasks::user_id.eq(user_uuid))
+                    .filter(tasks::project_id.eq(project_to_purge_id))
+                    .filter(tasks::deleted_at.is_not_null()),
+            )
+            .execute(conn)?;
+            diesel::delete(projects::table.filter(projects::id.eq(project_to_purge_id)))
+                .execute(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (purge_project): {:?}", e);
+        ServiceError::InternalServerError("Error processing purge_project request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": "success",
+        "message": format!("Project with id {} permanently deleted", project_to_purge_id)
+    })))
+}
@@ -0,0 +1,226 @@
+// OptiTask/backend-api/src/handlers/user_handlers.rs
+use crate::auth_utils::AuthenticatedUser;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::models::{
+    MeResponse, NewUser, NewUserSettings, UpdateMePayload, UpdateUserChangeset,
+    UpdateUserSettingsChangeset, User, UserSettings,
+};
+use crate::schema::{user_settings, users};
+use actix_web::{get, post, put, web, HttpResponse, Result as ActixResult};
+use chrono::Utc;
+use chrono_tz::Tz;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use serde_json::json;
+use uuid::Uuid;
+
+// Crée à la volée le profil et les réglages par défaut d'un utilisateur
+// authentifié qui n'a encore jamais appelé /me.
+fn ensure_user_rows(conn: &mut PgConnection, user_uuid: Uuid) -> Result<(), ServiceError> {
+    diesel::insert_into(users::table)
+        .values(&NewUser { id: user_uuid })
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    diesel::insert_into(user_settings::table)
+        .values(&NewUserSettings { user_id: user_uuid })
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    Ok(())
+}
+
+// Réglages de l'utilisateur (valeurs par défaut si jamais configurés).
+// Utilisé par /me mais aussi par les analytics et les timers.
+pub fn load_user_settings(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+) -> Result<UserSettings, ServiceError> {
+    ensure_user_rows(conn, user_uuid)?;
+    user_settings::table
+        .filter(user_settings::user_id.eq(user_uuid))
+        .select(UserSettings::as_select())
+        .first::<UserSettings>(conn)
+        .map_err(ServiceError::from)
+}
+
+fn load_me(conn: &mut PgConnection, user_uuid: Uuid) -> Result<MeResponse, ServiceError> {
+    let settings = load_user_settings(conn, user_uuid)?;
+    let user = users::table
+        .filter(users::id.eq(user_uuid))
+        .select(User::as_select())
+        .first::<User>(conn)?;
+    Ok(MeResponse { user, settings })
+}
+
+fn validate_update_me_payload(payload: &UpdateMePayload) -> Result<(), ServiceError> {
+    if let Some(tz_name) = &payload.timezone {
+        tz_name
+            .parse::<Tz>()
+            .map_err(|_| ServiceError::BadRequest(format!("Unknown IANA timezone: {}", tz_name)))?;
+    }
+    if let Some(day) = payload.week_start_day {
+        if !(1..=7).contains(&day) {
+            return Err(ServiceError::BadRequest(
+                "week_start_day must be between 1 (Monday) and 7 (Sunday)".to_string(),
+            ));
+        }
+    }
+    let pomodoro_lengths = [
+        ("pomodoro_work_minutes", payload.pomodoro_work_minutes),
+        (
+            "pomodoro_short_break_minutes",
+            payload.pomodoro_short_break_minutes,
+        ),
+        (
+            "pomodoro_long_break_minutes",
+            payload.pomodoro_long_break_minutes,
+        ),
+        (
+            "pomodoro_cycles_before_long_break",
+            payload.pomodoro_cycles_before_long_break,
+        ),
+    ];
+    for (field, value) in pomodoro_lengths {
+        if value.is_some_and(|v| v <= 0) {
+            return Err(ServiceError::BadRequest(format!(
+                "{} must be greater than 0",
+                field
+            )));
+        }
+    }
+    if payload.max_running_entry_minutes.is_some_and(|v| v <= 0) {
+        return Err(ServiceError::BadRequest(
+            "max_running_entry_minutes must be greater than 0".to_string(),
+        ));
+    }
+    if let Some(policy) = &payload.idle_policy {
+        if !["cap", "last_activity", "flag"].contains(&policy.as_str()) {
+            return Err(ServiceError::BadRequest(format!(
+                "Invalid idle_policy: {}. Supported: cap, last_activity, flag.",
+                policy
+            )));
+        }
+    }
+    Ok(())
+}
+
+// === GET /me ===
+#[get("")]
+pub async fn get_me_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!("Fetching profile and settings for user {}", user_uuid);
+
+    let me = web::block(move || run_as_user(&pool, user_uuid, |conn| load_me(conn, user_uuid)))
+        .await
+        .map_err(|e| {
+            log::error!("Blocking task error (get_me): {:?}", e);
+            ServiceError::InternalServerError("Error processing get_me request".to_string())
+        })??;
+
+    Ok(HttpResponse::Ok().json(me))
+}
+
+// === PUT /me ===
+#[put("")]
+pub async fn update_me_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<UpdateMePayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!(
+        "User {} updating /me with payload: {:?}",
+        user_uuid,
+        payload
+    );
+
+    validate_update_me_payload(&payload)?;
+
+    let now = Some(Utc::now().naive_utc());
+    let user_changes = UpdateUserChangeset {
+        display_name: payload.display_name.clone(),
+        email: payload.email.clone(),
+        updated_at: now,
+    };
+    let settings_changes = UpdateUserSettingsChangeset {
+        timezone: payload.timezone.clone(),
+        week_start_day: payload.week_start_day,
+        pomodoro_work_minutes: payload.pomodoro_work_minutes,
+        pomodoro_short_break_minutes: payload.pomodoro_short_break_minutes,
+        pomodoro_long_break_minutes: payload.pomodoro_long_break_minutes,
+        pomodoro_cycles_before_long_break: payload.pomodoro_cycles_before_long_break,
+        working_hours_start: payload.working_hours_start,
+        working_hours_end: payload.working_hours_end,
+        max_running_entry_minutes: payload.max_running_entry_minutes,
+        idle_policy: payload.idle_policy.clone(),
+        updated_at: now,
+    };
+
+    let me = web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            let current_settings = load_user_settings(conn, user_uuid)?;
+
+            // Les heures de travail peuvent n'être modifiées qu'à moitié : on valide le résultat final
+            let hours_start = settings_changes
+                .working_hours_start
+                .unwrap_or(current_settings.working_hours_start);
+            let hours_end = settings_changes
+                .working_hours_end
+                .unwrap_or(current_settings.working_hours_end);
+            if hours_start >= hours_end {
+                return Err(ServiceError::BadRequest(
+                    "working_hours_start must be before working_hours_end".to_string(),
+                ));
+            }
+
+            diesel::update(users::table.filter(users::id.eq(user_uuid)))
+                .set(&user_changes)
+                .execute(conn)?;
+            diesel::update(user_settings::table.filter(user_settings::user_id.eq(user_uuid)))
+                .set(&settings_changes)
+                .execute(conn)?;
+
+            load_me(conn, user_uuid)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (update_me): {:?}", e);
+        ServiceError::InternalServerError("Error processing update_me request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(me))
+}
+
+// === POST /me/heartbeat ===
+// Signe de vie envoyé périodiquement par les clients ouverts. Sert de "dernière activité"
+// pour la politique 'last_activity' des timers oubliés.
+#[post("/heartbeat")]
+pub async fn heartbeat_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::debug!("Heartbeat from user {}", user_uuid);
+
+    web::block(move || {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_user_rows(conn, user_uuid)?;
+            diesel::update(users::table.filter(users::id.eq(user_uuid)))
+                .set(users::last_active_at.eq(Some(Utc::now())))
+                .execute(conn)
+                .map_err(ServiceError::from)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (heartbeat): {:?}", e);
+        ServiceError::InternalServerError("Error processing heartbeat request".to_string())
+    })??;
+
+    Ok(HttpResponse::Ok().json(json!({ "status": "success" })))
+}
@@ -0,0 +1,130 @@
+// OptiTask/backend-api/src/handlers/workflow_handlers.rs
+// Workflows de statuts : workflow par défaut de l'utilisateur (/workflows/default) et
+// workflow propre à un projet (/projects/{id}/workflow).
+use crate::auth_utils::AuthenticatedUser;
+use crate::db::{run_as_user, DbPool};
+use crate::error_handler::ServiceError;
+use crate::models::{UpdateWorkflowPayload, Workflow};
+use crate::ownership::ensure_project_owned;
+use crate::workflows::{load_workflow, replace_workflow};
+use actix_web::{get, put, web, HttpResponse, Result as ActixResult};
+use uuid::Uuid;
+
+// === GET /workflows/default ===
+// Workflow des tâches sans projet et des projets sans workflow propre
+#[get("/default")]
+pub async fn get_default_workflow_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+
+    let workflow = web::block(move || -> Result<Workflow, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            load_workflow(conn, user_uuid, None)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_default_workflow): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing get_default_workflow request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Ok().json(workflow))
+}
+
+// === PUT /workflows/default ===
+// `statuses` vide : revient au workflow intégré (todo / inprogress / done)
+#[put("/default")]
+pub async fn update_default_workflow_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    payload: web::Json<UpdateWorkflowPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    log::info!(
+        "User {} replacing default workflow: {:?}",
+        user_uuid,
+        payload
+    );
+
+    let workflow = web::block(move || -> Result<Workflow, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            replace_workflow(conn, user_uuid, None, &payload)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (update_default_workflow): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing update_default_workflow request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Ok().json(workflow))
+}
+
+// === GET /projects/{project_id_path}/workflow ===
+// Workflow effectif du projet ("source" indique s'il est propre au projet ou hérité)
+#[get("/{project_id_path}/workflow")]
+pub async fn get_project_workflow_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    project_id_path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let workflow_project_id = project_id_path.into_inner();
+
+    let workflow = web::block(move || -> Result<Workflow, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_project_owned(conn, user_uuid, workflow_project_id)?;
+            load_workflow(conn, user_uuid, Some(workflow_project_id))
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (get_project_workflow): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing get_project_workflow request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Ok().json(workflow))
+}
+
+// === PUT /projects/{project_id_path}/workflow ===
+// `statuses` vide : le projet revient au workflow par défaut de l'utilisateur
+#[put("/{project_id_path}/workflow")]
+pub async fn update_project_workflow_handler(
+    pool: web::Data<DbPool>,
+    authenticated_user: AuthenticatedUser,
+    project_id_path: web::Path<Uuid>,
+    payload: web::Json<UpdateWorkflowPayload>,
+) -> ActixResult<HttpResponse, ServiceError> {
+    let user_uuid = authenticated_user.id;
+    let workflow_project_id = project_id_path.into_inner();
+    log::info!(
+        "User {} replacing workflow of project {}: {:?}",
+        user_uuid,
+        workflow_project_id,
+        payload
+    );
+
+    let workflow = web::block(move || -> Result<Workflow, ServiceError> {
+        run_as_user(&pool, user_uuid, |conn| {
+            ensure_project_owned(conn, user_uuid, workflow_project_id)?;
+            replace_workflow(conn, user_uuid, Some(workflow_project_id), &payload)
+        })
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Blocking task error (update_project_workflow): {:?}", e);
+        ServiceError::InternalServerError(
+            "Error processing update_project_workflow request".to_string(),
+        )
+    })??;
+
+    Ok(HttpResponse::Ok().json(workflow))
+}
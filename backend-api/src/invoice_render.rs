@@ -0,0 +1,466 @@
+// OptiTask/backend-api/src/invoice_render.rs
+// Rendu des factures en HTML et en PDF, entièrement local (aucun service externe).
+// Le PDF est écrit à la main : format A4, polices standard Courier/Courier-Bold
+// (chasse fixe, donc alignements exacts sans table de métriques), encodage WinAnsi.
+
+use crate::models::{Invoice, InvoiceLineItem};
+
+// "1,234.56 EUR"
+pub fn format_amount(cents: i64, currency: &str) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs_cents = cents.unsigned_abs();
+    let units = (abs_cents / 100).to_string();
+    let mut grouped = String::with_capacity(units.len() + units.len() / 3);
+    for (index, digit) in units.chars().enumerate() {
+        if index > 0 && (units.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{}{}.{:02} {}", sign, grouped, abs_cents % 100, currency)
+}
+
+// Heures décimales, ex: 5400 s -> "1.50"
+pub fn format_hours(seconds: i64) -> String {
+    format!("{:.2}", seconds as f64 / 3600.0)
+}
+
+fn rounding_note(invoice: &Invoice) -> Option<String> {
+    if invoice.rounding_increment_minutes <= 0 {
+        return None;
+    }
+    Some(format!(
+        "Time rounded {} to {} minutes per entry.",
+        invoice.rounding_direction, invoice.rounding_increment_minutes
+    ))
+}
+
+fn status_label(invoice: &Invoice) -> &'static str {
+    match invoice.status.as_str() {
+        "sent" => "SENT",
+        "paid" => "PAID",
+        _ => "DRAFT",
+    }
+}
+
+// --- HTML ---
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_html_multiline(text: &str) -> String {
+    text.lines()
+        .map(escape_html)
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+// Document HTML autonome (styles inline), imprimable tel quel
+pub fn render_invoice_html(invoice: &Invoice, line_items: &[InvoiceLineItem]) -> String {
+    let mut rows = String::new();
+    for item in line_items {
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            escape_html(&item.description),
+            format_hours(item.quantity_seconds),
+            format_amount(item.hourly_rate_cents, &invoice.currency),
+            format_amount(item.amount_cents, &invoice.currency)
+        ));
+    }
+
+    let mut client_block = format!("<strong>{}</strong>", escape_html(&invoice.client_name));
+    if let Some(address) = &invoice.client_address {
+        client_block.push_str(&format!("<br>{}", escape_html_multiline(address)));
+    }
+    if let Some(email) = &invoice.client_email {
+        client_block.push_str(&format!("<br>{}", escape_html(email)));
+    }
+
+    let due_line = invoice
+        .due_date
+        .map(|due| format!("<div>Due date: {}</div>", due))
+        .unwrap_or_default();
+    let notes_block = invoice
+        .notes
+        .as_deref()
+        .map(|notes| format!("<p class=\"notes\">{}</p>", escape_html_multiline(notes)))
+        .unwrap_or_default();
+    let rounding_block = rounding_note(invoice)
+        .map(|note| format!("<p class=\"muted\">{}</p>", escape_html(&note)))
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Invoice {number}</title>
+<style>
+body {{ font-family: Helvetica, Arial, sans-serif; color: #222; margin: 40px; }}
+h1 {{ margin: 0 0 4px 0; }}
+.status {{ display: inline-block; padding: 2px 8px; border: 1px solid #888; font-size: 12px; }}
+.header {{ display: flex; justify-content: space-between; margin-bottom: 32px; }}
+table {{ width: 100%; border-collapse: collapse; }}
+th, td {{ padding: 6px 8px; border-bottom: 1px solid #ddd; text-align: left; }}
+.num {{ text-align: right; white-space: nowrap; }}
+.total td {{ font-weight: bold; border-top: 2px solid #222; border-bottom: none; }}
+.muted {{ color: #777; font-size: 12px; }}
+</style>
+</head>
+<body>
+<div class="header">
+<div>
+<h1>Invoice {number}</h1>
+<span class="status">{status}</span>
+<div>Issue date: {issue_date}</div>
+{due_line}
+<div>Period: {period_start} to {period_end}</div>
+</div>
+<div>{client_block}</div>
+</div>
+<table>
+<thead><tr><th>Description</th><th class="num">Hours</th><th class="num">Rate</th><th class="num">Amount</th></tr></thead>
+<tbody>
+{rows}<tr class="total"><td colspan="3">Total</td><td class="num">{total}</td></tr>
+</tbody>
+</table>
+{rounding_block}
+{notes_block}
+</body>
+</html>
+"#,
+        number = escape_html(&invoice.invoice_number),
+        status = status_label(invoice),
+        issue_date = invoice.issue_date,
+        due_line = due_line,
+        period_start = invoice.period_start,
+        period_end = invoice.period_end,
+        client_block = client_block,
+        rows = rows,
+        total = format_amount(invoice.total_cents, &invoice.currency),
+        rounding_block = rounding_block,
+        notes_block = notes_block,
+    )
+}
+
+// --- PDF ---
+
+const PAGE_WIDTH: f32 = 595.0; // A4 en points
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const COURIER_CHAR_WIDTH: f32 = 0.6; // Largeur d'un caractère Courier, en fraction du corps
+const BODY_SIZE: f32 = 9.0;
+const LINE_HEIGHT: f32 = 13.0;
+// Colonnes du tableau (bords droits des colonnes numériques)
+const DESCRIPTION_CHARS: usize = 48;
+const HOURS_RIGHT: f32 = 380.0;
+const RATE_RIGHT: f32 = 465.0;
+const AMOUNT_RIGHT: f32 = PAGE_WIDTH - MARGIN;
+
+// Caractère -> octet WinAnsi, '?' pour ce qui n'est pas représentable
+fn win_ansi_byte(c: char) -> u8 {
+    match c {
+        ' '..='~' => c as u8,
+        '\u{A0}'..='\u{FF}' => c as u32 as u8,
+        '€' => 0x80,
+        '…' => 0x85,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '–' => 0x96,
+        '—' => 0x97,
+        _ => b'?',
+    }
+}
+
+// Chaîne littérale PDF, en ASCII pur (octets hors ASCII en octal)
+fn pdf_string(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('(');
+    for byte in text.chars().map(win_ansi_byte) {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                literal.push('\\');
+                literal.push(byte as char);
+            }
+            0x20..=0x7E => literal.push(byte as char),
+            _ => literal.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    literal.push(')');
+    literal
+}
+
+fn text_width(text: &str, size: f32) -> f32 {
+    text.chars().count() as f32 * size * COURIER_CHAR_WIDTH
+}
+
+// Découpe en lignes d'au plus `width` caractères, sur les espaces si possible
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if current.is_empty() {
+            current = word;
+        } else if current.chars().count() + 1 + word.chars().count() <= width {
+            current.push(' ');
+            current.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut current, word));
+        }
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// Flux de contenu des pages, avec saut de page automatique
+struct PdfPages {
+    pages: Vec<String>,
+    current: String,
+    y: f32,
+}
+
+impl PdfPages {
+    fn new() -> Self {
+        PdfPages {
+            pages: Vec::new(),
+            current: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.pages.push(std::mem::take(&mut self.current));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn text(&mut self, x: f32, bold: bool, size: f32, text: &str) {
+        let font = if bold { "F2" } else { "F1" };
+        self.current.push_str(&format!(
+            "BT /{} {:.1} Tf {:.2} {:.2} Td {} Tj ET\n",
+            font,
+            size,
+            x,
+            self.y,
+            pdf_string(text)
+        ));
+    }
+
+    fn text_right(&mut self, right_x: f32, bold: bool, size: f32, text: &str) {
+        self.text(right_x - text_width(text, size), bold, size, text);
+    }
+
+    fn rule(&mut self) {
+        let rule_y = self.y + LINE_HEIGHT - 3.0;
+        self.current.push_str(&format!(
+            "0.5 w {:.2} {:.2} m {:.2} {:.2} l S\n",
+            MARGIN,
+            rule_y,
+            PAGE_WIDTH - MARGIN,
+            rule_y
+        ));
+    }
+
+    fn advance(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.pages.push(self.current);
+        self.pages
+    }
+}
+
+fn table_header(pages: &mut PdfPages) {
+    pages.ensure_space(LINE_HEIGHT * 2.0);
+    pages.text(MARGIN, true, BODY_SIZE, "Description");
+    pages.text_right(HOURS_RIGHT, true, BODY_SIZE, "Hours");
+    pages.text_right(RATE_RIGHT, true, BODY_SIZE, "Rate");
+    pages.text_right(AMOUNT_RIGHT, true, BODY_SIZE, "Amount");
+    pages.advance(LINE_HEIGHT);
+    pages.rule();
+}
+
+// Assemble le fichier : catalogue, arbre des pages, polices, puis une page et
+// un flux de contenu par page ; la table xref référence les offsets en octets.
+fn assemble_pdf(page_contents: &[String]) -> Vec<u8> {
+    let page_count = page_contents.len();
+    // Objets 1 à 4 fixes, puis (page, contenu) pour chaque page
+    let kids: Vec<String> = (0..page_count)
+        .map(|index| format!("{} 0 R", 5 + index * 2))
+        .collect();
+
+    let mut objects: Vec<String> = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            page_count
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (index, content) in page_contents.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            6 + index * 2
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut output: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(output.len());
+        output.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).as_bytes());
+    }
+
+    let xref_offset = output.len();
+    let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        xref.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    xref.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ));
+    output.extend_from_slice(xref.as_bytes());
+    output
+}
+
+pub fn render_invoice_pdf(invoice: &Invoice, line_items: &[InvoiceLineItem]) -> Vec<u8> {
+    let mut pages = PdfPages::new();
+
+    // En-tête : numéro et statut à gauche, client à droite
+    pages.text(
+        MARGIN,
+        true,
+        18.0,
+        &format!("Invoice {}", invoice.invoice_number),
+    );
+    pages.text_right(AMOUNT_RIGHT, true, 10.0, status_label(invoice));
+    pages.advance(28.0);
+
+    let mut left_lines = vec![
+        format!("Issue date: {}", invoice.issue_date),
+        format!("Period: {} to {}", invoice.period_start, invoice.period_end),
+    ];
+    if let Some(due) = invoice.due_date {
+        left_lines.insert(1, format!("Due date: {}", due));
+    }
+    let mut client_lines = vec![invoice.client_name.clone()];
+    if let Some(address) = &invoice.client_address {
+        client_lines.extend(address.lines().map(str::to_string));
+    }
+    if let Some(email) = &invoice.client_email {
+        client_lines.push(email.clone());
+    }
+    for row in 0..left_lines.len().max(client_lines.len()) {
+        pages.ensure_space(LINE_HEIGHT);
+        if let Some(line) = left_lines.get(row) {
+            pages.text(MARGIN, false, BODY_SIZE, line);
+        }
+        if let Some(line) = client_lines.get(row) {
+            pages.text(320.0, row == 0, BODY_SIZE, line);
+        }
+        pages.advance(LINE_HEIGHT);
+    }
+    pages.advance(LINE_HEIGHT * 2.0);
+
+    // Lignes de facture ; l'en-tête du tableau est répété sur chaque nouvelle page
+    table_header(&mut pages);
+    for item in line_items {
+        let description_lines = wrap_text(&item.description, DESCRIPTION_CHARS);
+        let needed = LINE_HEIGHT * description_lines.len() as f32;
+        if pages.y - needed < MARGIN {
+            pages.ensure_space(needed + LINE_HEIGHT * 2.0);
+            table_header(&mut pages);
+        }
+        pages.text(MARGIN, false, BODY_SIZE, &description_lines[0]);
+        pages.text_right(
+            HOURS_RIGHT,
+            false,
+            BODY_SIZE,
+            &format_hours(item.quantity_seconds),
+        );
+        pages.text_right(
+            RATE_RIGHT,
+            false,
+            BODY_SIZE,
+            &format_amount(item.hourly_rate_cents, &invoice.currency),
+        );
+        pages.text_right(
+            AMOUNT_RIGHT,
+            false,
+            BODY_SIZE,
+            &format_amount(item.amount_cents, &invoice.currency),
+        );
+        for continuation in &description_lines[1..] {
+            pages.advance(LINE_HEIGHT);
+            pages.text(MARGIN, false, BODY_SIZE, continuation);
+        }
+        pages.advance(LINE_HEIGHT);
+    }
+
+    pages.ensure_space(LINE_HEIGHT * 2.0);
+    pages.rule();
+    pages.text(MARGIN, true, 10.0, "Total");
+    pages.text_right(
+        AMOUNT_RIGHT,
+        true,
+        10.0,
+        &format_amount(invoice.total_cents, &invoice.currency),
+    );
+    pages.advance(LINE_HEIGHT * 2.0);
+
+    let mut footer_lines: Vec<String> = Vec::new();
+    if let Some(note) = rounding_note(invoice) {
+        footer_lines.push(note);
+    }
+    if let Some(notes) = &invoice.notes {
+        for paragraph in notes.lines() {
+            footer_lines.extend(wrap_text(paragraph, 90));
+        }
+    }
+    for line in footer_lines {
+        pages.ensure_space(LINE_HEIGHT);
+        pages.text(MARGIN, false, BODY_SIZE, &line);
+        pages.advance(LINE_HEIGHT);
+    }
+
+    assemble_pdf(&pages.finish())
+}
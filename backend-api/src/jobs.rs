@@ -0,0 +1,395 @@
+// OptiTask/backend-api/src/jobs.rs
+// Tâches de fond lancées au démarrage du serveur.
+// Elles tournent avec le rôle de connexion du backend (pas sous l'identité d'un
+// utilisateur) : elles traitent les données de tous les utilisateurs.
+
+use crate::db::DbPool;
+use crate::error_handler::ServiceError;
+use crate::models::Task;
+use crate::recurrence::spawn_next_occurrence;
+use crate::schema::tasks;
+use actix_web::rt::{spawn, time};
+use actix_web::web;
+use chrono::{Duration, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{Date, Timestamptz, Uuid as DieselUuid};
+use std::env;
+use std::time::Duration as StdDuration;
+use uuid::Uuid;
+
+const DEFAULT_SWEEP_INTERVAL_SECONDS: u64 = 300;
+const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+const DEFAULT_TRASH_PURGE_INTERVAL_SECONDS: u64 = 3600;
+const DEFAULT_RECURRENCE_INTERVAL_SECONDS: u64 = 3600;
+
+#[derive(QueryableByName, Debug)]
+struct OverdueOccurrence {
+    #[diesel(sql_type = DieselUuid)]
+    id: Uuid,
+    #[diesel(sql_type = Date)]
+    local_today: NaiveDate,
+}
+
+// Ferme ou annote les timers ouverts depuis plus que le maximum de l'utilisateur
+// (`user_settings.max_running_entry_minutes`, 600 par défaut), selon son `idle_policy` :
+// - 'cap' : end_time = start_time + maximum ;
+// - 'last_activity' : end_time = dernière activité connue après le début (heartbeat,
+//   modification de tâche ou d'une autre entrée, usage d'un token), sinon comme 'cap' ;
+// - 'flag' : l'entrée reste ouverte, seule l'annotation est posée.
+// Une phase de travail Pomodoro ne dépasse jamais la durée de travail de sa session.
+// Les entrées d'une semaine approuvée sont verrouillées et laissées de côté.
+// Chaque entrée traitée reçoit `auto_stop_reason` et `auto_stopped_at` et n'est traitée qu'une fois.
+pub fn sweep_forgotten_timers(pool: &DbPool) -> Result<usize, ServiceError> {
+    let mut conn = pool.get()?;
+
+    let affected = sql_query(
+        "WITH candidates AS ( \
+            SELECT te.id, te.start_time, \
+                COALESCE(s.idle_policy, 'cap') AS idle_policy, \
+                LEAST( \
+                    te.start_time + make_interval(mins => COALESCE(s.max_running_entry_minutes, 600)), \
+                    te.start_time + make_interval(mins => ps.work_minutes) \
+                ) AS cap_at, \
+                ps.id IS NOT NULL AS is_pomodoro_phase, \
+                GREATEST( \
+                    u.last_active_at, \
+                    (SELECT MAX(t.updated_at) FROM tasks t WHERE t.user_id = te.user_id), \
+                    (SELECT MAX(o.updated_at) FROM time_entries o WHERE o.user_id = te.user_id AND o.id <> te.id), \
+                    (SELECT MAX(a.last_used_at) FROM api_tokens a WHERE a.user_id = te.user_id) \
+                ) AS last_activity_at \
+            FROM time_entries te \
+            LEFT JOIN user_settings s ON s.user_id = te.user_id \
+            LEFT JOIN users u ON u.id = te.user_id \
+            LEFT JOIN pomodoro_sessions ps ON ps.id = te.pomodoro_session_id \
+            WHERE te.end_time IS NULL \
+            AND te.auto_stop_reason IS NULL \
+            AND LEAST( \
+                te.start_time + make_interval(mins => COALESCE(s.max_running_entry_minutes, 600)), \
+                te.start_time + make_interval(mins => ps.work_minutes) \
+            ) <= NOW() \
+            AND time_entry_in_approved_week(te.user_id, te.start_time) IS NULL \
+        ), decisions AS ( \
+            SELECT id, start_time, \
+                CASE \
+                    WHEN is_pomodoro_phase THEN 'capped_at_max' \
+                    WHEN idle_policy = 'flag' THEN 'flagged_long_running' \
+                    WHEN idle_policy = 'last_activity' \
+                        AND last_activity_at > start_time AND last_activity_at < cap_at \
+                        THEN 'cut_at_last_activity' \
+                    ELSE 'capped_at_max' \
+                END AS reason, \
+                CASE \
+                    WHEN is_pomodoro_phase THEN cap_at \
+                    WHEN idle_policy = 'flag' THEN NULL \
+                    WHEN idle_policy = 'last_activity' \
+                        AND last_activity_at > start_time AND last_activity_at < cap_at \
+                        THEN last_activity_at \
+                    ELSE cap_at \
+                END AS cut_at \
+            FROM candidates \
+        ) \
+        UPDATE time_entries te \
+        SET end_time = d.cut_at, \
+            duration_seconds = EXTRACT(EPOCH FROM (d.cut_at - d.start_time))::INTEGER, \
+            auto_stop_reason = d.reason, \
+            auto_stopped_at = NOW() \
+        FROM decisions d \
+        WHERE te.id = d.id",
+    )
+    .execute(&mut conn)?;
+
+    Ok(affected)
+}
+
+// Lance le sweeper des timers oubliés toutes les RUNNING_ENTRY_SWEEP_INTERVAL_SECONDS
+// secondes (300 par défaut).
+pub fn spawn_forgotten_timer_sweeper(pool: DbPool) {
+    let interval_seconds = env::var("RUNNING_ENTRY_SWEEP_INTERVAL_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(DEFAULT_SWEEP_INTERVAL_SECONDS);
+    log::info!(
+        "Forgotten timer sweeper running every {} seconds",
+        interval_seconds
+    );
+
+    spawn(async move {
+        let mut ticker = time::interval(StdDuration::from_secs(interval_seconds));
+        loop {
+            ticker.tick().await;
+            let pool = pool.clone();
+            match web::block(move || sweep_forgotten_timers(&pool)).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(affected)) => {
+                    log::info!("Auto-stopped or flagged {} forgotten timers", affected)
+                }
+                Ok(Err(e)) => log::error!("Forgotten timer sweep failed: {:?}", e),
+                Err(e) => log::error!("Blocking task error (sweep_forgotten_timers): {:?}", e),
+            }
+        }
+    });
+}
+
+// Durée de conservation des tâches et projets dans la corbeille : TRASH_RETENTION_DAYS
+// jours (30 par défaut).
+pub fn trash_retention_days() -> i64 {
+    env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+}
+
+// Purge définitivement les tâches et projets restés dans la corbeille au-delà de la
+// rétention. Les tâches d'un projet purgé partent avec lui ; les time entries sont
+// conservées (détachées par le trigger keep_time_entries_on_task_delete et les clés
+// étrangères ON DELETE SET NULL).
+pub fn purge_expired_trash(pool: &DbPool) -> Result<usize, ServiceError> {
+    let mut conn = pool.get()?;
+    let cutoff = Utc::now() - Duration::days(trash_retention_days());
+
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        let purged_tasks = sql_query(
+            "DELETE FROM tasks t \
+             WHERE t.deleted_at IS NOT NULL \
+             AND (t.deleted_at <= $1 OR EXISTS ( \
+                SELECT 1 FROM projects p \
+                WHERE p.id = t.project_id AND p.deleted_at <= $1))",
+        )
+        .bind::<Timestamptz, _>(cutoff)
+        .execute(conn)?;
+        let purged_projects = sql_query("DELETE FROM projects WHERE deleted_at <= $1")
+            .bind::<Timestamptz, _>(cutoff)
+            .execute(conn)?;
+        Ok(purged_tasks + purged_projects)
+    })
+}
+
+// Lance la purge de la corbeille toutes les TRASH_PURGE_INTERVAL_SECONDS secondes
+// (3600 par défaut).
+pub fn spawn_trash_purger(pool: DbPool) {
+    let interval_seconds = env::var("TRASH_PURGE_INTERVAL_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(DEFAULT_TRASH_PURGE_INTERVAL_SECONDS);
+    log::info!(
+        "Trash purger running every {} seconds (retention: {} days)",
+        interval_seconds,
+        trash_retention_days()
+    );
+
+    spawn(async move {
+        let mut ticker = time::interval(StdDuration::from_secs(interval_seconds));
+        loop {
+            ticker.tick().await;
+            let pool = pool.clone();
+            match web::block(move || purge_expired_trash(&pool)).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(purged)) => log::info!("Purged {} expired trash items", purged),
+                Ok(Err(e)) => log::error!("Trash purge failed: {:?}", e),
+                Err(e) => log::error!("Blocking task error (purge_expired_trash): {:?}", e),
+            }
+        }
+    });
+}
+
+// Fait avancer les séries récurrentes dont la dernière occurrence a dépassé sa due_date
+// (jour local de l'utilisateur) sans être terminée : l'occurrence suivante est créée à la
+// première date de la règle à partir d'aujourd'hui. L'occurrence en retard reste ouverte.
+pub fn generate_due_recurrences(pool: &DbPool) -> Result<usize, ServiceError> {
+    let mut conn = pool.get()?;
+
+    let overdue = sql_query(
+        "SELECT t.id, (NOW() AT TIME ZONE COALESCE(s.timezone, 'UTC'))::DATE AS local_today \
+         FROM tasks t \
+         LEFT JOIN user_settings s ON s.user_id = t.user_id \
+         WHERE t.recurrence_rule IS NOT NULL \
+         AND t.deleted_at IS NULL \
+         AND t.status_category <> 'done' \
+         AND t.due_date < (NOW() AT TIME ZONE COALESCE(s.timezone, 'UTC'))::DATE \
+         AND NOT EXISTS ( \
+            SELECT 1 FROM tasks n \
+            WHERE n.recurrence_series_id = COALESCE(t.recurrence_series_id, t.id) \
+            AND n.recurrence_index > t.recurrence_index)",
+    )
+    .load::<OverdueOccurrence>(&mut conn)?;
+
+    let mut generated = 0;
+    for occurrence in overdue {
+        let created = conn.transaction::<_, ServiceError, _>(|conn| {
+            let task = tasks::table
+                .filter(tasks::id.eq(occurrence.id))
+                .select(Task::as_select())
+                .first::<Task>(conn)?;
+            spawn_next_occurrence(conn, &task, Some(occurrence.local_today))
+        });
+        match created {
+            Ok(Some(_)) => generated += 1,
+            Ok(None) => {}
+            // Une règle invalide ne bloque pas les autres séries
+            Err(e) => log::error!(
+                "Recurrence generation failed for task {}: {:?}",
+                occurrence.id,
+                e
+            ),
+        }
+    }
+    Ok(generated)
+}
+
+// Lance la génération planifiée des occurrences toutes les RECURRENCE_INTERVAL_SECONDS
+// secondes (3600 par défaut).
+pub fn spawn_recurrence_scheduler(pool: DbPool) {
+    let interval_seconds = env::var("RECURRENCE_INTERVAL_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(DEFAULT_RECURRENCE_INTERVAL_SECONDS);
+    log::info!(
+        "Recurrence scheduler running every {} seconds",
+        interval_seconds
+    );
+
+    spawn(async move {
+        let mut ticker = time::interval(StdDuration::from_secs(interval_seconds));
+        loop {
+            ticker.tick().await;
+            let pool = pool.clone();
+            match web::block(move || generate_due_recurrences(&pool)).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(generated)) => {
+                    log::info!("Generated {} recurring task occurrences", generated)
+                }
+                Ok(Err(e)) => log::error!("Recurrence generation failed: {:?}", e),
+                Err(e) => log::error!("Blocking task error (generate_due_recurrences): {:?}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::user_handlers::load_user_settings;
+    use crate::models::NewTimesheet;
+    use crate::schema::{pomodoro_sessions, time_entries, timesheets};
+    use crate::test_support::{seed_task, test_pool};
+    use crate::timesheets::IsoWeek;
+    use chrono::DateTime;
+
+    // Un timer oublié dans une semaine approuvée (état d'avant le refus d'approbation) ne
+    // bloque pas le balayage des autres utilisateurs
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn sweep_skips_entries_of_approved_weeks() {
+        let pool = test_pool();
+        let mut conn = pool.get().expect("Failed to get test connection");
+        let (locked_owner, other_owner, manager) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        for user in [locked_owner, other_owner, manager] {
+            load_user_settings(&mut conn, user).expect("Failed to create user");
+        }
+        let started: DateTime<Utc> = Utc::now() - Duration::hours(20);
+        let mut running_entry = |owner: Uuid| -> Uuid {
+            let task = seed_task(&mut conn, owner, None);
+            diesel::insert_into(time_entries::table)
+                .values((
+                    time_entries::user_id.eq(owner),
+                    time_entries::task_id.eq(Some(task)),
+                    time_entries::start_time.eq(started),
+                ))
+                .returning(time_entries::id)
+                .get_result::<Uuid>(&mut conn)
+                .expect("Failed to seed running entry")
+        };
+        let (locked_entry, other_entry) = (running_entry(locked_owner), running_entry(other_owner));
+        let week = IsoWeek::containing(started.date_naive());
+        diesel::insert_into(timesheets::table)
+            .values(&NewTimesheet {
+                user_id: locked_owner,
+                approver_id: manager,
+                iso_year: week.year,
+                iso_week: week.week,
+                week_start: week.start,
+                week_end: week.end,
+                status: "approved".to_string(),
+                total_seconds: 0,
+                submit_comment: None,
+                submitted_at: Some(Utc::now()),
+            })
+            .execute(&mut conn)
+            .expect("Failed to approve timesheet");
+        drop(conn);
+
+        sweep_forgotten_timers(&pool).expect("Sweep failed");
+
+        let mut conn = pool.get().expect("Failed to get test connection");
+        let reason = |conn: &mut PgConnection, entry: Uuid| {
+            time_entries::table
+                .find(entry)
+                .select(time_entries::auto_stop_reason)
+                .first::<Option<String>>(conn)
+                .expect("Failed to load time entry")
+        };
+        assert_eq!(reason(&mut conn, locked_entry), None);
+        assert_eq!(
+            reason(&mut conn, other_entry).as_deref(),
+            Some("capped_at_max")
+        );
+    }
+
+    // Une phase de travail oubliée est arrêtée à la durée de travail de sa session, sans
+    // attendre le maximum de l'utilisateur (600 minutes par défaut)
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn sweep_caps_pomodoro_phases_at_their_work_length() {
+        let pool = test_pool();
+        let mut conn = pool.get().expect("Failed to get test connection");
+        let owner = Uuid::new_v4();
+        load_user_settings(&mut conn, owner).expect("Failed to create user");
+        let task = seed_task(&mut conn, owner, None);
+        let started = Utc::now() - Duration::hours(1);
+        let session: Uuid = diesel::insert_into(pomodoro_sessions::table)
+            .values((
+                pomodoro_sessions::user_id.eq(owner),
+                pomodoro_sessions::task_id.eq(task),
+                pomodoro_sessions::work_minutes.eq(25),
+                pomodoro_sessions::short_break_minutes.eq(5),
+                pomodoro_sessions::long_break_minutes.eq(15),
+                pomodoro_sessions::cycles_before_long_break.eq(4),
+                pomodoro_sessions::phase_duration_seconds.eq(25 * 60),
+                pomodoro_sessions::phase_resumed_at.eq(started),
+            ))
+            .returning(pomodoro_sessions::id)
+            .get_result(&mut conn)
+            .expect("Failed to seed Pomodoro session");
+        let entry: Uuid = diesel::insert_into(time_entries::table)
+            .values((
+                time_entries::user_id.eq(owner),
+                time_entries::task_id.eq(Some(task)),
+                time_entries::start_time.eq(started),
+                time_entries::is_pomodoro_session.eq(true),
+                time_entries::pomodoro_session_id.eq(Some(session)),
+            ))
+            .returning(time_entries::id)
+            .get_result(&mut conn)
+            .expect("Failed to seed Pomodoro entry");
+        drop(conn);
+
+        sweep_forgotten_timers(&pool).expect("Sweep failed");
+
+        let mut conn = pool.get().expect("Failed to get test connection");
+        let (reason, seconds) = time_entries::table
+            .find(entry)
+            .select((
+                time_entries::auto_stop_reason,
+                time_entries::duration_seconds,
+            ))
+            .first::<(Option<String>, Option<i32>)>(&mut conn)
+            .expect("Failed to load time entry");
+        assert_eq!(reason.as_deref(), Some("capped_at_max"));
+        assert_eq!(seconds, Some(25 * 60));
+    }
+}
@@ -1,10 +1,23 @@
 // OptiTask/backend-api/src/main.rs
 mod auth_utils;
+mod billing;
 mod db;
 mod error_handler;
 mod handlers;
+mod invoice_render;
+mod jobs;
 mod models;
+mod overlaps;
+mod ownership;
+mod recurrence;
+mod rounding;
 pub mod schema;
+mod task_graph;
+mod task_tree;
+#[cfg(test)]
+mod test_support;
+mod timesheets;
+mod workflows;
 
 // Ajouts pour JsonConfig
 use actix_web::{
@@ -33,12 +46,150 @@ async fn health_check(
     })))
 }
 
+// Routes de l'API, partagées par le serveur et les tests des handlers
+fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/clients")
+            .service(handlers::client_handlers::create_client_handler)
+            .service(handlers::client_handlers::list_clients_handler)
+            .service(handlers::client_handlers::get_client_handler)
+            .service(handlers::client_handlers::update_client_handler)
+            .service(handlers::client_handlers::delete_client_handler),
+    )
+    .service(
+        web::scope("/rates")
+            .service(handlers::rate_handlers::create_hourly_rate_handler)
+            .service(handlers::rate_handlers::list_hourly_rates_handler)
+            .service(handlers::rate_handlers::delete_hourly_rate_handler),
+    )
+    .service(
+        web::scope("/invoices")
+            .service(handlers::invoice_handlers::create_invoice_handler)
+            .service(handlers::invoice_handlers::list_invoices_handler)
+            .service(handlers::invoice_handlers::get_invoice_handler)
+            .service(handlers::invoice_handlers::get_invoice_html_handler)
+            .service(handlers::invoice_handlers::get_invoice_pdf_handler)
+            .service(handlers::invoice_handlers::update_invoice_status_handler)
+            .service(handlers::invoice_handlers::delete_invoice_handler),
+    )
+    .service(
+        web::scope("/projects")
+            .service(handlers::project_handlers::create_project_handler)
+            .service(handlers::project_handlers::list_projects_handler)
+            .service(handlers::project_handlers::get_project_handler)
+            .service(handlers::project_handlers::update_project_handler)
+            .service(handlers::project_handlers::delete_project_handler)
+            .service(handlers::workflow_handlers::get_project_workflow_handler)
+            .service(handlers::workflow_handlers::update_project_workflow_handler)
+            .service(handlers::board_handlers::get_board_handler)
+            .service(handlers::board_handlers::move_board_task_handler),
+    )
+    .service(
+        web::scope("/workflows")
+            .service(handlers::workflow_handlers::get_default_workflow_handler)
+            .service(handlers::workflow_handlers::update_default_workflow_handler),
+    )
+    .service(
+        web::scope("/trash")
+            .service(handlers::trash_handlers::list_trash_handler)
+            .service(handlers::trash_handlers::restore_task_handler)
+            .service(handlers::trash_handlers::restore_project_handler)
+            .service(handlers::trash_handlers::purge_task_handler)
+            .service(handlers::trash_handlers::purge_project_handler),
+    )
+    .service(
+        web::scope("/tasks")
+            .service(handlers::task_handlers::create_task_handler)
+            .service(handlers::task_handlers::list_tasks_handler)
+            .service(handlers::task_handlers::list_subtasks_handler)
+            .service(handlers::task_handlers::list_occurrences_handler)
+            .service(handlers::task_handlers::get_task_handler)
+            .service(handlers::task_handlers::update_task_handler)
+            .service(handlers::task_handlers::delete_task_handler)
+            .service(handlers::task_dependency_handlers::add_task_dependency_handler)
+            .service(handlers::task_dependency_handlers::remove_task_dependency_handler)
+            // Services pour les labels d'une tâche (utilisent le même scope /tasks)
+            .service(handlers::task_label_handlers::add_label_to_task_handler) // POST /tasks/{taskId}/labels
+            .service(handlers::task_label_handlers::list_labels_for_task_handler) // GET /tasks/{taskId}/labels
+            .service(handlers::task_label_handlers::remove_label_from_task_handler), // DELETE /tasks/{taskId}/labels/{labelId}
+    )
+    .service(
+        web::scope("/labels")
+            .service(handlers::label_handlers::create_label_handler)
+            .service(handlers::label_handlers::list_labels_handler)
+            .service(handlers::label_handlers::get_label_handler)
+            .service(handlers::label_handlers::update_label_handler)
+            .service(handlers::label_handlers::delete_label_handler),
+    )
+    .service(
+        web::scope("/timesheets")
+            // Routes fixes avant "/{timesheet_id_path}"
+            .service(handlers::timesheet_handlers::get_timesheet_week_handler)
+            .service(handlers::timesheet_handlers::submit_timesheet_handler)
+            .service(handlers::timesheet_handlers::list_timesheets_handler)
+            .service(handlers::timesheet_handlers::get_timesheet_handler)
+            .service(handlers::timesheet_handlers::approve_timesheet_handler)
+            .service(handlers::timesheet_handlers::reject_timesheet_handler)
+            .service(handlers::timesheet_handlers::reopen_timesheet_handler),
+    )
+    .service(
+        web::scope("/time-entries")
+            // Routes fixes avant "/{entry_id_path}"
+            .service(handlers::time_entry_handlers::start_timer_handler)
+            .service(handlers::time_entry_handlers::stop_timer_handler)
+            .service(handlers::time_entry_handlers::get_current_timer_handler)
+            .service(handlers::time_entry_handlers::list_time_entry_overlaps_handler)
+            .service(handlers::time_entry_handlers::merge_time_entries_handler)
+            .service(handlers::time_entry_handlers::create_time_entry_handler)
+            .service(handlers::time_entry_handlers::list_time_entries_handler)
+            .service(handlers::time_entry_handlers::get_time_entry_handler)
+            .service(handlers::time_entry_handlers::update_time_entry_handler)
+            .service(handlers::time_entry_handlers::split_time_entry_handler)
+            .service(handlers::time_entry_handlers::delete_time_entry_handler),
+    )
+    .service(
+        web::scope("/pomodoro")
+            .service(handlers::pomodoro_handlers::start_pomodoro_handler)
+            .service(handlers::pomodoro_handlers::get_current_pomodoro_handler)
+            .service(handlers::pomodoro_handlers::pause_pomodoro_handler)
+            .service(handlers::pomodoro_handlers::resume_pomodoro_handler)
+            .service(handlers::pomodoro_handlers::skip_pomodoro_phase_handler)
+            .service(handlers::pomodoro_handlers::stop_pomodoro_handler),
+    )
+    .service(
+        web::scope("/me")
+            .service(handlers::user_handlers::get_me_handler)
+            .service(handlers::user_handlers::update_me_handler)
+            .service(handlers::user_handlers::heartbeat_handler),
+    )
+    .service(
+        web::scope("/tokens")
+            .service(handlers::token_handlers::create_api_token_handler)
+            .service(handlers::token_handlers::list_api_tokens_handler)
+            .service(handlers::token_handlers::revoke_api_token_handler),
+    )
+    .service(
+        web::scope("/analytics") 
+            .service(handlers::analytics_handlers::get_time_by_project_handler)
+            .service(handlers::analytics_handlers::get_time_by_label_handler)
+            .service(handlers::analytics_handlers::get_flow_metrics_handler)
+            .service(handlers::analytics_handlers::get_billable_report_handler)
+            .service(handlers::analytics_handlers::get_entries_report_handler)
+            .service(handlers::analytics_handlers::get_productivity_trend_handler),
+    );
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenvy::dotenv().ok();
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
     let pool = db::establish_connection_pool();
+    let auth_config = web::Data::new(auth_utils::AuthConfig::from_env());
+
+    jobs::spawn_forgotten_timer_sweeper(pool.clone());
+    jobs::spawn_trash_purger(pool.clone());
+    jobs::spawn_recurrence_scheduler(pool.clone());
 
     let server_address =
         std::env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
@@ -80,49 +231,10 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .wrap(actix_web::middleware::Logger::default())
             .app_data(web::Data::new(pool.clone()))
+            .app_data(auth_config.clone())
             .app_data(json_config) // <--- ENREGISTRER LA CONFIGURATION JSON PERSONNALISÉE
             .service(health_check)
-            .service(
-                web::scope("/projects")
-                    .service(handlers::project_handlers::create_project_handler)
-                    .service(handlers::project_handlers::list_projects_handler)
-                    .service(handlers::project_handlers::get_project_handler)
-                    .service(handlers::project_handlers::update_project_handler)
-                    .service(handlers::project_handlers::delete_project_handler),
-            )
-            .service(
-                web::scope("/tasks")
-                    .service(handlers::task_handlers::create_task_handler)
-                    .service(handlers::task_handlers::list_tasks_handler)
-                    .service(handlers::task_handlers::get_task_handler)
-                    .service(handlers::task_handlers::update_task_handler)
-                    .service(handlers::task_handlers::delete_task_handler)
-                    // Services pour les labels d'une tâche (utilisent le même scope /tasks)
-                    .service(handlers::task_label_handlers::add_label_to_task_handler) // POST /tasks/{taskId}/labels
-                    .service(handlers::task_label_handlers::list_labels_for_task_handler) // GET /tasks/{taskId}/labels
-                    .service(handlers::task_label_handlers::remove_label_from_task_handler), // DELETE /tasks/{taskId}/labels/{labelId}
-            )
-            .service(
-                web::scope("/labels")
-                    .service(handlers::label_handlers::create_label_handler)
-                    .service(handlers::label_handlers::list_labels_handler)
-                    .service(handlers::label_handlers::get_label_handler)
-                    .service(handlers::label_handlers::update_label_handler)
-                    .service(handlers::label_handlers::delete_label_handler),
-            )
-            .service(
-                web::scope("/time-entries")
-                    .service(handlers::time_entry_handlers::create_time_entry_handler)
-                    .service(handlers::time_entry_handlers::list_time_entries_handler)
-                    .service(handlers::time_entry_handlers::get_time_entry_handler)
-                    .service(handlers::time_entry_handlers::update_time_entry_handler)
-                    .service(handlers::time_entry_handlers::delete_time_entry_handler),
-            )
-            .service(
-                web::scope("/analytics") 
-                    .service(handlers::analytics_handlers::get_time_by_project_handler)
-                    .service(handlers::analytics_handlers::get_productivity_trend_handler),
-            )
+            .configure(configure_routes)
     })
     .bind(server_address)?
     .run()
@@ -1,7 +1,13 @@
-use crate::schema::{labels, projects, task_labels, tasks, time_entries};
-use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use crate::schema::{
+    api_tokens, clients, hourly_rates, invoice_line_items, invoices, labels,
+    pomodoro_interruptions, pomodoro_sessions, projects, task_dependencies, task_labels, tasks,
+    time_entries, timesheet_lines, timesheets, user_settings, users, workflow_statuses,
+    workflow_transitions,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Deserializer, Serialize}; // Deserializer est nécessaire pour deserialize_with
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use diesel::sql_types::BigInt; // Pour les sommes de durées
@@ -58,6 +64,18 @@ where
     }
 }
 
+// Pour Option<Option<bool>>
+fn deserialize_opt_opt_bool<'de, D>(deserializer: D) -> Result<Option<Option<bool>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<bool>::deserialize(deserializer) {
+        Ok(Some(b)) => Ok(Some(Some(b))),
+        Ok(None) => Ok(Some(None)),
+        Err(e) => Err(e),
+    }
+}
+
 // Pour Option<Option<NaiveDateTime>>
 fn deserialize_opt_opt_naivedatetime<'de, D>(
     deserializer: D,
@@ -88,6 +106,38 @@ where
     }
 }
 
+// --- Client Model ---
+#[derive(Queryable, Selectable, Identifiable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = clients)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Client {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    pub address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Deserialize, Debug)]
+#[diesel(table_name = clients)]
+pub struct NewClient {
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    pub address: Option<String>,
+}
+
+#[derive(AsChangeset, Debug)]
+#[diesel(table_name = clients)]
+pub struct UpdateClientChangeset {
+    pub name: Option<String>,
+    pub email: Option<Option<String>>,
+    pub address: Option<Option<String>>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
 // --- Project Model ---
 #[derive(Queryable, Selectable, Identifiable, Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[diesel(table_name = projects)]
@@ -99,6 +149,13 @@ pub struct Project {
     pub color: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub client_id: Option<Uuid>,
+    pub billable: bool, // Valeur par défaut des tâches et entrées du projet
+    pub rounding_increment_minutes: i32, // Arrondi du temps facturé, 0 : aucun
+    pub rounding_direction: String, // 'up' | 'down' | 'nearest'
+    pub rounding_scope: String, // 'entry' (chaque entrée) | 'day' (total du jour)
+    pub deleted_at: Option<NaiveDateTime>, // Renseigné tant que le projet est dans la corbeille
+    pub approver_id: Option<Uuid>, // Responsable qui approuve les feuilles de temps du projet
 }
 
 #[derive(Insertable, Deserialize, Debug)]
@@ -107,6 +164,12 @@ pub struct NewProject {
     pub user_id: Uuid,
     pub name: String,
     pub color: Option<String>,
+    pub client_id: Option<Uuid>,
+    pub billable: Option<bool>,
+    pub rounding_increment_minutes: Option<i32>,
+    pub rounding_direction: Option<String>,
+    pub rounding_scope: Option<String>,
+    pub approver_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Debug)]
@@ -114,13 +177,233 @@ pub struct NewProject {
 pub struct UpdateProjectChangeset {
     pub name: Option<String>,
     pub color: Option<Option<String>>,
+    pub client_id: Option<Option<Uuid>>,
+    pub approver_id: Option<Option<Uuid>>,
+    pub billable: Option<bool>,
+    pub rounding_increment_minutes: Option<i32>,
+    pub rounding_direction: Option<String>,
+    pub rounding_scope: Option<String>,
     pub updated_at: Option<NaiveDateTime>,
 }
 
+// --- HourlyRate Model ---
+// Taux horaire d'un client, d'un projet ou d'une tâche (exactement une cible),
+// applicable à partir de `effective_from` jusqu'au taux suivant de la même cible.
+#[derive(Queryable, Selectable, Identifiable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = hourly_rates)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct HourlyRate {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub client_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub amount_cents: i64, // Par heure, en centimes de `currency`
+    pub currency: String,  // Code ISO 4217 (ex: "EUR")
+    pub effective_from: NaiveDate,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = hourly_rates)]
+pub struct NewHourlyRate {
+    pub user_id: Uuid,
+    pub client_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub amount_cents: i64,
+    pub currency: String,
+    pub effective_from: NaiveDate,
+}
+
+// --- Invoice Model ---
+// Statut : 'draft' -> 'sent' -> 'paid'. Seul un brouillon peut être supprimé.
+#[derive(Queryable, Selectable, Identifiable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = invoices)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Invoice {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub client_id: Option<Uuid>,
+    pub sequence_number: i32,
+    pub invoice_number: String,
+    pub status: String,
+    // Coordonnées du client copiées à la création
+    pub client_name: String,
+    pub client_email: Option<String>,
+    pub client_address: Option<String>,
+    pub currency: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub issue_date: NaiveDate,
+    pub due_date: Option<NaiveDate>,
+    pub group_by: String,                // 'project' ou 'task'
+    pub rounding_increment_minutes: i32, // 0 : pas d'arrondi
+    pub rounding_direction: String,
+    pub total_cents: i64,
+    pub notes: Option<String>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = invoices)]
+pub struct NewInvoice {
+    pub user_id: Uuid,
+    pub client_id: Option<Uuid>,
+    pub sequence_number: i32,
+    pub invoice_number: String,
+    pub client_name: String,
+    pub client_email: Option<String>,
+    pub client_address: Option<String>,
+    pub currency: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub issue_date: NaiveDate,
+    pub due_date: Option<NaiveDate>,
+    pub group_by: String,
+    pub rounding_increment_minutes: i32,
+    pub rounding_direction: String,
+    pub total_cents: i64,
+    pub notes: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = invoice_line_items)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct InvoiceLineItem {
+    pub id: Uuid,
+    pub invoice_id: Uuid,
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub description: String,
+    pub quantity_seconds: i64, // Temps facturé, après arrondi
+    pub hourly_rate_cents: i64,
+    pub amount_cents: i64,
+    pub position: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = invoice_line_items)]
+pub struct NewInvoiceLineItem {
+    pub invoice_id: Uuid,
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub description: String,
+    pub quantity_seconds: i64,
+    pub hourly_rate_cents: i64,
+    pub amount_cents: i64,
+    pub position: i32,
+}
+
+// Réponse de GET /invoices/{id} : la facture et ses lignes
+#[derive(Serialize, Debug)]
+pub struct InvoiceWithLineItems {
+    #[serde(flatten)]
+    pub invoice: Invoice,
+    pub line_items: Vec<InvoiceLineItem>,
+    pub time_entry_count: i64,
+}
+
+// --- Timesheet Model ---
+// Statut : 'submitted' -> 'approved' | 'rejected' ; 'open' après réouverture.
+// Une semaine approuvée refuse toute modification de ses time entries.
+#[derive(Queryable, Selectable, Identifiable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = timesheets)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Timesheet {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub approver_id: Uuid,
+    pub iso_year: i32,
+    pub iso_week: i32,
+    pub week_start: NaiveDate, // Lundi
+    pub week_end: NaiveDate,   // Dimanche
+    pub status: String,
+    pub total_seconds: i64, // Photo prise à la soumission
+    pub submit_comment: Option<String>,
+    pub review_comment: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = timesheets)]
+pub struct NewTimesheet {
+    pub user_id: Uuid,
+    pub approver_id: Uuid,
+    pub iso_year: i32,
+    pub iso_week: i32,
+    pub week_start: NaiveDate,
+    pub week_end: NaiveDate,
+    pub status: String,
+    pub total_seconds: i64,
+    pub submit_comment: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
+// Temps d'un projet sur un jour de la semaine : calculé à la volée pour l'utilisateur,
+// lu dans timesheet_lines (copie faite à la soumission) pour l'approbateur
+#[derive(Queryable, Selectable, QueryableByName, Serialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = timesheet_lines)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TimesheetDayTotal {
+    pub project_id: Option<Uuid>,
+    pub project_name: String,
+    pub work_date: NaiveDate,
+    pub seconds: i64,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = timesheet_lines)]
+pub struct NewTimesheetLine {
+    pub timesheet_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub project_name: String,
+    pub work_date: NaiveDate,
+    pub seconds: i64,
+}
+
+// Réponse de GET /timesheets/{id} et des transitions : la feuille et son agrégat
+#[derive(Serialize, Debug)]
+pub struct TimesheetWithLines {
+    #[serde(flatten)]
+    pub timesheet: Timesheet,
+    pub lines: Vec<TimesheetDayTotal>,
+}
+
+// Réponse de GET /timesheets/week : agrégat en direct de la semaine et feuille éventuelle
+#[derive(Serialize, Debug)]
+pub struct TimesheetWeekResponse {
+    pub iso_year: i32,
+    pub iso_week: i32,
+    pub week_start: NaiveDate,
+    pub week_end: NaiveDate,
+    pub total_seconds: i64,
+    pub lines: Vec<TimesheetDayTotal>,
+    pub timesheet: Option<Timesheet>,
+}
+
 // --- Task Model (Diesel Queryable) ---
 // Cette struct est pour interagir avec la DB. Elle ne contiendra pas directement les labels.
 #[derive(
-    Queryable, Selectable, Identifiable, Associations, Deserialize, Debug, Clone, PartialEq,
+    Queryable,
+    Selectable,
+    Identifiable,
+    Associations,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
 )]
 #[diesel(table_name = tasks)]
 #[diesel(belongs_to(Project, foreign_key = project_id))]
@@ -137,6 +420,14 @@ pub struct Task {
     pub order: Option<i32>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>, // Renseigné par trigger au passage à 'done'
+    pub billable: Option<bool>,              // None : hérite du projet
+    pub deleted_at: Option<NaiveDateTime>,   // Renseigné tant que la tâche est dans la corbeille
+    pub parent_task_id: Option<Uuid>,        // Tâche parente (même projet), None : tâche racine
+    pub recurrence_rule: Option<String>,     // RRULE normalisée, None : tâche non récurrente
+    pub recurrence_series_id: Option<Uuid>,  // Première occurrence, None : c'est elle
+    pub recurrence_index: i32,               // Rang de l'occurrence dans la série (1..)
+    pub status_category: String,             // Catégorie du statut dans le workflow de la tâche
 }
 
 // === NOUVELLE STRUCT POUR LA RÉPONSE API DE TÂCHE ===
@@ -155,8 +446,24 @@ pub struct TaskApiResponse {
     pub task_order: Option<i32>, // Utiliser un nom de champ différent de Task.order pour éviter confusion
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+    pub billable: Option<bool>,
+    pub parent_task_id: Option<Uuid>,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_series_id: Option<Uuid>,
+    pub recurrence_index: i32,
+    pub status_category: String,
     // Labels associés
     pub labels: Vec<Label>,
+    // Dépendances (tâches hors corbeille) : bloquée tant qu'un bloqueur n'est pas 'done'
+    pub blocked_by: Vec<Uuid>,
+    pub blocking: Vec<Uuid>,
+    pub is_blocked: bool,
+    // Cumuls sur toutes les sous-tâches (tous niveaux, hors corbeille)
+    pub subtasks_done: i64,
+    pub subtasks_total: i64,
+    pub tracked_seconds: i64,       // Temps suivi sur la tâche elle-même
+    pub total_tracked_seconds: i64, // Tâche et sous-tâches
 }
 
 // Helper pour convertir une Task DB en TaskApiResponse (sans labels au début)
@@ -174,7 +481,21 @@ impl From<Task> for TaskApiResponse {
             task_order: task_db.order, // Mapper depuis Task.order
             created_at: task_db.created_at,
             updated_at: task_db.updated_at,
+            completed_at: task_db.completed_at,
+            billable: task_db.billable,
+            parent_task_id: task_db.parent_task_id,
+            recurrence_rule: task_db.recurrence_rule,
+            recurrence_series_id: task_db.recurrence_series_id,
+            recurrence_index: task_db.recurrence_index,
+            status_category: task_db.status_category,
             labels: Vec::new(), // Initialisé vide, sera peuplé dans le handler
+            blocked_by: Vec::new(),
+            blocking: Vec::new(),
+            is_blocked: false,
+            subtasks_done: 0, // Cumuls peuplés dans le handler
+            subtasks_total: 0,
+            tracked_seconds: 0,
+            total_tracked_seconds: 0,
         }
     }
 }
@@ -190,18 +511,28 @@ pub struct NewTask {
     pub due_date: Option<NaiveDate>,
     #[diesel(column_name = task_order)]
     pub order: Option<i32>,
+    pub billable: Option<bool>,
+    pub parent_task_id: Option<Uuid>,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_series_id: Option<Uuid>,
+    pub recurrence_index: Option<i32>,
+    pub status_category: String,
 }
 
 #[derive(AsChangeset, Debug)]
 #[diesel(table_name = tasks)]
 pub struct UpdateTaskChangeset {
     pub project_id: Option<Option<Uuid>>,
+    pub parent_task_id: Option<Option<Uuid>>,
     pub title: Option<String>,
     pub description: Option<Option<String>>,
     pub status: Option<String>,
+    pub status_category: Option<String>,
     pub due_date: Option<Option<NaiveDate>>,
     #[diesel(column_name = task_order)]
     pub order: Option<Option<i32>>,
+    pub billable: Option<Option<bool>>,
+    pub recurrence_rule: Option<Option<String>>,
     pub updated_at: Option<NaiveDateTime>,
 }
 
@@ -262,6 +593,64 @@ pub struct NewTaskLabelAssociation {
     pub label_id: Uuid,
 }
 
+// --- Task dependency : task_id est bloquée par blocked_by_task_id ---
+#[derive(Queryable, Selectable, Serialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = task_dependencies)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub blocked_by_task_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = task_dependencies)]
+pub struct NewTaskDependency {
+    pub task_id: Uuid,
+    pub blocked_by_task_id: Uuid,
+    pub user_id: Uuid,
+}
+
+// --- Workflow de statuts (project_id None : workflow par défaut de l'utilisateur) ---
+#[derive(Queryable, Selectable, Identifiable, Serialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = workflow_statuses)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkflowStatus {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub key: String,
+    pub name: String,
+    pub category: String,
+    pub position: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub wip_limit: Option<i32>, // None : colonne sans limite WIP
+    pub wip_policy: String,     // "warn" ou "reject"
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = workflow_statuses)]
+pub struct NewWorkflowStatus {
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub key: String,
+    pub name: String,
+    pub category: String,
+    pub position: i32,
+    pub wip_limit: Option<i32>,
+    pub wip_policy: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = workflow_transitions)]
+pub struct NewWorkflowTransition {
+    pub from_status_id: Uuid,
+    pub to_status_id: Uuid,
+    pub user_id: Uuid,
+}
+
 // --- TimeEntry Model ---
 #[derive(
     Queryable,
@@ -279,24 +668,45 @@ pub struct NewTaskLabelAssociation {
 pub struct TimeEntry {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub task_id: Uuid,
+    pub task_id: Option<Uuid>, // None : entrée rattachée directement à un projet
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
     pub duration_seconds: Option<i32>,
     pub is_pomodoro_session: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub pomodoro_session_id: Option<Uuid>, // Phase de travail d'une session Pomodoro
+    pub auto_stop_reason: Option<String>,  // Posé par le sweeper des timers oubliés
+    pub auto_stopped_at: Option<DateTime<Utc>>,
+    pub billable: Option<bool>, // None : hérite de la tâche puis du projet
+    pub invoice_id: Option<Uuid>, // Facture qui verrouille l'entrée
+    pub note: Option<String>,   // Ce qui a été fait pendant l'entrée
+    pub project_id: Option<Uuid>, // Renseigné seulement pour une entrée sans tâche
+}
+
+// Deux entrées qui se chevauchent (rapport GET /time-entries/overlaps)
+#[derive(Serialize, Debug)]
+pub struct TimeEntryOverlap {
+    pub first_entry: TimeEntry,
+    pub second_entry: TimeEntry,
+    pub overlap_start: DateTime<Utc>,
+    pub overlap_end: Option<DateTime<Utc>>, // None si les deux entrées sont ouvertes
+    pub overlap_seconds: Option<i64>,
 }
 
 #[derive(Insertable, Deserialize, Debug)]
 #[diesel(table_name = time_entries)]
 pub struct NewTimeEntry {
     pub user_id: Uuid,
-    pub task_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub note: Option<String>,
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
     pub duration_seconds: Option<i32>,
     pub is_pomodoro_session: Option<bool>,
+    pub pomodoro_session_id: Option<Uuid>,
+    pub billable: Option<bool>,
 }
 
 #[derive(AsChangeset, Debug)]
@@ -306,15 +716,259 @@ pub struct UpdateTimeEntryChangeset {
     pub end_time: Option<Option<DateTime<Utc>>>,
     pub duration_seconds: Option<Option<i32>>,
     pub is_pomodoro_session: Option<bool>,
+    pub billable: Option<Option<bool>>,
+    pub note: Option<Option<String>>,
     pub updated_at: Option<NaiveDateTime>,
 }
 
+// --- PomodoroSession Model ---
+// État de la machine Pomodoro. Les réglages sont figés au démarrage de la session.
+#[derive(Queryable, Selectable, Identifiable, Serialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = pomodoro_sessions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PomodoroSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub task_id: Uuid,
+    pub status: String, // 'active' | 'completed'
+    pub phase: String,  // 'work' | 'short_break' | 'long_break'
+    pub work_minutes: i32,
+    pub short_break_minutes: i32,
+    pub long_break_minutes: i32,
+    pub cycles_before_long_break: i32,
+    pub completed_work_cycles: i32,
+    pub interruption_count: i32,
+    pub phase_duration_seconds: i32,
+    pub phase_elapsed_seconds: i32, // Temps écoulé dans la phase avant la dernière pause
+    pub phase_resumed_at: DateTime<Utc>,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = pomodoro_sessions)]
+pub struct NewPomodoroSession {
+    pub user_id: Uuid,
+    pub task_id: Uuid,
+    pub phase: String,
+    pub work_minutes: i32,
+    pub short_break_minutes: i32,
+    pub long_break_minutes: i32,
+    pub cycles_before_long_break: i32,
+    pub phase_duration_seconds: i32,
+    pub phase_resumed_at: DateTime<Utc>,
+}
+
+// Sauvegarde complète de l'état après une transition (les None remettent à NULL)
+#[derive(AsChangeset, Debug)]
+#[diesel(table_name = pomodoro_sessions)]
+#[diesel(treat_none_as_null = true)]
+pub struct PomodoroSessionStateChangeset {
+    pub status: String,
+    pub phase: String,
+    pub completed_work_cycles: i32,
+    pub interruption_count: i32,
+    pub phase_duration_seconds: i32,
+    pub phase_elapsed_seconds: i32,
+    pub phase_resumed_at: DateTime<Utc>,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl From<&PomodoroSession> for PomodoroSessionStateChangeset {
+    fn from(session: &PomodoroSession) -> Self {
+        PomodoroSessionStateChangeset {
+            status: session.status.clone(),
+            phase: session.phase.clone(),
+            completed_work_cycles: session.completed_work_cycles,
+            interruption_count: session.interruption_count,
+            phase_duration_seconds: session.phase_duration_seconds,
+            phase_elapsed_seconds: session.phase_elapsed_seconds,
+            phase_resumed_at: session.phase_resumed_at,
+            paused_at: session.paused_at,
+            ended_at: session.ended_at,
+        }
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = pomodoro_interruptions)]
+pub struct NewPomodoroInterruption {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub phase: String,
+    pub reason: Option<String>,
+}
+
+// État renvoyé aux clients : la session plus ce qu'il faut pour afficher le décompte
+#[derive(Serialize, Debug)]
+pub struct PomodoroStateResponse {
+    #[serde(flatten)]
+    pub session: PomodoroSession,
+    pub is_paused: bool,
+    pub remaining_seconds: i64,
+    pub phase_ends_at: Option<DateTime<Utc>>, // None si la phase est en pause
+    pub current_time_entry_id: Option<Uuid>,
+}
+
+// --- ApiToken Model ---
+// Le hash n'est jamais renvoyé au client.
+#[derive(Queryable, Selectable, Identifiable, Serialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = api_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = api_tokens)]
+pub struct NewApiToken {
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+// Réponse de création : le seul moment où le token en clair est visible
+#[derive(Serialize, Debug)]
+pub struct CreatedApiTokenResponse {
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+// --- User Model ---
+// L'id est celui fourni par l'authentification (`sub` du JWT), pas généré ici.
+#[derive(Queryable, Selectable, Identifiable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = users)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct User {
+    pub id: Uuid,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub last_active_at: Option<DateTime<Utc>>, // Dernier POST /me/heartbeat
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = users)]
+pub struct NewUser {
+    pub id: Uuid,
+}
+
+#[derive(AsChangeset, Debug)]
+#[diesel(table_name = users)]
+pub struct UpdateUserChangeset {
+    pub display_name: Option<Option<String>>,
+    pub email: Option<Option<String>>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+// --- UserSettings Model ---
+#[derive(
+    Queryable,
+    Selectable,
+    Identifiable,
+    Associations,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+#[diesel(table_name = user_settings)]
+#[diesel(belongs_to(User))]
+#[diesel(primary_key(user_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct UserSettings {
+    pub user_id: Uuid,
+    pub timezone: String,    // Nom IANA, ex: "Europe/Paris"
+    pub week_start_day: i16, // ISO : 1 = lundi, 7 = dimanche
+    pub pomodoro_work_minutes: i32,
+    pub pomodoro_short_break_minutes: i32,
+    pub pomodoro_long_break_minutes: i32,
+    pub pomodoro_cycles_before_long_break: i32,
+    pub working_hours_start: NaiveTime,
+    pub working_hours_end: NaiveTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub max_running_entry_minutes: i32, // Au-delà, un timer en cours est traité par le sweeper
+    pub idle_policy: String,            // 'cap' | 'last_activity' | 'flag'
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = user_settings)]
+pub struct NewUserSettings {
+    pub user_id: Uuid,
+}
+
+#[derive(AsChangeset, Debug)]
+#[diesel(table_name = user_settings)]
+pub struct UpdateUserSettingsChangeset {
+    pub timezone: Option<String>,
+    pub week_start_day: Option<i16>,
+    pub pomodoro_work_minutes: Option<i32>,
+    pub pomodoro_short_break_minutes: Option<i32>,
+    pub pomodoro_long_break_minutes: Option<i32>,
+    pub pomodoro_cycles_before_long_break: Option<i32>,
+    pub working_hours_start: Option<NaiveTime>,
+    pub working_hours_end: Option<NaiveTime>,
+    pub max_running_entry_minutes: Option<i32>,
+    pub idle_policy: Option<String>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+// Réponse de GET/PUT /me
+#[derive(Serialize, Debug)]
+pub struct MeResponse {
+    pub user: User,
+    pub settings: UserSettings,
+}
+
 // --- PAYLOAD DTOs ---
 
+#[derive(Deserialize, Debug)]
+pub struct CreateClientPayload {
+    pub name: String,
+    pub email: Option<String>,
+    pub address: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateClientPayload {
+    pub name: Option<String>,
+    #[serde(deserialize_with = "deserialize_opt_opt_string", default)]
+    pub email: Option<Option<String>>,
+    #[serde(deserialize_with = "deserialize_opt_opt_string", default)]
+    pub address: Option<Option<String>>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct CreateProjectPayload {
     pub name: String,
     pub color: Option<String>,
+    pub client_id: Option<Uuid>,
+    pub billable: Option<bool>,
+    pub rounding_increment_minutes: Option<i32>,
+    pub rounding_direction: Option<String>, // "up" (défaut), "down" ou "nearest"
+    pub rounding_scope: Option<String>,     // "entry" (défaut) ou "day"
+    pub approver_id: Option<Uuid>,          // Approbateur des feuilles de temps, pas soi-même
 }
 
 #[derive(Deserialize, Debug)]
@@ -322,6 +976,173 @@ pub struct UpdateProjectPayload {
     pub name: Option<String>,
     #[serde(deserialize_with = "deserialize_opt_opt_string", default)]
     pub color: Option<Option<String>>,
+    #[serde(deserialize_with = "deserialize_opt_opt_uuid", default)]
+    pub client_id: Option<Option<Uuid>>,
+    pub billable: Option<bool>,
+    pub rounding_increment_minutes: Option<i32>,
+    pub rounding_direction: Option<String>,
+    pub rounding_scope: Option<String>,
+    #[serde(deserialize_with = "deserialize_opt_opt_uuid", default)]
+    pub approver_id: Option<Option<Uuid>>,
+}
+
+// Contenu de GET /trash. `purge_at` : date de la purge automatique par le job de rétention.
+// Les tâches d'un projet supprimé y figurent aussi et sont restaurées avec lui.
+#[derive(Serialize, Debug)]
+pub struct TrashedProject {
+    #[serde(flatten)]
+    pub project: Project,
+    pub purge_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TrashedTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub purge_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TrashResponse {
+    pub retention_days: i64,
+    pub projects: Vec<TrashedProject>,
+    pub tasks: Vec<TrashedTask>,
+}
+
+// Statut d'un workflow tel qu'exposé par l'API (aussi pour le workflow intégré)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkflowStatusView {
+    pub key: String,
+    pub name: String,
+    pub category: String, // "todo", "in_progress" ou "done"
+    pub position: i32,
+    pub wip_limit: Option<i32>,
+    pub wip_policy: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkflowTransitionView {
+    pub from: String,
+    pub to: String,
+}
+
+// Workflow effectif d'un projet ou de l'utilisateur
+#[derive(Serialize, Debug, Clone)]
+pub struct Workflow {
+    pub source: String, // "project", "user" ou "default" (workflow intégré)
+    pub project_id: Option<Uuid>,
+    pub statuses: Vec<WorkflowStatusView>,
+    // Vide : tous les passages sont autorisés
+    pub transitions: Vec<WorkflowTransitionView>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WorkflowStatusPayload {
+    pub key: String,
+    pub name: Option<String>, // Défaut : la clé
+    pub category: String,
+    pub wip_limit: Option<i32>,
+    pub wip_policy: Option<String>, // "warn" (défaut) ou "reject"
+}
+
+// Remplace le workflow. `statuses` vide : revient au workflow hérité.
+// `status_mapping` : nouveau statut des tâches dont le statut (ancienne clé) disparaît
+// ou est renommé.
+#[derive(Deserialize, Debug)]
+pub struct UpdateWorkflowPayload {
+    pub statuses: Vec<WorkflowStatusPayload>,
+    #[serde(default)]
+    pub transitions: Vec<WorkflowTransitionView>,
+    #[serde(default)]
+    pub status_mapping: HashMap<String, String>,
+}
+
+// Colonne du tableau Kanban d'un projet : un statut du workflow et ses tâches ordonnées
+#[derive(Serialize, Debug)]
+pub struct BoardColumn {
+    #[serde(flatten)]
+    pub status: WorkflowStatusView,
+    pub task_count: i64,
+    pub at_wip_limit: bool, // Une tâche de plus déclenche l'avertissement ou le refus
+    pub tasks: Vec<TaskApiResponse>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BoardResponse {
+    pub project_id: Uuid,
+    pub workflow_source: String,
+    pub transitions: Vec<WorkflowTransitionView>, // Vide : tous les passages sont autorisés
+    pub total_tasks: i64,
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MoveBoardTaskPayload {
+    pub task_id: Uuid,
+    pub status: String,
+    pub position: Option<usize>, // Rang dans la colonne (0 : en haut) ; absent : en bas
+}
+
+#[derive(Serialize, Debug)]
+pub struct MoveBoardTaskResponse {
+    pub task: TaskApiResponse,
+    pub warnings: Vec<String>, // Limite WIP atteinte (politique 'warn')
+}
+
+// Réponse de PUT /tasks/{id} : la tâche, plus les avertissements WIP s'il y en a
+#[derive(Serialize, Debug)]
+pub struct UpdateTaskResponse {
+    #[serde(flatten)]
+    pub task: TaskApiResponse,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>, // Limite WIP atteinte (politique 'warn')
+}
+
+// Facture des entrées facturables non facturées du client, dont le début tombe
+// entre date_from et date_to inclus (jours locaux de l'utilisateur)
+#[derive(Deserialize, Debug)]
+pub struct CreateInvoicePayload {
+    pub client_id: Uuid,
+    pub date_from: NaiveDate,
+    pub date_to: NaiveDate,
+    pub group_by: Option<String>, // "project" (défaut) ou "task"
+    // Arrondi imposé à chaque entrée (0 : aucun) ; absent : règles d'arrondi des projets
+    pub rounding_increment_minutes: Option<i32>,
+    pub rounding_direction: Option<String>, // "up" (défaut), "down" ou "nearest"
+    pub issue_date: Option<NaiveDate>,      // Défaut : aujourd'hui
+    pub due_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateInvoiceStatusPayload {
+    pub status: String,
+}
+
+// Soumet la semaine ISO contenant week_of (jour local de l'utilisateur)
+#[derive(Deserialize, Debug)]
+pub struct SubmitTimesheetPayload {
+    pub week_of: NaiveDate,
+    // Déduit des projets de la semaine ; requis seulement pour une semaine sans temps saisi
+    pub approver_id: Option<Uuid>,
+    pub comment: Option<String>,
+}
+
+// Approbation (commentaire facultatif), rejet (commentaire obligatoire) ou réouverture
+#[derive(Deserialize, Debug)]
+pub struct ReviewTimesheetPayload {
+    pub comment: Option<String>,
+}
+
+// Un seul de client_id, project_id et task_id doit être fourni
+#[derive(Deserialize, Debug)]
+pub struct CreateHourlyRatePayload {
+    pub client_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub amount_cents: i64,
+    pub currency: String,
+    pub effective_from: NaiveDate,
 }
 
 #[derive(Deserialize, Debug)]
@@ -332,12 +1153,18 @@ pub struct CreateTaskPayload {
     pub status: Option<String>,
     pub due_date: Option<NaiveDate>,
     pub order: Option<i32>,
+    pub billable: Option<bool>,
+    pub parent_task_id: Option<Uuid>, // Le projet est alors celui de la tâche parente
+    pub recurrence_rule: Option<String>, // RRULE, exige une due_date
 }
 
 #[derive(Deserialize, Debug)]
 pub struct UpdateTaskPayload {
     #[serde(deserialize_with = "deserialize_opt_opt_uuid", default)]
     pub project_id: Option<Option<Uuid>>,
+    // Déplace la tâche (et ses sous-tâches) sous un autre parent ; null : tâche racine
+    #[serde(deserialize_with = "deserialize_opt_opt_uuid", default)]
+    pub parent_task_id: Option<Option<Uuid>>,
     pub title: Option<String>,
     #[serde(deserialize_with = "deserialize_opt_opt_string", default)]
     pub description: Option<Option<String>>,
@@ -346,6 +1173,11 @@ pub struct UpdateTaskPayload {
     pub due_date: Option<Option<NaiveDate>>,
     #[serde(deserialize_with = "deserialize_opt_opt_i32", default)]
     pub order: Option<Option<i32>>,
+    #[serde(deserialize_with = "deserialize_opt_opt_bool", default)]
+    pub billable: Option<Option<bool>>, // null : hérite du projet
+    // null : arrête la récurrence (les occurrences existantes sont conservées)
+    #[serde(deserialize_with = "deserialize_opt_opt_string", default)]
+    pub recurrence_rule: Option<Option<String>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -361,13 +1193,55 @@ pub struct UpdateLabelPayload {
     pub color: Option<Option<String>>,
 }
 
+// Exactement un de task_id et project_id (travail hors tâche)
 #[derive(Deserialize, Debug)]
 pub struct CreateTimeEntryPayload {
-    pub task_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub note: Option<String>,
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
     pub duration_seconds: Option<i32>,
     pub is_pomodoro_session: Option<bool>,
+    pub billable: Option<bool>,
+}
+
+// Démarrage d'un timer côté serveur : start_time = maintenant, end_time NULL
+#[derive(Deserialize, Debug)]
+pub struct StartTimerPayload {
+    pub task_id: Option<Uuid>, // Exactement un de task_id et project_id
+    pub project_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub is_pomodoro_session: Option<bool>,
+}
+
+// Découpe d'une entrée en deux à l'instant `at` ; `task_id` réassigne la seconde partie
+#[derive(Deserialize, Debug)]
+pub struct SplitTimeEntryPayload {
+    pub at: DateTime<Utc>,
+    pub task_id: Option<Uuid>,
+}
+
+// Fusion d'entrées consécutives d'une même tâche ; `max_gap_seconds` borne le trou toléré entre deux entrées
+#[derive(Deserialize, Debug)]
+pub struct MergeTimeEntriesPayload {
+    pub entry_ids: Vec<Uuid>,
+    pub max_gap_seconds: Option<i64>,
+}
+
+// Démarrage d'une session Pomodoro ; les durées absentes viennent des réglages utilisateur
+#[derive(Deserialize, Debug)]
+pub struct StartPomodoroPayload {
+    pub task_id: Uuid,
+    pub work_minutes: Option<i32>,
+    pub short_break_minutes: Option<i32>,
+    pub long_break_minutes: Option<i32>,
+    pub cycles_before_long_break: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct PausePomodoroPayload {
+    pub reason: Option<String>, // ex: "phone call", "colleague"
 }
 
 #[derive(Deserialize, Debug)]
@@ -378,6 +1252,35 @@ pub struct UpdateTimeEntryPayload {
     #[serde(deserialize_with = "deserialize_opt_opt_i32", default)]
     pub duration_seconds: Option<Option<i32>>,
     pub is_pomodoro_session: Option<bool>, // Boolean ne peut pas vraiment être "absent vs null", juste true/false/absent
+    #[serde(deserialize_with = "deserialize_opt_opt_bool", default)]
+    pub billable: Option<Option<bool>>, // Ici null a un sens : hérite de la tâche
+    #[serde(deserialize_with = "deserialize_opt_opt_string", default)]
+    pub note: Option<Option<String>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateApiTokenPayload {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateMePayload {
+    #[serde(deserialize_with = "deserialize_opt_opt_string", default)]
+    pub display_name: Option<Option<String>>,
+    #[serde(deserialize_with = "deserialize_opt_opt_string", default)]
+    pub email: Option<Option<String>>,
+    pub timezone: Option<String>,
+    pub week_start_day: Option<i16>,
+    pub pomodoro_work_minutes: Option<i32>,
+    pub pomodoro_short_break_minutes: Option<i32>,
+    pub pomodoro_long_break_minutes: Option<i32>,
+    pub pomodoro_cycles_before_long_break: Option<i32>,
+    pub working_hours_start: Option<NaiveTime>,
+    pub working_hours_end: Option<NaiveTime>,
+    pub max_running_entry_minutes: Option<i32>,
+    pub idle_policy: Option<String>,
 }
 
 // --- Pagination DTOs ---
@@ -408,30 +1311,176 @@ pub struct PaginatedResponse<T> {
 #[derive(QueryableByName, Serialize, Deserialize, Debug, Clone)] // QueryableByName si on utilise du SQL brut
 #[diesel(check_for_backend(diesel::pg::Pg))] // Nécessaire pour QueryableByName avec un backend spécifique
 pub struct TimeByProjectStat {
-    #[diesel(sql_type = diesel::sql_types::Uuid)] // Spécifier le type SQL pour QueryableByName
-    pub project_id: Uuid,
+    // None pour le bucket "No project" (tâches sans projet)
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Uuid>)]
+    // Spécifier le type SQL pour QueryableByName
+    pub project_id: Option<Uuid>,
     #[diesel(sql_type = diesel::sql_types::Text)]
     pub project_name: String,
     // Diesel sum sur i32 retourne i64 (BigInt). Optionnel si certains projets n'ont pas de temps.
     #[diesel(sql_type = BigInt)] // Diesel sum sur i32/Option<i32> retourne BigInt/Option<BigInt>
     pub total_duration_seconds: i64, // Stocker en i64 car la somme peut dépasser i32
+    // Temps suivi brut (= total_duration_seconds) et temps arrondi selon les règles du projet
+    #[diesel(sql_type = BigInt)]
+    pub raw_duration_seconds: i64,
+    #[diesel(sql_type = BigInt)]
+    pub rounded_duration_seconds: i64,
 }
 
 #[derive(QueryableByName, Serialize, Deserialize, Debug, Clone)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
-pub struct ProductivityTrendPoint {
-    #[diesel(sql_type = diesel::sql_types::Date)]
-    // Ou Timestamptz si vous groupez par heure/jour exact
-    pub date_point: NaiveDate, // Représente le jour ou le début de la semaine/mois
+pub struct TimeByLabelStat {
+    // None pour le bucket "Unlabeled" (tâches sans label)
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Uuid>)]
+    pub label_id: Option<Uuid>,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub label_name: String,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub label_color: Option<String>,
     #[diesel(sql_type = BigInt)]
     pub total_duration_seconds: i64,
 }
 
+// Un point par bucket (heure, jour, semaine ou mois local), buckets vides inclus
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProductivityTrendPoint {
+    pub date_point: NaiveDate, // Jour du début du bucket (premier jour de la semaine/du mois)
+    pub bucket_start: NaiveDateTime, // Début du bucket en heure locale
+    // Même instant en UTC : distingue les deux occurrences de l'heure répétée en automne
+    pub bucket_start_utc: DateTime<Utc>,
+    pub total_duration_seconds: i64,
+}
+
+// Nombre de tâches terminées par bucket de la période
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ThroughputPoint {
+    pub date_point: NaiveDate,
+    pub bucket_start: NaiveDateTime,
+    pub bucket_start_utc: DateTime<Utc>,
+    pub completed_count: i64,
+}
+
+// Statistiques d'une durée (lead time ou cycle time), en secondes.
+// Les valeurs sont None quand aucune tâche n'entre dans le calcul.
+#[derive(QueryableByName, Serialize, Deserialize, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DurationStats {
+    #[diesel(sql_type = BigInt)]
+    pub task_count: i64,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+    pub average_seconds: Option<f64>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+    pub p50_seconds: Option<f64>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+    pub p85_seconds: Option<f64>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+    pub p95_seconds: Option<f64>,
+}
+
+// Réponse de GET /analytics/flow
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FlowMetricsResponse {
+    pub completed_count: i64,
+    pub throughput: Vec<ThroughputPoint>,
+    pub lead_time: DurationStats,  // Création -> done
+    pub cycle_time: DurationStats, // Premier passage en cours -> done
+}
+
+// Temps facturable d'un projet dans une devise (currency None : aucun taux applicable)
+#[derive(QueryableByName, Serialize, Deserialize, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BillableProjectStat {
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Uuid>)]
+    pub client_id: Option<Uuid>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub client_name: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Uuid>)]
+    pub project_id: Option<Uuid>,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub project_name: String,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub currency: Option<String>,
+    #[diesel(sql_type = BigInt)]
+    pub billable_seconds: i64, // Temps suivi brut (= raw_duration_seconds)
+    #[diesel(sql_type = BigInt)]
+    pub raw_duration_seconds: i64,
+    #[diesel(sql_type = BigInt)]
+    pub rounded_duration_seconds: i64, // Base du montant
+    #[diesel(sql_type = BigInt)]
+    pub amount_cents: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BillableCurrencyTotal {
+    pub currency: String,
+    pub billable_seconds: i64,
+    pub rounded_duration_seconds: i64,
+    pub amount_cents: i64,
+}
+
+// Réponse de GET /analytics/billable
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BillableReportResponse {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub billable_seconds: i64,
+    pub rounded_billable_seconds: i64, // Selon les règles d'arrondi des projets
+    pub non_billable_seconds: i64,
+    pub unrated_billable_seconds: i64, // Facturable mais sans taux applicable
+    pub totals: Vec<BillableCurrencyTotal>, // Une ligne par devise, montants non convertis
+    pub by_project: Vec<BillableProjectStat>,
+}
+
+// Ligne du rapport détaillé GET /analytics/entries (et de son export CSV)
+#[derive(QueryableByName, Serialize, Debug, Clone)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TimeEntryReportRow {
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    pub entry_id: Uuid,
+    #[diesel(sql_type = diesel::sql_types::Timestamptz)]
+    pub start_time: DateTime<Utc>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Timestamptz>)]
+    pub end_time: Option<DateTime<Utc>>,
+    #[diesel(sql_type = BigInt)]
+    pub seconds: i64,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Uuid>)]
+    pub client_id: Option<Uuid>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub client_name: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Uuid>)]
+    pub project_id: Option<Uuid>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub project_name: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Uuid>)]
+    pub task_id: Option<Uuid>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub task_title: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub note: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Bool)]
+    pub is_billable: bool,
+}
+
 // DTO pour les paramètres de requête des analytics
 #[derive(Deserialize, Debug)]
 pub struct AnalyticsQueryPeriod {
-    // Ex: "week", "month", "last7days", "last30days", ou des dates spécifiques
+    // Ex: "today", "this_week", "last_month", "this_quarter", "last_90_days", ou des dates spécifiques
     pub period: Option<String>,
     pub start_date: Option<NaiveDate>, // YYYY-MM-DD
     pub end_date: Option<NaiveDate>,   // YYYY-MM-DD
+    // Fuseau IANA (ex: "Europe/Paris"). Par défaut, celui des réglages de l'utilisateur.
+    pub tz: Option<String>,
+    // Tendance uniquement : "hour", "day" (défaut), "week" ou "month"
+    pub granularity: Option<String>,
+    // Temps par label uniquement, pour les tâches à plusieurs labels :
+    // "split" (défaut, durée répartie entre les labels) ou "double_count" (durée entière par label)
+    pub mode: Option<String>,
+    // Métriques de flux et rapport facturable : restreindre à un projet et/ou un label
+    pub project_id: Option<Uuid>,
+    pub label_id: Option<Uuid>,
+    // Rapport facturable et rapport détaillé : restreindre à un client
+    pub client_id: Option<Uuid>,
+    // Rapport détaillé uniquement : recherche dans les notes, "json" (défaut) ou "csv"
+    pub q: Option<String>,
+    pub format: Option<String>,
 }
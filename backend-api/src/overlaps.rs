@@ -0,0 +1,405 @@
+// OptiTask/backend-api/src/overlaps.rs
+// Détection et résolution des chevauchements entre time entries d'un même utilisateur.
+// Une entrée sans end_time (timer en cours) est considérée comme ouverte jusqu'à l'infini.
+// Les fonctions travaillent sur une entrée déjà écrite en base (création ou mise à jour),
+// dans la transaction du handler : une erreur annule donc aussi l'écriture.
+// Une résolution qui toucherait une semaine approuvée (feuille de temps) est refusée.
+
+use crate::error_handler::ServiceError;
+use crate::models::{NewTimeEntry, TimeEntry};
+use crate::schema::time_entries::{self, dsl::*};
+use crate::timesheets::ensure_week_not_approved;
+use chrono::{DateTime, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use serde_json::json;
+
+// Mode de résolution demandé par le client (`?overlap=...`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverlapResolution {
+    // Refuse l'écriture avec un 409 listant les entrées en conflit (défaut)
+    Reject,
+    // La nouvelle entrée gagne : les entrées existantes sont raccourcies, coupées en deux ou supprimées
+    TrimExisting,
+    // Les entrées existantes gagnent : la nouvelle entrée est découpée dans les trous restants
+    Split,
+    // Accepte le chevauchement tel quel
+    Allow,
+}
+
+impl OverlapResolution {
+    pub fn parse(value: Option<&str>) -> Result<Self, ServiceError> {
+        match value {
+            Some("reject") | None => Ok(OverlapResolution::Reject),
+            Some("trim_existing") => Ok(OverlapResolution::TrimExisting),
+            Some("split") => Ok(OverlapResolution::Split),
+            Some("allow") => Ok(OverlapResolution::Allow),
+            Some(other) => Err(ServiceError::BadRequest(format!(
+                "Invalid overlap mode: {}. Supported: reject, trim_existing, split, allow.",
+                other
+            ))),
+        }
+    }
+}
+
+// `a` se termine strictement avant `b` (None = ouvert, donc infini)
+fn ends_before(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a < b,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+pub fn duration_between(start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> Option<i32> {
+    end.map(|end| (end - start).num_seconds().max(0) as i32)
+}
+
+// Copie d'une entrée sur un autre intervalle (même tâche ou projet, même note, même lien Pomodoro)
+fn insert_piece(
+    conn: &mut PgConnection,
+    source: &TimeEntry,
+    piece_start: DateTime<Utc>,
+    piece_end: Option<DateTime<Utc>>,
+) -> Result<TimeEntry, ServiceError> {
+    diesel::insert_into(time_entries::table)
+        .values(&NewTimeEntry {
+            user_id: source.user_id,
+            task_id: source.task_id,
+            project_id: source.project_id,
+            note: source.note.clone(),
+            start_time: piece_start,
+            end_time: piece_end,
+            duration_seconds: duration_between(piece_start, piece_end),
+            is_pomodoro_session: Some(source.is_pomodoro_session),
+            pomodoro_session_id: source.pomodoro_session_id,
+            billable: source.billable,
+        })
+        .get_result::<TimeEntry>(conn)
+        .map_err(ServiceError::from)
+}
+
+fn set_interval(
+    conn: &mut PgConnection,
+    entry_id: uuid::Uuid,
+    new_start: DateTime<Utc>,
+    new_end: Option<DateTime<Utc>>,
+) -> Result<TimeEntry, ServiceError> {
+    diesel::update(time_entries.filter(id.eq(entry_id)))
+        .set((
+            start_time.eq(new_start),
+            end_time.eq(new_end),
+            duration_seconds.eq(duration_between(new_start, new_end)),
+        ))
+        .get_result::<TimeEntry>(conn)
+        .map_err(ServiceError::from)
+}
+
+// Entrées de l'utilisateur qui chevauchent `entry` (hors elle-même), par début croissant
+pub fn find_overlapping_entries(
+    conn: &mut PgConnection,
+    entry: &TimeEntry,
+) -> Result<Vec<TimeEntry>, ServiceError> {
+    // Une entrée de durée nulle ne chevauche rien
+    if entry.end_time.is_some_and(|end| end <= entry.start_time) {
+        return Ok(Vec::new());
+    }
+
+    let mut query = time_entries
+        .filter(user_id.eq(entry.user_id))
+        .filter(id.ne(entry.id))
+        .filter(end_time.is_null().or(end_time.gt(entry.start_time)))
+        .order(start_time.asc())
+        .select(TimeEntry::as_select())
+        .into_boxed();
+    if let Some(end) = entry.end_time {
+        query = query.filter(start_time.lt(end));
+    }
+
+    query.load::<TimeEntry>(conn).map_err(ServiceError::from)
+}
+
+// Raccourcit les entrées existantes pour libérer l'intervalle de `entry`
+fn trim_existing_entries(
+    conn: &mut PgConnection,
+    entry: &TimeEntry,
+    clashing: &[TimeEntry],
+) -> Result<(), ServiceError> {
+    // Semaine de chaque entrée raccourcie ou supprimée, et de la partie recréée après
+    // `entry` quand une entrée existante dépasse sa fin
+    let mut touched_starts: Vec<DateTime<Utc>> = clashing
+        .iter()
+        .map(|existing| existing.start_time)
+        .collect();
+    if clashing
+        .iter()
+        .any(|existing| ends_before(entry.end_time, existing.end_time))
+    {
+        touched_starts.extend(entry.end_time);
+    }
+    ensure_week_not_approved(conn, entry.user_id, &touched_starts)?;
+
+    for existing in clashing {
+        let keeps_before = existing.start_time < entry.start_time;
+        let keeps_after = ends_before(entry.end_time, existing.end_time);
+
+        match (keeps_before, keeps_after) {
+            // Entièrement recouverte : supprimée
+            (false, false) => {
+                diesel::delete(time_entries.filter(id.eq(existing.id))).execute(conn)?;
+            }
+            (true, false) => {
+                set_interval(
+                    conn,
+                    existing.id,
+                    existing.start_time,
+                    Some(entry.start_time),
+                )?;
+            }
+            (false, true) => {
+                // keeps_after implique que `entry` a une fin
+                set_interval(
+                    conn,
+                    existing.id,
+                    entry.end_time.unwrap(),
+                    existing.end_time,
+                )?;
+            }
+            // L'entrée existante englobe la nouvelle : on garde l'avant et on recrée l'après.
+            // L'existante est fermée d'abord pour respecter l'index "un seul timer en cours".
+            (true, true) => {
+                set_interval(
+                    conn,
+                    existing.id,
+                    existing.start_time,
+                    Some(entry.start_time),
+                )?;
+                insert_piece(conn, existing, entry.end_time.unwrap(), existing.end_time)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Découpe `entry` dans les intervalles laissés libres par les entrées existantes.
+// La première partie réutilise `entry`, les suivantes sont de nouvelles entrées.
+fn split_into_free_intervals(
+    conn: &mut PgConnection,
+    entry: &TimeEntry,
+    clashing: &[TimeEntry],
+) -> Result<Vec<TimeEntry>, ServiceError> {
+    let mut free_intervals: Vec<(DateTime<Utc>, Option<DateTime<Utc>>)> = Vec::new();
+    let mut cursor = Some(entry.start_time);
+
+    for existing in clashing {
+        let gap_start = match cursor {
+            Some(gap_start) => gap_start,
+            None => break, // Une entrée ouverte couvre tout le reste
+        };
+        if existing.start_time > gap_start {
+            let gap_end = match entry.end_time {
+                Some(end) => end.min(existing.start_time),
+                None => existing.start_time,
+            };
+            free_intervals.push((gap_start, Some(gap_end)));
+        }
+        if ends_before(cursor, existing.end_time) {
+            cursor = existing.end_time;
+        }
+    }
+    if let Some(gap_start) = cursor {
+        if ends_before(Some(gap_start), entry.end_time) {
+            free_intervals.push((gap_start, entry.end_time));
+        }
+    }
+
+    if free_intervals.is_empty() {
+        return Err(ServiceError::BadRequest(
+            "Time entry is entirely covered by existing entries, nothing left to keep.".to_string(),
+        ));
+    }
+
+    // Une partie peut commencer dans une autre semaine que l'entrée d'origine
+    let piece_starts: Vec<DateTime<Utc>> = free_intervals
+        .iter()
+        .map(|(piece_start, _)| *piece_start)
+        .collect();
+    ensure_week_not_approved(conn, entry.user_id, &piece_starts)?;
+
+    let mut pieces = Vec::with_capacity(free_intervals.len());
+    let (first_start, first_end) = free_intervals[0];
+    pieces.push(set_interval(conn, entry.id, first_start, first_end)?);
+    for (piece_start, piece_end) in free_intervals.into_iter().skip(1) {
+        pieces.push(insert_piece(conn, entry, piece_start, piece_end)?);
+    }
+    Ok(pieces)
+}
+
+// Applique le mode de résolution à `entry` fraîchement écrite.
+// Renvoie les parties résultantes de l'entrée (plusieurs seulement en mode Split).
+pub fn resolve_overlaps(
+    conn: &mut PgConnection,
+    entry: TimeEntry,
+    resolution: OverlapResolution,
+) -> Result<Vec<TimeEntry>, ServiceError> {
+    if resolution == OverlapResolution::Allow {
+        return Ok(vec![entry]);
+    }
+
+    let clashing = find_overlapping_entries(conn, &entry)?;
+    if clashing.is_empty() {
+        return Ok(vec![entry]);
+    }
+
+    match resolution {
+        OverlapResolution::Reject => Err(ServiceError::ConflictWithDetails(
+            format!(
+                "Time entry overlaps {} existing entr{}. Use ?overlap=trim_existing, split or allow to resolve.",
+                clashing.len(),
+                if clashing.len() > 1 { "ies" } else { "y" }
+            ),
+            json!({ "overlapping_entries": clashing }),
+        )),
+        OverlapResolution::TrimExisting => {
+            trim_existing_entries(conn, &entry, &clashing)?;
+            Ok(vec![entry])
+        }
+        OverlapResolution::Split => split_into_free_intervals(conn, &entry, &clashing),
+        OverlapResolution::Allow => Ok(vec![entry]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::user_handlers::load_user_settings;
+    use crate::models::NewTimesheet;
+    use crate::schema::timesheets;
+    use crate::test_support::{seed_task, test_connection};
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse::<DateTime<Utc>>().unwrap()
+    }
+
+    fn insert_entry(
+        conn: &mut PgConnection,
+        owner: Uuid,
+        task: Uuid,
+        start: &str,
+        end: &str,
+    ) -> TimeEntry {
+        let (entry_start, entry_end) = (at(start), Some(at(end)));
+        diesel::insert_into(time_entries::table)
+            .values(&NewTimeEntry {
+                user_id: owner,
+                task_id: Some(task),
+                project_id: None,
+                note: None,
+                start_time: entry_start,
+                end_time: entry_end,
+                duration_seconds: duration_between(entry_start, entry_end),
+                is_pomodoro_session: None,
+                pomodoro_session_id: None,
+                billable: None,
+            })
+            .get_result::<TimeEntry>(conn)
+            .expect("Failed to insert time entry")
+    }
+
+    // Utilisateur en UTC dont la semaine 2026-W43 (lundi 19 octobre) est approuvée,
+    // avec une entrée existante lundi de 00:00 à 02:00
+    fn approved_week_with_entry(conn: &mut PgConnection) -> (Uuid, Uuid, TimeEntry) {
+        let (owner, approver) = (Uuid::new_v4(), Uuid::new_v4());
+        load_user_settings(conn, owner).expect("Failed to create user");
+        load_user_settings(conn, approver).expect("Failed to create approver");
+        let task = seed_task(conn, owner, None);
+        let existing = insert_entry(
+            conn,
+            owner,
+            task,
+            "2026-10-19T00:00:00Z",
+            "2026-10-19T02:00:00Z",
+        );
+        diesel::insert_into(timesheets::table)
+            .values(&NewTimesheet {
+                user_id: owner,
+                approver_id: approver,
+                iso_year: 2026,
+                iso_week: 43,
+                week_start: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                week_end: NaiveDate::from_ymd_opt(2026, 10, 25).unwrap(),
+                status: "approved".to_string(),
+                total_seconds: 7200,
+                submit_comment: None,
+                submitted_at: Some(Utc::now()),
+            })
+            .execute(conn)
+            .expect("Failed to insert approved timesheet");
+        (owner, task, existing)
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn trim_existing_refuses_to_rewrite_an_approved_week() {
+        let mut conn = test_connection();
+        let (owner, task, existing) = approved_week_with_entry(&mut conn);
+        // Commence dimanche (semaine ouverte), finit dans la semaine approuvée
+        let entry = insert_entry(
+            &mut conn,
+            owner,
+            task,
+            "2026-10-18T23:00:00Z",
+            "2026-10-19T01:00:00Z",
+        );
+
+        let result = resolve_overlaps(&mut conn, entry, OverlapResolution::TrimExisting);
+        assert!(matches!(result, Err(ServiceError::Conflict(_))));
+        let unchanged = time_entries
+            .filter(id.eq(existing.id))
+            .select(TimeEntry::as_select())
+            .first::<TimeEntry>(&mut conn)
+            .expect("Approved entry should still exist");
+        assert_eq!(unchanged.start_time, existing.start_time);
+        assert_eq!(unchanged.end_time, existing.end_time);
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn split_refuses_pieces_that_land_in_an_approved_week() {
+        let mut conn = test_connection();
+        let (owner, task, _) = approved_week_with_entry(&mut conn);
+        // Trous libres : dimanche 22:00-00:00 (ouvert) et lundi 02:00-03:00 (approuvé)
+        let entry = insert_entry(
+            &mut conn,
+            owner,
+            task,
+            "2026-10-18T22:00:00Z",
+            "2026-10-19T03:00:00Z",
+        );
+
+        let result = resolve_overlaps(&mut conn, entry, OverlapResolution::Split);
+        assert!(matches!(result, Err(ServiceError::Conflict(_))));
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn split_is_allowed_when_every_piece_stays_outside_approved_weeks() {
+        let mut conn = test_connection();
+        let (owner, task, existing) = approved_week_with_entry(&mut conn);
+        let entry = insert_entry(
+            &mut conn,
+            owner,
+            task,
+            "2026-10-18T23:00:00Z",
+            "2026-10-19T01:00:00Z",
+        );
+
+        let pieces = resolve_overlaps(&mut conn, entry, OverlapResolution::Split)
+            .expect("Split outside the approved week should succeed");
+        assert_eq!(pieces.len(), 1);
+        assert_eq!(pieces[0].start_time, at("2026-10-18T23:00:00Z"));
+        assert_eq!(pieces[0].end_time, Some(existing.start_time));
+    }
+}
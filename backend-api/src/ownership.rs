@@ -0,0 +1,693 @@
+// OptiTask/backend-api/src/ownership.rs
+// Gardes de propriété partagées par tous les chemins d'écriture.
+// Chaque id référencé (dans le path ou dans le payload) passe par ici avant
+// d'être utilisé, pour que les erreurs soient identiques partout : 404 si la
+// ressource n'existe pas ou appartient à un autre utilisateur. Sous RLS (voir
+// `db::with_user_rls`) les lignes des autres utilisateurs sont invisibles, et on
+// ne veut de toute façon pas révéler l'existence de leurs ids : pas de 403 ici.
+// Les tâches et projets dans la corbeille sont traités comme inexistants.
+
+use crate::error_handler::ServiceError;
+use crate::schema::{clients, hourly_rates, labels, projects, tasks, time_entries};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+fn check_owner(
+    owner: Option<Uuid>,
+    user_uuid: Uuid,
+    entity: &str,
+    entity_id: Uuid,
+) -> Result<(), ServiceError> {
+    match owner {
+        Some(owner_id) if owner_id == user_uuid => Ok(()),
+        _ => Err(ServiceError::NotFound(format!(
+            "{} with id {} not found",
+            entity, entity_id
+        ))),
+    }
+}
+
+pub fn ensure_client_owned(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    client_id: Uuid,
+) -> Result<(), ServiceError> {
+    let owner = clients::table
+        .filter(clients::id.eq(client_id))
+        .select(clients::user_id)
+        .first::<Uuid>(conn)
+        .optional()?;
+    check_owner(owner, user_uuid, "Client", client_id)
+}
+
+pub fn ensure_project_owned(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    project_id: Uuid,
+) -> Result<(), ServiceError> {
+    let owner = projects::table
+        .filter(projects::id.eq(project_id))
+        .filter(projects::deleted_at.is_null())
+        .select(projects::user_id)
+        .first::<Uuid>(conn)
+        .optional()?;
+    check_owner(owner, user_uuid, "Project", project_id)
+}
+
+pub fn ensure_task_owned(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    task_id: Uuid,
+) -> Result<(), ServiceError> {
+    let owner = tasks::table
+        .filter(tasks::id.eq(task_id))
+        .filter(tasks::deleted_at.is_null())
+        .select(tasks::user_id)
+        .first::<Uuid>(conn)
+        .optional()?;
+    check_owner(owner, user_uuid, "Task", task_id)
+}
+
+pub fn ensure_label_owned(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    label_id: Uuid,
+) -> Result<(), ServiceError> {
+    let owner = labels::table
+        .filter(labels::id.eq(label_id))
+        .select(labels::user_id)
+        .first::<Uuid>(conn)
+        .optional()?;
+    check_owner(owner, user_uuid, "Label", label_id)
+}
+
+pub fn ensure_time_entry_owned(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    time_entry_id: Uuid,
+) -> Result<(), ServiceError> {
+    let owner = time_entries::table
+        .filter(time_entries::id.eq(time_entry_id))
+        .select(time_entries::user_id)
+        .first::<Uuid>(conn)
+        .optional()?;
+    check_owner(owner, user_uuid, "TimeEntry", time_entry_id)
+}
+
+pub fn ensure_hourly_rate_owned(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    hourly_rate_id: Uuid,
+) -> Result<(), ServiceError> {
+    let owner = hourly_rates::table
+        .filter(hourly_rates::id.eq(hourly_rate_id))
+        .select(hourly_rates::user_id)
+        .first::<Uuid>(conn)
+        .optional()?;
+    check_owner(owner, user_uuid, "HourlyRate", hourly_rate_id)
+}
+
+// Cible d'une time entry : exactement une tâche ou un projet (travail hors tâche)
+pub fn ensure_time_entry_target_owned(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    task_id: Option<Uuid>,
+    project_id: Option<Uuid>,
+) -> Result<(), ServiceError> {
+    match (task_id, project_id) {
+        (Some(task_id), None) => ensure_task_owned(conn, user_uuid, task_id),
+        (None, Some(project_id)) => ensure_project_owned(conn, user_uuid, project_id),
+        _ => Err(ServiceError::BadRequest(
+            "A time entry needs exactly one of task_id or project_id.".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configure_routes;
+    use crate::handlers::user_handlers::load_user_settings;
+    use crate::models::{NewInvoice, NewTimesheet};
+    use crate::schema::{
+        clients, hourly_rates, invoices, labels, projects, task_labels, tasks, time_entries,
+        timesheets,
+    };
+    use crate::test_support::{
+        seed_label, seed_project, seed_task, seed_time_entry, test_auth_config, test_pool,
+    };
+    use actix_web::http::{Method, StatusCode};
+    use actix_web::{test, web, App};
+    use chrono::{Duration, NaiveDate, Utc};
+    use diesel::prelude::*;
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    // Chaque route qui reçoit un id, appelée par A avec un id de B (path ou payload).
+    // Le message d'erreur doit citer l'id de B : un 404 de routage ne suffit pas.
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn write_paths_answer_404_for_other_user_ids() {
+        let pool = test_pool();
+        let (user_a, user_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let (task_a, label_a, project_b, task_b, label_b, entry_b) = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            let project_a = seed_project(&mut conn, user_a);
+            let project_b = seed_project(&mut conn, user_b);
+            let task_b = seed_task(&mut conn, user_b, Some(project_b));
+            (
+                seed_task(&mut conn, user_a, Some(project_a)),
+                seed_label(&mut conn, user_a),
+                project_b,
+                task_b,
+                seed_label(&mut conn, user_b),
+                seed_time_entry(&mut conn, user_b, task_b),
+            )
+        };
+        // Facturation, corbeille et feuille de temps de B ; entrée de A pour split et merge
+        let (project_a, entry_a, client_b, rate_b, invoice_b) = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            let project_a = tasks::table
+                .find(task_a)
+                .select(tasks::project_id)
+                .first::<Option<Uuid>>(&mut conn)
+                .expect("Failed to load task of user A")
+                .unwrap();
+            let client_b: Uuid = diesel::insert_into(clients::table)
+                .values((clients::user_id.eq(user_b), clients::name.eq("Client B")))
+                .returning(clients::id)
+                .get_result(&mut conn)
+                .expect("Failed to seed client");
+            let rate_b: Uuid = diesel::insert_into(hourly_rates::table)
+                .values((
+                    hourly_rates::user_id.eq(user_b),
+                    hourly_rates::client_id.eq(Some(client_b)),
+                    hourly_rates::amount_cents.eq(10_000),
+                    hourly_rates::currency.eq("EUR"),
+                    hourly_rates::effective_from.eq(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()),
+                ))
+                .returning(hourly_rates::id)
+                .get_result(&mut conn)
+                .expect("Failed to seed rate");
+            let invoice_b: Uuid = diesel::insert_into(invoices::table)
+                .values(&NewInvoice {
+                    user_id: user_b,
+                    client_id: Some(client_b),
+                    sequence_number: 1,
+                    invoice_number: "INV-0001".to_string(),
+                    client_name: "Client B".to_string(),
+                    client_email: None,
+                    client_address: None,
+                    currency: "EUR".to_string(),
+                    period_start: NaiveDate::from_ymd_opt(2026, 9, 1).unwrap(),
+                    period_end: NaiveDate::from_ymd_opt(2026, 9, 30).unwrap(),
+                    issue_date: NaiveDate::from_ymd_opt(2026, 10, 1).unwrap(),
+                    due_date: None,
+                    group_by: "project".to_string(),
+                    rounding_increment_minutes: 0,
+                    rounding_direction: "up".to_string(),
+                    total_cents: 0,
+                    notes: None,
+                })
+                .returning(invoices::id)
+                .get_result(&mut conn)
+                .expect("Failed to seed invoice");
+            (
+                project_a,
+                seed_time_entry(&mut conn, user_a, task_a),
+                client_b,
+                rate_b,
+                invoice_b,
+            )
+        };
+        let (trashed_project_b, trashed_task_b, timesheet_b) = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            let trashed_project_b = seed_project(&mut conn, user_b);
+            let trashed_task_b = seed_task(&mut conn, user_b, Some(trashed_project_b));
+            let deleted = Some(Utc::now().naive_utc());
+            diesel::update(projects::table.find(trashed_project_b))
+                .set(projects::deleted_at.eq(deleted))
+                .execute(&mut conn)
+                .expect("Failed to trash project");
+            diesel::update(tasks::table.find(trashed_task_b))
+                .set(tasks::deleted_at.eq(deleted))
+                .execute(&mut conn)
+                .expect("Failed to trash task");
+            let approver = Uuid::new_v4();
+            for user in [user_b, approver] {
+                load_user_settings(&mut conn, user).expect("Failed to create user");
+            }
+            // Semaine de B soumise à un tiers : A n'en est ni l'auteur ni l'approbateur
+            let timesheet_b: Uuid = diesel::insert_into(timesheets::table)
+                .values(&NewTimesheet {
+                    user_id: user_b,
+                    approver_id: approver,
+                    iso_year: 2026,
+                    iso_week: 43,
+                    week_start: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                    week_end: NaiveDate::from_ymd_opt(2026, 10, 25).unwrap(),
+                    status: "submitted".to_string(),
+                    total_seconds: 0,
+                    submit_comment: None,
+                    submitted_at: Some(Utc::now()),
+                })
+                .returning(timesheets::id)
+                .get_result(&mut conn)
+                .expect("Failed to seed timesheet");
+            (trashed_project_b, trashed_task_b, timesheet_b)
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_auth_config()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let start = Utc::now() - Duration::hours(3);
+        let end = start + Duration::minutes(30);
+        // Milieu des entrées de seed_time_entry (une heure, il y a deux jours)
+        let entry_a_split_at = Utc::now() - Duration::days(2) + Duration::minutes(30);
+        let entry_b_split_at = entry_a_split_at;
+        let cases: Vec<(Method, String, Option<Value>, Uuid)> = vec![
+            (
+                Method::POST,
+                "/tasks".to_string(),
+                Some(json!({ "title": "Intrusion", "project_id": project_b })),
+                project_b,
+            ),
+            (
+                Method::POST,
+                "/tasks".to_string(),
+                Some(json!({ "title": "Intrusion", "parent_task_id": task_b })),
+                task_b,
+            ),
+            (
+                Method::PUT,
+                format!("/tasks/{}", task_b),
+                Some(json!({ "title": "Renamed" })),
+                task_b,
+            ),
+            (
+                Method::PUT,
+                format!("/tasks/{}", task_a),
+                Some(json!({ "project_id": project_b })),
+                project_b,
+            ),
+            (Method::DELETE, format!("/tasks/{}", task_b), None, task_b),
+            (
+                Method::POST,
+                format!("/tasks/{}/labels", task_a),
+                Some(json!({ "label_id": label_b })),
+                label_b,
+            ),
+            (
+                Method::POST,
+                format!("/tasks/{}/labels", task_b),
+                Some(json!({ "label_id": label_a })),
+                task_b,
+            ),
+            (
+                Method::DELETE,
+                format!("/tasks/{}/labels/{}", task_a, label_b),
+                None,
+                label_b,
+            ),
+            (
+                Method::POST,
+                format!("/tasks/{}/dependencies", task_a),
+                Some(json!({ "blocked_by_task_id": task_b })),
+                task_b,
+            ),
+            (
+                Method::PUT,
+                format!("/projects/{}", project_b),
+                Some(json!({ "name": "Renamed" })),
+                project_b,
+            ),
+            (
+                Method::DELETE,
+                format!("/projects/{}", project_b),
+                None,
+                project_b,
+            ),
+            (
+                Method::PUT,
+                format!("/labels/{}", label_b),
+                Some(json!({ "name": "Renamed" })),
+                label_b,
+            ),
+            (
+                Method::DELETE,
+                format!("/labels/{}", label_b),
+                None,
+                label_b,
+            ),
+            (
+                Method::POST,
+                "/time-entries".to_string(),
+                Some(json!({ "task_id": task_b, "start_time": start, "end_time": end })),
+                task_b,
+            ),
+            (
+                Method::POST,
+                "/time-entries".to_string(),
+                Some(json!({ "project_id": project_b, "start_time": start, "end_time": end })),
+                project_b,
+            ),
+            (
+                Method::PUT,
+                format!("/time-entries/{}", entry_b),
+                Some(json!({ "note": "Edited" })),
+                entry_b,
+            ),
+            (
+                Method::DELETE,
+                format!("/time-entries/{}", entry_b),
+                None,
+                entry_b,
+            ),
+            (
+                Method::POST,
+                "/time-entries/start".to_string(),
+                Some(json!({ "task_id": task_b })),
+                task_b,
+            ),
+            (
+                Method::POST,
+                "/pomodoro/start".to_string(),
+                Some(json!({ "task_id": task_b })),
+                task_b,
+            ),
+            (
+                Method::POST,
+                format!("/time-entries/{}/split", entry_b),
+                Some(json!({ "at": entry_b_split_at })),
+                entry_b,
+            ),
+            (
+                Method::POST,
+                format!("/time-entries/{}/split", entry_a),
+                Some(json!({ "at": entry_a_split_at, "task_id": task_b })),
+                task_b,
+            ),
+            (
+                Method::POST,
+                "/time-entries/merge".to_string(),
+                Some(json!({ "entry_ids": [entry_a, entry_b] })),
+                entry_b,
+            ),
+            (
+                Method::PUT,
+                format!("/clients/{}", client_b),
+                Some(json!({ "name": "Renamed" })),
+                client_b,
+            ),
+            (
+                Method::DELETE,
+                format!("/clients/{}", client_b),
+                None,
+                client_b,
+            ),
+            (
+                Method::POST,
+                "/projects".to_string(),
+                Some(json!({ "name": "Intrusion", "client_id": client_b })),
+                client_b,
+            ),
+            (
+                Method::PUT,
+                format!("/projects/{}", project_a),
+                Some(json!({ "client_id": client_b })),
+                client_b,
+            ),
+            (
+                Method::POST,
+                "/rates".to_string(),
+                Some(json!({
+                    "client_id": client_b,
+                    "amount_cents": 1,
+                    "currency": "EUR",
+                    "effective_from": "2026-01-01",
+                })),
+                client_b,
+            ),
+            (
+                Method::POST,
+                "/rates".to_string(),
+                Some(json!({
+                    "project_id": project_b,
+                    "amount_cents": 1,
+                    "currency": "EUR",
+                    "effective_from": "2026-01-01",
+                })),
+                project_b,
+            ),
+            (
+                Method::POST,
+                "/rates".to_string(),
+                Some(json!({
+                    "task_id": task_b,
+                    "amount_cents": 1,
+                    "currency": "EUR",
+                    "effective_from": "2026-01-01",
+                })),
+                task_b,
+            ),
+            (Method::DELETE, format!("/rates/{}", rate_b), None, rate_b),
+            (
+                Method::POST,
+                "/invoices".to_string(),
+                Some(json!({
+                    "client_id": client_b,
+                    "date_from": "2026-09-01",
+                    "date_to": "2026-09-30",
+                })),
+                client_b,
+            ),
+            (
+                Method::PUT,
+                format!("/invoices/{}/status", invoice_b),
+                Some(json!({ "status": "sent" })),
+                invoice_b,
+            ),
+            (
+                Method::DELETE,
+                format!("/invoices/{}", invoice_b),
+                None,
+                invoice_b,
+            ),
+            (
+                Method::POST,
+                format!("/projects/{}/board/move", project_b),
+                Some(json!({ "task_id": task_b, "status": "done" })),
+                project_b,
+            ),
+            (
+                Method::POST,
+                format!("/projects/{}/board/move", project_a),
+                Some(json!({ "task_id": task_b, "status": "done" })),
+                task_b,
+            ),
+            (
+                Method::PUT,
+                format!("/projects/{}/workflow", project_b),
+                Some(json!({ "statuses": [] })),
+                project_b,
+            ),
+            (
+                Method::POST,
+                format!("/trash/tasks/{}/restore", trashed_task_b),
+                None,
+                trashed_task_b,
+            ),
+            (
+                Method::POST,
+                format!("/trash/projects/{}/restore", trashed_project_b),
+                None,
+                trashed_project_b,
+            ),
+            (
+                Method::DELETE,
+                format!("/trash/tasks/{}", trashed_task_b),
+                None,
+                trashed_task_b,
+            ),
+            (
+                Method::DELETE,
+                format!("/trash/projects/{}", trashed_project_b),
+                None,
+                trashed_project_b,
+            ),
+            (
+                Method::POST,
+                format!("/timesheets/{}/approve", timesheet_b),
+                Some(json!({})),
+                timesheet_b,
+            ),
+            (
+                Method::POST,
+                format!("/timesheets/{}/reject", timesheet_b),
+                Some(json!({ "comment": "Intrusion" })),
+                timesheet_b,
+            ),
+            (
+                Method::POST,
+                format!("/timesheets/{}/reopen", timesheet_b),
+                Some(json!({})),
+                timesheet_b,
+            ),
+        ];
+
+        for (method, uri, body, foreign_id) in cases {
+            let mut request = test::TestRequest::default()
+                .method(method.clone())
+                .uri(&uri)
+                .insert_header(("X-User-Id", user_a.to_string()));
+            if let Some(body) = body {
+                request = request.set_json(body);
+            }
+            let response = test::call_service(&app, request.to_request()).await;
+            assert_eq!(
+                response.status(),
+                StatusCode::NOT_FOUND,
+                "{} {}",
+                method,
+                uri
+            );
+            let body: Value = test::read_body_json(response).await;
+            let message = body["message"].as_str().unwrap_or_default();
+            assert!(
+                message.contains(&foreign_id.to_string()),
+                "{} {}: unexpected error message {:?}",
+                method,
+                uri,
+                message
+            );
+        }
+
+        // Contrôle positif : A peut écrire sur ses propres ids avec les mêmes routes
+        let own_update = test::TestRequest::put()
+            .uri(&format!("/tasks/{}", task_a))
+            .insert_header(("X-User-Id", user_a.to_string()))
+            .set_json(json!({ "title": "Renamed by owner" }))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, own_update).await.status(),
+            StatusCode::OK
+        );
+
+        // Les données de B sont intactes
+        let mut conn = pool.get().expect("Failed to get test connection");
+        diesel::sql_query("RESET ROLE")
+            .execute(&mut conn)
+            .expect("Failed to reset role");
+        let task_b_title = tasks::table
+            .filter(tasks::id.eq(task_b))
+            .filter(tasks::deleted_at.is_null())
+            .select(tasks::title)
+            .first::<String>(&mut conn)
+            .expect("Task of user B should still exist");
+        assert_eq!(task_b_title, "Seeded task");
+        let project_b_name = projects::table
+            .filter(projects::id.eq(project_b))
+            .filter(projects::deleted_at.is_null())
+            .select(projects::name)
+            .first::<String>(&mut conn)
+            .expect("Project of user B should still exist");
+        assert_eq!(project_b_name, "Seeded project");
+        let label_b_name = labels::table
+            .find(label_b)
+            .select(labels::name)
+            .first::<String>(&mut conn)
+            .expect("Label of user B should still exist");
+        assert_eq!(label_b_name, "Seeded label");
+        let entry_b_note = time_entries::table
+            .find(entry_b)
+            .select(time_entries::note)
+            .first::<Option<String>>(&mut conn)
+            .expect("Time entry of user B should still exist");
+        assert_eq!(entry_b_note, None);
+        let attached_labels = task_labels::table
+            .filter(task_labels::task_id.eq_any([task_a, task_b]))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .expect("Failed to count task labels");
+        assert_eq!(attached_labels, 0);
+        let tasks_of_a = tasks::table
+            .filter(tasks::user_id.eq(user_a))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .expect("Failed to count tasks");
+        assert_eq!(tasks_of_a, 1);
+        let entries_of_a = time_entries::table
+            .filter(time_entries::user_id.eq(user_a))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .expect("Failed to count time entries");
+        // entry_a seulement : ni seconde partie de split, ni fusion
+        assert_eq!(entries_of_a, 1);
+        let entries_of_b = time_entries::table
+            .filter(time_entries::user_id.eq(user_b))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .expect("Failed to count time entries");
+        assert_eq!(entries_of_b, 1);
+        let client_b_name = clients::table
+            .find(client_b)
+            .select(clients::name)
+            .first::<String>(&mut conn)
+            .expect("Client of user B should still exist");
+        assert_eq!(client_b_name, "Client B");
+        let project_a_client = projects::table
+            .find(project_a)
+            .select(projects::client_id)
+            .first::<Option<Uuid>>(&mut conn)
+            .expect("Failed to load project of user A");
+        assert_eq!(project_a_client, None);
+        let rates = hourly_rates::table
+            .filter(hourly_rates::user_id.eq_any([user_a, user_b]))
+            .select(hourly_rates::id)
+            .load::<Uuid>(&mut conn)
+            .expect("Failed to load rates");
+        assert_eq!(rates, vec![rate_b]);
+        let invoice_b_status = invoices::table
+            .find(invoice_b)
+            .select(invoices::status)
+            .first::<String>(&mut conn)
+            .expect("Invoice of user B should still exist");
+        assert_eq!(invoice_b_status, "draft");
+        let invoices_of_a = invoices::table
+            .filter(invoices::user_id.eq(user_a))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .expect("Failed to count invoices");
+        assert_eq!(invoices_of_a, 0);
+        let task_b_status = tasks::table
+            .find(task_b)
+            .select(tasks::status)
+            .first::<String>(&mut conn)
+            .expect("Task of user B should still exist");
+        assert_ne!(task_b_status, "done");
+        let still_trashed = tasks::table
+            .filter(tasks::id.eq(trashed_task_b))
+            .filter(tasks::deleted_at.is_not_null())
+            .count()
+            .get_result::<i64>(&mut conn)
+            .expect("Failed to count trashed tasks")
+            + projects::table
+                .filter(projects::id.eq(trashed_project_b))
+                .filter(projects::deleted_at.is_not_null())
+                .count()
+                .get_result::<i64>(&mut conn)
+                .expect("Failed to count trashed projects");
+        assert_eq!(still_trashed, 2);
+        let timesheet_b_status = timesheets::table
+            .find(timesheet_b)
+            .select(timesheets::status)
+            .first::<String>(&mut conn)
+            .expect("Timesheet of user B should still exist");
+        assert_eq!(timesheet_b_status, "submitted");
+    }
+}
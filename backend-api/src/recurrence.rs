@@ -0,0 +1,624 @@
+// OptiTask/backend-api/src/recurrence.rs
+// Tâches récurrentes : règles RRULE (sous-ensemble de la RFC 5545) et génération de
+// l'occurrence suivante.
+// Parties reconnues : FREQ (DAILY, WEEKLY, MONTHLY, YEARLY), INTERVAL, BYDAY, BYMONTHDAY,
+// COUNT et UNTIL. Les occurrences sont des jours (due_date) : UNTIL ne garde que sa date,
+// les semaines commencent le lundi et une règle YEARLY reste dans le mois de l'occurrence.
+// L'occurrence suivante est calculée à partir de la due_date de l'occurrence courante.
+
+use crate::error_handler::ServiceError;
+use crate::models::{NewTask, NewTaskLabelAssociation, Task};
+use crate::schema::{task_labels, tasks};
+use crate::workflows::load_workflow;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use diesel::dsl::exists;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::Uuid as DieselUuid;
+use std::collections::HashSet;
+use std::fmt;
+use uuid::Uuid;
+
+// Borne de INTERVAL (aussi celle de la recherche de l'occurrence suivante)
+pub const MAX_RECURRENCE_INTERVAL: u32 = 99;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+
+    // Jours maximum d'une période
+    fn period_days(&self) -> i64 {
+        match self {
+            Frequency::Daily => 1,
+            Frequency::Weekly => 7,
+            Frequency::Monthly => 31,
+            Frequency::Yearly => 366,
+        }
+    }
+}
+
+// Élément de BYDAY : "MO", ou "1MO" / "-1FR" (n-ième jour du mois, MONTHLY et YEARLY)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByDay {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+impl ByDay {
+    fn matches(&self, date: NaiveDate) -> bool {
+        if date.weekday() != self.weekday {
+            return false;
+        }
+        match self.ordinal {
+            Some(n) if n > 0 => ((date.day() - 1) / 7 + 1) as i32 == n,
+            Some(n) => ((days_in_month(date) - date.day()) / 7 + 1) as i32 == -n,
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<ByDay>,
+    pub by_month_day: Vec<i32>,
+    pub count: Option<i32>,
+    pub until: Option<NaiveDate>,
+}
+
+fn invalid_rule(reason: String) -> ServiceError {
+    ServiceError::BadRequest(format!("Invalid recurrence_rule: {}", reason))
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_by_day(value: &str) -> Result<ByDay, ServiceError> {
+    let split_at = value.len().saturating_sub(2);
+    let (ordinal, code) = value.split_at(split_at);
+    let weekday = match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(invalid_rule(format!("unknown BYDAY value '{}'", value))),
+    };
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        Some(
+            ordinal
+                .trim_start_matches('+')
+                .parse::<i32>()
+                .ok()
+                .filter(|n| *n != 0 && n.abs() <= 5)
+                .ok_or_else(|| invalid_rule(format!("unknown BYDAY value '{}'", value)))?,
+        )
+    };
+    Ok(ByDay { ordinal, weekday })
+}
+
+fn parse_month_day(value: &str) -> Result<i32, ServiceError> {
+    value
+        .trim_start_matches('+')
+        .parse::<i32>()
+        .ok()
+        .filter(|day| *day != 0 && day.abs() <= 31)
+        .ok_or_else(|| {
+            invalid_rule(format!(
+                "BYMONTHDAY must be between 1 and 31 or -31 and -1, got '{}'",
+                value
+            ))
+        })
+}
+
+// UNTIL : AAAAMMJJ ou AAAAMMJJTHHMMSS[Z] (seule la date est gardée)
+fn parse_until(value: &str) -> Result<NaiveDate, ServiceError> {
+    let date_part = match value.split_once('T') {
+        Some((date_part, _)) => date_part,
+        None => value,
+    };
+    NaiveDate::parse_from_str(date_part, "%Y%m%d")
+        .map_err(|_| invalid_rule(format!("UNTIL must be a YYYYMMDD date, got '{}'", value)))
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first_of_next_month| first_of_next_month.pred_opt())
+        .map(|last_day| last_day.day())
+        .unwrap_or(31)
+}
+
+fn month_day_matches(date: NaiveDate, month_day: i32) -> bool {
+    if month_day > 0 {
+        date.day() as i32 == month_day
+    } else {
+        date.day() as i32 == days_in_month(date) as i32 + month_day + 1
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn month_number(date: NaiveDate) -> i64 {
+    date.year() as i64 * 12 + date.month0() as i64
+}
+
+impl RecurrenceRule {
+    // Accepte "FREQ=...;..." avec ou sans préfixe "RRULE:", sans tenir compte de la casse
+    pub fn parse(raw: &str) -> Result<Self, ServiceError> {
+        let upper = raw.trim().to_ascii_uppercase();
+        let body = upper.strip_prefix("RRULE:").unwrap_or(&upper);
+        if !body.is_ascii() {
+            return Err(invalid_rule(
+                "only ASCII characters are allowed".to_string(),
+            ));
+        }
+
+        let mut seen_parts = HashSet::new();
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_day = Vec::new();
+        let mut by_month_day = Vec::new();
+        let mut count = None;
+        let mut until = None;
+
+        for part in body.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid_rule(format!("'{}' is not a KEY=VALUE part", part)))?;
+            if !seen_parts.insert(key) {
+                return Err(invalid_rule(format!("{} is given more than once", key)));
+            }
+            match key {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => {
+                            return Err(invalid_rule(format!(
+                                "FREQ must be DAILY, WEEKLY, MONTHLY or YEARLY, got '{}'",
+                                value
+                            )))
+                        }
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| (1..=MAX_RECURRENCE_INTERVAL).contains(interval))
+                        .ok_or_else(|| {
+                            invalid_rule(format!(
+                                "INTERVAL must be between 1 and {}, got '{}'",
+                                MAX_RECURRENCE_INTERVAL, value
+                            ))
+                        })?
+                }
+                "BYDAY" => {
+                    by_day = value
+                        .split(',')
+                        .map(parse_by_day)
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => {
+                    by_month_day = value
+                        .split(',')
+                        .map(parse_month_day)
+                        .collect::<Result<_, _>>()?
+                }
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .parse::<i32>()
+                            .ok()
+                            .filter(|count| *count >= 1)
+                            .ok_or_else(|| {
+                                invalid_rule(format!(
+                                    "COUNT must be a positive integer, got '{}'",
+                                    value
+                                ))
+                            })?,
+                    )
+                }
+                "UNTIL" => until = Some(parse_until(value)?),
+                _ => {
+                    return Err(invalid_rule(format!(
+                        "{} is not supported (FREQ, INTERVAL, BYDAY, BYMONTHDAY, COUNT, UNTIL)",
+                        key
+                    )))
+                }
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| invalid_rule("FREQ is required".to_string()))?;
+        if count.is_some() && until.is_some() {
+            return Err(invalid_rule(
+                "COUNT and UNTIL cannot be used together".to_string(),
+            ));
+        }
+        if frequency == Frequency::Weekly && !by_month_day.is_empty() {
+            return Err(invalid_rule(
+                "BYMONTHDAY cannot be used with FREQ=WEEKLY".to_string(),
+            ));
+        }
+        if matches!(frequency, Frequency::Daily | Frequency::Weekly)
+            && by_day.iter().any(|day| day.ordinal.is_some())
+        {
+            return Err(invalid_rule(
+                "numbered BYDAY values (e.g. 1MO) need FREQ=MONTHLY or FREQ=YEARLY".to_string(),
+            ));
+        }
+
+        Ok(RecurrenceRule {
+            frequency,
+            interval,
+            by_day,
+            by_month_day,
+            count,
+            until,
+        })
+    }
+
+    // `date` est dans une période retenue par INTERVAL, comptée depuis `anchor`
+    fn in_period(&self, date: NaiveDate, anchor: NaiveDate) -> bool {
+        let interval = self.interval as i64;
+        match self.frequency {
+            Frequency::Daily => (date - anchor).num_days() % interval == 0,
+            Frequency::Weekly => {
+                ((week_start(date) - week_start(anchor)).num_days() / 7) % interval == 0
+            }
+            Frequency::Monthly => (month_number(date) - month_number(anchor)) % interval == 0,
+            Frequency::Yearly => {
+                date.month() == anchor.month()
+                    && (date.year() - anchor.year()) as i64 % interval == 0
+            }
+        }
+    }
+
+    // Sans BYDAY ni BYMONTHDAY, le jour est celui de l'ancre (jour de semaine, du mois)
+    fn matches_day(&self, date: NaiveDate, anchor: NaiveDate) -> bool {
+        if self.by_day.is_empty() && self.by_month_day.is_empty() {
+            return match self.frequency {
+                Frequency::Daily => true,
+                Frequency::Weekly => date.weekday() == anchor.weekday(),
+                Frequency::Monthly | Frequency::Yearly => date.day() == anchor.day(),
+            };
+        }
+        (self.by_month_day.is_empty()
+            || self
+                .by_month_day
+                .iter()
+                .any(|month_day| month_day_matches(date, *month_day)))
+            && (self.by_day.is_empty() || self.by_day.iter().any(|day| day.matches(date)))
+    }
+
+    // Date de l'occurrence qui suit `current` (de rang `current_index`) ; None quand la
+    // série est terminée (COUNT, UNTIL) ou qu'aucune date ne correspond à la règle
+    pub fn next_occurrence(&self, current: NaiveDate, current_index: i32) -> Option<NaiveDate> {
+        if self.count.is_some_and(|count| current_index >= count) {
+            return None;
+        }
+        let horizon_days = self.frequency.period_days() * self.interval as i64 * 8;
+        let mut date = current;
+        for _ in 0..horizon_days {
+            date = date.succ_opt()?;
+            if self.until.is_some_and(|until| date > until) {
+                return None;
+            }
+            if self.in_period(date, current) && self.matches_day(date, current) {
+                return Some(date);
+            }
+        }
+        None
+    }
+}
+
+// Forme normalisée enregistrée sur la tâche
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|day| match day.ordinal {
+                    Some(n) => format!("{}{}", n, weekday_code(day.weekday)),
+                    None => weekday_code(day.weekday).to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let month_days: Vec<String> = self
+                .by_month_day
+                .iter()
+                .map(|day| day.to_string())
+                .collect();
+            write!(f, ";BYMONTHDAY={}", month_days.join(","))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        Ok(())
+    }
+}
+
+// Valide et normalise une règle reçue par l'API
+pub fn normalize_rule(raw: &str) -> Result<String, ServiceError> {
+    RecurrenceRule::parse(raw).map(|rule| rule.to_string())
+}
+
+// Crée l'occurrence suivante de `task` : mêmes titre, description, projet, tâche parente,
+// facturation et labels, statut initial du workflow. `not_before` saute les dates déjà passées (chaque
+// date sautée compte pour COUNT).
+// Ne fait rien si la tâche n'est pas récurrente, si la série est terminée ou si une
+// occurrence plus récente existe déjà (y compris dans la corbeille).
+pub fn spawn_next_occurrence(
+    conn: &mut PgConnection,
+    task: &Task,
+    not_before: Option<NaiveDate>,
+) -> Result<Option<Task>, ServiceError> {
+    let (Some(raw_rule), Some(current_due)) = (task.recurrence_rule.as_deref(), task.due_date)
+    else {
+        return Ok(None);
+    };
+    let rule = RecurrenceRule::parse(raw_rule)?;
+    let series_id = task.recurrence_series_id.unwrap_or(task.id);
+
+    // Sérialise les générations d'une même série (complétion et planificateur)
+    sql_query("SELECT pg_advisory_xact_lock(hashtext($1::TEXT))")
+        .bind::<DieselUuid, _>(series_id)
+        .execute(conn)?;
+    let later_occurrence_exists = diesel::select(exists(
+        tasks::table
+            .filter(tasks::recurrence_series_id.eq(series_id))
+            .filter(tasks::recurrence_index.gt(task.recurrence_index)),
+    ))
+    .get_result::<bool>(conn)?;
+    if later_occurrence_exists {
+        return Ok(None);
+    }
+
+    let Some(mut next_due) = rule.next_occurrence(current_due, task.recurrence_index) else {
+        return Ok(None);
+    };
+    let mut next_index = task.recurrence_index + 1;
+    if let Some(not_before) = not_before {
+        while next_due < not_before {
+            match rule.next_occurrence(next_due, next_index) {
+                Some(date) => {
+                    next_due = date;
+                    next_index += 1;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    let workflow = load_workflow(conn, task.user_id, task.project_id)?;
+    let initial_status = workflow.initial_status()?;
+    let next_occurrence = diesel::insert_into(tasks::table)
+        .values(&NewTask {
+            user_id: task.user_id,
+            project_id: task.project_id,
+            title: task.title.clone(),
+            description: task.description.clone(),
+            status: Some(initial_status.key.clone()),
+            status_category: initial_status.category.clone(),
+            due_date: Some(next_due),
+            order: task.order,
+            billable: task.billable,
+            parent_task_id: task.parent_task_id,
+            recurrence_rule: task.recurrence_rule.clone(),
+            recurrence_series_id: Some(series_id),
+            recurrence_index: Some(next_index),
+        })
+        .get_result::<Task>(conn)?;
+
+    let label_ids: Vec<Uuid> = task_labels::table
+        .filter(task_labels::task_id.eq(task.id))
+        .select(task_labels::label_id)
+        .load::<Uuid>(conn)?;
+    if !label_ids.is_empty() {
+        let associations: Vec<NewTaskLabelAssociation> = label_ids
+            .into_iter()
+            .map(|label_id| NewTaskLabelAssociation {
+                task_id: next_occurrence.id,
+                label_id,
+            })
+            .collect();
+        diesel::insert_into(task_labels::table)
+            .values(&associations)
+            .execute(conn)?;
+    }
+
+    Ok(Some(next_occurrence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn next(rule: &str, current: &str) -> Option<NaiveDate> {
+        RecurrenceRule::parse(rule)
+            .unwrap()
+            .next_occurrence(date(current), 1)
+    }
+
+    #[test]
+    fn parse_normalizes_accepted_rules() {
+        for (raw, normalized) in [
+            ("rrule:freq=weekly;byday=mo,fr", "FREQ=WEEKLY;BYDAY=MO,FR"),
+            ("FREQ=DAILY;INTERVAL=1", "FREQ=DAILY"),
+            ("FREQ=MONTHLY;BYDAY=+2MO", "FREQ=MONTHLY;BYDAY=2MO"),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=6",
+                "FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=6",
+            ),
+            (
+                "FREQ=YEARLY;UNTIL=20301231T235959Z",
+                "FREQ=YEARLY;UNTIL=20301231",
+            ),
+        ] {
+            assert_eq!(normalize_rule(raw).unwrap(), normalized, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_rules_outside_the_subset() {
+        for raw in [
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;FREQ=WEEKLY",
+            "FREQ=DAILY;BYHOUR=9",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;INTERVAL=100",
+            "FREQ=DAILY;COUNT=0",
+            "FREQ=DAILY;COUNT=3;UNTIL=20301231",
+            "FREQ=WEEKLY;BYMONTHDAY=1",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=MONTHLY;BYDAY=6MO",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=YEARLY;UNTIL=2030-12-31",
+            "FREQ=DAILY;NOVALUE",
+        ] {
+            assert!(RecurrenceRule::parse(raw).is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn daily_and_weekly_intervals() {
+        assert_eq!(
+            next("FREQ=DAILY;INTERVAL=3", "2026-10-17"),
+            Some(date("2026-10-20"))
+        );
+        // Sans BYDAY : même jour de semaine
+        assert_eq!(next("FREQ=WEEKLY", "2026-10-16"), Some(date("2026-10-23")));
+        assert_eq!(
+            next("FREQ=WEEKLY;BYDAY=MO,WE,FR", "2026-10-16"),
+            Some(date("2026-10-19"))
+        );
+        // Les jours restants de la semaine courante, puis la semaine retenue suivante
+        assert_eq!(
+            next("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR", "2026-10-12"),
+            Some(date("2026-10-14"))
+        );
+        assert_eq!(
+            next("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR", "2026-10-16"),
+            Some(date("2026-10-26"))
+        );
+    }
+
+    #[test]
+    fn monthly_rules_handle_month_ends() {
+        // Le 31 saute les mois qui n'en ont pas
+        assert_eq!(next("FREQ=MONTHLY", "2026-01-31"), Some(date("2026-03-31")));
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=31", "2026-04-15"),
+            Some(date("2026-05-31"))
+        );
+        // -1 : dernier jour du mois, années bissextiles comprises
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=-1", "2026-01-31"),
+            Some(date("2026-02-28"))
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=-1", "2028-01-31"),
+            Some(date("2028-02-29"))
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYDAY=-1FR", "2026-10-30"),
+            Some(date("2026-11-27"))
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYDAY=2MO", "2026-10-12"),
+            Some(date("2026-11-09"))
+        );
+    }
+
+    #[test]
+    fn yearly_rules_stay_in_the_anchor_month() {
+        // Plus de lundi en octobre 2026 après le 26 : premier lundi d'octobre 2027
+        assert_eq!(
+            next("FREQ=YEARLY;BYDAY=MO", "2026-10-26"),
+            Some(date("2027-10-04"))
+        );
+        assert_eq!(next("FREQ=YEARLY", "2024-02-29"), Some(date("2028-02-29")));
+    }
+
+    #[test]
+    fn search_stops_at_the_horizon_of_eight_periods() {
+        // Février n'a jamais de 30 : rien dans les 8 années examinées
+        assert_eq!(next("FREQ=YEARLY;BYMONTHDAY=30", "2026-02-10"), None);
+        // Un 29 février est à 4 ans d'un autre, à 8 ans quand une année séculaire s'intercale
+        assert_eq!(next("FREQ=YEARLY", "2096-02-29"), Some(date("2104-02-29")));
+        assert_eq!(
+            next("FREQ=YEARLY;INTERVAL=3", "2024-02-29"),
+            Some(date("2036-02-29"))
+        );
+    }
+
+    #[test]
+    fn count_and_until_end_the_series() {
+        let rule = RecurrenceRule::parse("FREQ=DAILY;COUNT=2").unwrap();
+        assert_eq!(
+            rule.next_occurrence(date("2026-10-17"), 1),
+            Some(date("2026-10-18"))
+        );
+        assert_eq!(rule.next_occurrence(date("2026-10-18"), 2), None);
+
+        assert_eq!(
+            next("FREQ=DAILY;UNTIL=20261018", "2026-10-17"),
+            Some(date("2026-10-18"))
+        );
+        assert_eq!(next("FREQ=DAILY;UNTIL=20261018", "2026-10-18"), None);
+    }
+}
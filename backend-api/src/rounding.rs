@@ -0,0 +1,174 @@
+// OptiTask/backend-api/src/rounding.rs
+// Arrondi du temps facturé. Le temps suivi (duration_seconds) n'est jamais modifié :
+// l'arrondi s'applique au moment de valoriser (factures, rapports).
+
+use crate::error_handler::ServiceError;
+
+// Arrondi maximal accepté (une journée de travail)
+pub const MAX_ROUNDING_INCREMENT_MINUTES: i32 = 480;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundingDirection {
+    Up,
+    Down,
+    Nearest,
+}
+
+impl RoundingDirection {
+    pub fn parse(value: Option<&str>) -> Result<Self, ServiceError> {
+        match value {
+            Some("up") | None => Ok(RoundingDirection::Up),
+            Some("down") => Ok(RoundingDirection::Down),
+            Some("nearest") => Ok(RoundingDirection::Nearest),
+            Some(other) => Err(ServiceError::BadRequest(format!(
+                "Invalid rounding direction: {}. Supported: up, down, nearest.",
+                other
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoundingDirection::Up => "up",
+            RoundingDirection::Down => "down",
+            RoundingDirection::Nearest => "nearest",
+        }
+    }
+}
+
+// Unité arrondie : chaque entrée, ou le total d'un jour local (par projet)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundingScope {
+    Entry,
+    Day,
+}
+
+impl RoundingScope {
+    pub fn parse(value: Option<&str>) -> Result<Self, ServiceError> {
+        match value {
+            Some("entry") | None => Ok(RoundingScope::Entry),
+            Some("day") => Ok(RoundingScope::Day),
+            Some(other) => Err(ServiceError::BadRequest(format!(
+                "Invalid rounding scope: {}. Supported: entry, day.",
+                other
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoundingScope::Entry => "entry",
+            RoundingScope::Day => "day",
+        }
+    }
+}
+
+pub fn validate_increment_minutes(increment_minutes: i32) -> Result<(), ServiceError> {
+    if !(0..=MAX_ROUNDING_INCREMENT_MINUTES).contains(&increment_minutes) {
+        return Err(ServiceError::BadRequest(format!(
+            "rounding_increment_minutes must be between 0 and {}.",
+            MAX_ROUNDING_INCREMENT_MINUTES
+        )));
+    }
+    Ok(())
+}
+
+// Arrondit `seconds` au multiple de `increment_minutes` (0 : inchangé).
+// En mode 'nearest', une demi-période est arrondie au supérieur.
+pub fn round_seconds(seconds: i64, increment_minutes: i32, direction: RoundingDirection) -> i64 {
+    if increment_minutes <= 0 || seconds <= 0 {
+        return seconds.max(0);
+    }
+    let increment = i64::from(increment_minutes) * 60;
+    let remainder = seconds % increment;
+    if remainder == 0 {
+        return seconds;
+    }
+    let floor = seconds - remainder;
+    match direction {
+        RoundingDirection::Up => floor + increment,
+        RoundingDirection::Down => floor,
+        RoundingDirection::Nearest if remainder * 2 >= increment => floor + increment,
+        RoundingDirection::Nearest => floor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_connection;
+    use diesel::prelude::*;
+    use diesel::sql_query;
+    use diesel::sql_types::{Array, BigInt, Integer, Text};
+
+    #[derive(QueryableByName)]
+    struct SqlRounded {
+        #[diesel(sql_type = BigInt)]
+        rounded: i64,
+    }
+
+    // round_duration_seconds (migration 2026-10-17-190000) doit rester identique à
+    // round_seconds : rapports SQL et factures arrondissent les mêmes durées.
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn sql_rounding_matches_round_seconds() {
+        let mut conn = test_connection();
+        let directions = [
+            RoundingDirection::Up,
+            RoundingDirection::Down,
+            RoundingDirection::Nearest,
+        ];
+        let mut cases: Vec<(i64, i32, RoundingDirection)> = Vec::new();
+        for increment_minutes in [0, 1, 5, 6, 7, 15, 30, 60, MAX_ROUNDING_INCREMENT_MINUTES] {
+            let increment = i64::from(increment_minutes.max(1)) * 60;
+            // Bords : zéro, négatif, autour de la demi-période et des multiples
+            let seconds = [
+                -61,
+                -1,
+                0,
+                1,
+                59,
+                60,
+                61,
+                increment / 2 - 1,
+                increment / 2,
+                increment / 2 + 1,
+                increment - 1,
+                increment,
+                increment + 1,
+                3 * increment + increment / 2,
+                3 * increment + increment / 2 - 1,
+                36_000 + 1,
+                86_400 * 7 - 1,
+            ];
+            for seconds in seconds {
+                for direction in directions {
+                    cases.push((seconds, increment_minutes, direction));
+                }
+            }
+        }
+
+        let rounded = sql_query(
+            "SELECT round_duration_seconds(s, i, d) AS rounded \
+             FROM unnest($1::BIGINT[], $2::INTEGER[], $3::TEXT[]) WITH ORDINALITY AS c(s, i, d, n) \
+             ORDER BY n",
+        )
+        .bind::<Array<BigInt>, _>(cases.iter().map(|case| case.0).collect::<Vec<_>>())
+        .bind::<Array<Integer>, _>(cases.iter().map(|case| case.1).collect::<Vec<_>>())
+        .bind::<Array<Text>, _>(cases.iter().map(|case| case.2.as_str()).collect::<Vec<_>>())
+        .load::<SqlRounded>(&mut conn)
+        .expect("Failed to call round_duration_seconds");
+
+        assert_eq!(rounded.len(), cases.len());
+        for ((seconds, increment_minutes, direction), sql) in cases.iter().zip(&rounded) {
+            assert_eq!(
+                sql.rounded,
+                round_seconds(*seconds, *increment_minutes, *direction),
+                "{} s, {} min, {}",
+                seconds,
+                increment_minutes,
+                direction.as_str()
+            );
+        }
+    }
+}
@@ -1,5 +1,92 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        name -> Text,
+        token_prefix -> Text,
+        token_hash -> Text,
+        scopes -> Array<Text>,
+        last_used_at -> Nullable<Timestamptz>,
+        expires_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    clients (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        name -> Text,
+        email -> Nullable<Text>,
+        address -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    hourly_rates (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        client_id -> Nullable<Uuid>,
+        project_id -> Nullable<Uuid>,
+        task_id -> Nullable<Uuid>,
+        amount_cents -> Int8,
+        currency -> Text,
+        effective_from -> Date,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    invoice_line_items (id) {
+        id -> Uuid,
+        invoice_id -> Uuid,
+        user_id -> Uuid,
+        project_id -> Nullable<Uuid>,
+        task_id -> Nullable<Uuid>,
+        description -> Text,
+        quantity_seconds -> Int8,
+        hourly_rate_cents -> Int8,
+        amount_cents -> Int8,
+        position -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    invoices (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        client_id -> Nullable<Uuid>,
+        sequence_number -> Int4,
+        invoice_number -> Text,
+        status -> Text,
+        client_name -> Text,
+        client_email -> Nullable<Text>,
+        client_address -> Nullable<Text>,
+        currency -> Text,
+        period_start -> Date,
+        period_end -> Date,
+        issue_date -> Date,
+        due_date -> Nullable<Date>,
+        group_by -> Text,
+        rounding_increment_minutes -> Int4,
+        rounding_direction -> Text,
+        total_cents -> Int8,
+        notes -> Nullable<Text>,
+        sent_at -> Nullable<Timestamptz>,
+        paid_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     labels (id) {
         id -> Uuid,
@@ -11,6 +98,40 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    pomodoro_interruptions (id) {
+        id -> Uuid,
+        session_id -> Uuid,
+        user_id -> Uuid,
+        phase -> Text,
+        reason -> Nullable<Text>,
+        occurred_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    pomodoro_sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        task_id -> Uuid,
+        status -> Text,
+        phase -> Text,
+        work_minutes -> Int4,
+        short_break_minutes -> Int4,
+        long_break_minutes -> Int4,
+        cycles_before_long_break -> Int4,
+        completed_work_cycles -> Int4,
+        interruption_count -> Int4,
+        phase_duration_seconds -> Int4,
+        phase_elapsed_seconds -> Int4,
+        phase_resumed_at -> Timestamptz,
+        paused_at -> Nullable<Timestamptz>,
+        ended_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     projects (id) {
         id -> Uuid,
@@ -19,6 +140,22 @@ diesel::table! {
         color -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        client_id -> Nullable<Uuid>,
+        billable -> Bool,
+        rounding_increment_minutes -> Int4,
+        rounding_direction -> Text,
+        rounding_scope -> Text,
+        deleted_at -> Nullable<Timestamptz>,
+        approver_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    task_dependencies (task_id, blocked_by_task_id) {
+        task_id -> Uuid,
+        blocked_by_task_id -> Uuid,
+        user_id -> Uuid,
+        created_at -> Timestamptz,
     }
 }
 
@@ -29,6 +166,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    task_status_transitions (id) {
+        id -> Uuid,
+        task_id -> Uuid,
+        user_id -> Uuid,
+        from_status -> Nullable<Text>,
+        to_status -> Text,
+        transitioned_at -> Timestamptz,
+        to_category -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     tasks (id) {
         id -> Uuid,
@@ -41,6 +190,14 @@ diesel::table! {
         task_order -> Nullable<Int4>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        completed_at -> Nullable<Timestamptz>,
+        billable -> Nullable<Bool>,
+        deleted_at -> Nullable<Timestamptz>,
+        parent_task_id -> Nullable<Uuid>,
+        recurrence_rule -> Nullable<Text>,
+        recurrence_series_id -> Nullable<Uuid>,
+        recurrence_index -> Int4,
+        status_category -> Text,
     }
 }
 
@@ -48,38 +205,150 @@ diesel::table! {
     time_entries (id) {
         id -> Uuid,
         user_id -> Uuid,
-        task_id -> Uuid,
+        task_id -> Nullable<Uuid>,
         start_time -> Timestamptz,
         end_time -> Nullable<Timestamptz>,
         duration_seconds -> Nullable<Int4>,
         is_pomodoro_session -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        pomodoro_session_id -> Nullable<Uuid>,
+        auto_stop_reason -> Nullable<Text>,
+        auto_stopped_at -> Nullable<Timestamptz>,
+        billable -> Nullable<Bool>,
+        invoice_id -> Nullable<Uuid>,
+        note -> Nullable<Text>,
+        project_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    timesheet_lines (id) {
+        id -> Uuid,
+        timesheet_id -> Uuid,
+        project_id -> Nullable<Uuid>,
+        project_name -> Text,
+        work_date -> Date,
+        seconds -> Int8,
+    }
+}
+
+diesel::table! {
+    timesheets (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        approver_id -> Uuid,
+        iso_year -> Int4,
+        iso_week -> Int4,
+        week_start -> Date,
+        week_end -> Date,
+        status -> Text,
+        total_seconds -> Int8,
+        submit_comment -> Nullable<Text>,
+        review_comment -> Nullable<Text>,
+        submitted_at -> Nullable<Timestamptz>,
+        reviewed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    user_settings (user_id) {
+        user_id -> Uuid,
+        timezone -> Text,
+        week_start_day -> Int2,
+        pomodoro_work_minutes -> Int4,
+        pomodoro_short_break_minutes -> Int4,
+        pomodoro_long_break_minutes -> Int4,
+        pomodoro_cycles_before_long_break -> Int4,
+        working_hours_start -> Time,
+        working_hours_end -> Time,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        max_running_entry_minutes -> Int4,
+        idle_policy -> Text,
     }
 }
 
 diesel::table! {
     users (id) {
-        id -> Int4,
-        username -> Varchar,
-        seed -> Varchar,
-        channel_address -> Varchar,
-        last_message -> Varchar,
-        created_at -> Timestamp,
-        updated_at -> Timestamp,
+        id -> Uuid,
+        display_name -> Nullable<Text>,
+        email -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        last_active_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    workflow_statuses (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        project_id -> Nullable<Uuid>,
+        key -> Text,
+        name -> Text,
+        category -> Text,
+        position -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        wip_limit -> Nullable<Int4>,
+        wip_policy -> Text,
+    }
+}
+
+diesel::table! {
+    workflow_transitions (from_status_id, to_status_id) {
+        from_status_id -> Uuid,
+        to_status_id -> Uuid,
+        user_id -> Uuid,
     }
 }
 
+diesel::joinable!(hourly_rates -> clients (client_id));
+diesel::joinable!(hourly_rates -> projects (project_id));
+diesel::joinable!(hourly_rates -> tasks (task_id));
+diesel::joinable!(invoice_line_items -> invoices (invoice_id));
+diesel::joinable!(invoice_line_items -> projects (project_id));
+diesel::joinable!(invoice_line_items -> tasks (task_id));
+diesel::joinable!(invoices -> clients (client_id));
+diesel::joinable!(pomodoro_interruptions -> pomodoro_sessions (session_id));
+diesel::joinable!(pomodoro_sessions -> tasks (task_id));
+diesel::joinable!(projects -> clients (client_id));
 diesel::joinable!(task_labels -> labels (label_id));
 diesel::joinable!(task_labels -> tasks (task_id));
+diesel::joinable!(task_status_transitions -> tasks (task_id));
 diesel::joinable!(tasks -> projects (project_id));
+diesel::joinable!(timesheet_lines -> projects (project_id));
+diesel::joinable!(timesheet_lines -> timesheets (timesheet_id));
+diesel::joinable!(timesheets -> users (approver_id));
+diesel::joinable!(time_entries -> invoices (invoice_id));
+diesel::joinable!(time_entries -> pomodoro_sessions (pomodoro_session_id));
+diesel::joinable!(time_entries -> projects (project_id));
 diesel::joinable!(time_entries -> tasks (task_id));
+diesel::joinable!(user_settings -> users (user_id));
+diesel::joinable!(workflow_statuses -> projects (project_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_tokens,
+    clients,
+    hourly_rates,
+    invoice_line_items,
+    invoices,
     labels,
+    pomodoro_interruptions,
+    pomodoro_sessions,
     projects,
+    task_dependencies,
     task_labels,
+    task_status_transitions,
     tasks,
     time_entries,
+    timesheet_lines,
+    timesheets,
+    user_settings,
     users,
+    workflow_statuses,
+    workflow_transitions,
 );
@@ -0,0 +1,220 @@
+// OptiTask/backend-api/src/task_graph.rs
+// Graphe des dépendances entre tâches ("A est bloquée par B").
+// Le graphe d'un utilisateur reste acyclique : chaque nouvelle arête est vérifiée par un
+// parcours en profondeur depuis le bloqueur, sur toutes les arêtes existantes (y compris
+// celles des tâches dans la corbeille, qui redeviennent actives à leur restauration).
+
+use crate::error_handler::ServiceError;
+use crate::models::{NewTaskDependency, TaskApiResponse, TaskDependency};
+use crate::schema::task_dependencies;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::sql_query;
+use diesel::sql_types::{Array, Text, Uuid as DieselUuid};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(QueryableByName, Debug)]
+struct DependencyEdge {
+    #[diesel(sql_type = DieselUuid)]
+    task_id: Uuid,
+    #[diesel(sql_type = DieselUuid)]
+    blocked_by_task_id: Uuid,
+    #[diesel(sql_type = Text)]
+    blocker_status_category: String,
+}
+
+// Chemin blocked_by -> ... -> task_id si l'arête task_id -> blocked_by fermerait un cycle.
+// `blockers` : pour chaque tâche, les tâches qui la bloquent.
+fn find_cycle(
+    blockers: &HashMap<Uuid, Vec<Uuid>>,
+    task_id: Uuid,
+    blocked_by_task_id: Uuid,
+) -> Option<Vec<Uuid>> {
+    let mut reached_from: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut visited: HashSet<Uuid> = HashSet::from([blocked_by_task_id]);
+    let mut stack = vec![blocked_by_task_id];
+
+    while let Some(current) = stack.pop() {
+        if current == task_id {
+            let mut path = vec![current];
+            let mut node = current;
+            while let Some(previous) = reached_from.get(&node) {
+                path.push(*previous);
+                node = *previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in blockers.get(&current).into_iter().flatten() {
+            if visited.insert(*next) {
+                reached_from.insert(*next, current);
+                stack.push(*next);
+            }
+        }
+    }
+    None
+}
+
+// Ajoute l'arête après vérification d'acyclicité. Les tâches doivent déjà être
+// vérifiées (propriété, hors corbeille) par l'appelant.
+pub fn add_dependency(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    task_id: Uuid,
+    blocked_by_task_id: Uuid,
+) -> Result<TaskDependency, ServiceError> {
+    if task_id == blocked_by_task_id {
+        return Err(ServiceError::BadRequest(
+            "A task cannot be blocked by itself.".to_string(),
+        ));
+    }
+    // Sérialise les ajouts d'un même utilisateur : deux arêtes concurrentes pourraient
+    // chacune passer la vérification et former un cycle ensemble
+    sql_query("SELECT pg_advisory_xact_lock(hashtext($1::TEXT))")
+        .bind::<DieselUuid, _>(user_uuid)
+        .execute(conn)?;
+
+    let edges: Vec<(Uuid, Uuid)> = task_dependencies::table
+        .filter(task_dependencies::user_id.eq(user_uuid))
+        .select((
+            task_dependencies::task_id,
+            task_dependencies::blocked_by_task_id,
+        ))
+        .load::<(Uuid, Uuid)>(conn)?;
+    let mut blockers: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (blocked, blocker) in edges {
+        blockers.entry(blocked).or_default().push(blocker);
+    }
+    if let Some(cycle) = find_cycle(&blockers, task_id, blocked_by_task_id) {
+        return Err(ServiceError::ConflictWithDetails(
+            format!(
+                "Task {} is already (transitively) blocked by task {}; this dependency would create a cycle.",
+                blocked_by_task_id, task_id
+            ),
+            json!({ "cycle": cycle }),
+        ));
+    }
+
+    diesel::insert_into(task_dependencies::table)
+        .values(&NewTaskDependency {
+            task_id,
+            blocked_by_task_id,
+            user_id: user_uuid,
+        })
+        .get_result::<TaskDependency>(conn)
+        .map_err(|error| match error {
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                ServiceError::Conflict(format!(
+                    "Task {} is already blocked by task {}.",
+                    task_id, blocked_by_task_id
+                ))
+            }
+            _ => ServiceError::from(error),
+        })
+}
+
+// Renseigne blocked_by, blocking et is_blocked (un bloqueur pas encore terminé).
+// Les tâches dans la corbeille sont ignorées des deux côtés.
+pub fn apply_dependencies(
+    conn: &mut PgConnection,
+    responses: &mut [TaskApiResponse],
+) -> Result<(), ServiceError> {
+    if responses.is_empty() {
+        return Ok(());
+    }
+    let task_ids: Vec<Uuid> = responses.iter().map(|response| response.id).collect();
+    let edges = sql_query(
+        "SELECT d.task_id, d.blocked_by_task_id, b.status_category AS blocker_status_category \
+         FROM task_dependencies d \
+         JOIN tasks a ON a.id = d.task_id \
+         JOIN tasks b ON b.id = d.blocked_by_task_id \
+         WHERE (d.task_id = ANY($1) OR d.blocked_by_task_id = ANY($1)) \
+         AND a.deleted_at IS NULL AND b.deleted_at IS NULL \
+         ORDER BY d.created_at ASC",
+    )
+    .bind::<Array<DieselUuid>, _>(&task_ids)
+    .load::<DependencyEdge>(conn)?;
+
+    let positions: HashMap<Uuid, usize> = task_ids
+        .iter()
+        .enumerate()
+        .map(|(position, task_id)| (*task_id, position))
+        .collect();
+    for edge in edges {
+        if let Some(&position) = positions.get(&edge.task_id) {
+            let response = &mut responses[position];
+            response.blocked_by.push(edge.blocked_by_task_id);
+            if edge.blocker_status_category != "done" {
+                response.is_blocked = true;
+            }
+        }
+        if let Some(&position) = positions.get(&edge.blocked_by_task_id) {
+            responses[position].blocking.push(edge.task_id);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Graphe à partir d'arêtes (bloquée, bloqueur)
+    fn graph(edges: &[(Uuid, Uuid)]) -> HashMap<Uuid, Vec<Uuid>> {
+        let mut blockers: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for (blocked, blocker) in edges {
+            blockers.entry(*blocked).or_default().push(*blocker);
+        }
+        blockers
+    }
+
+    fn tasks<const N: usize>() -> [Uuid; N] {
+        std::array::from_fn(|_| Uuid::new_v4())
+    }
+
+    #[test]
+    fn direct_cycle() {
+        let [a, b] = tasks();
+        // b est bloquée par a : bloquer a par b ferme la boucle
+        let blockers = graph(&[(b, a)]);
+        assert_eq!(find_cycle(&blockers, a, b), Some(vec![b, a]));
+    }
+
+    #[test]
+    fn transitive_cycle_returns_the_path() {
+        let [a, b, c, d] = tasks();
+        // d -> c -> b -> a (chaque tâche bloquée par la suivante)
+        let blockers = graph(&[(d, c), (c, b), (b, a)]);
+        assert_eq!(find_cycle(&blockers, a, d), Some(vec![d, c, b, a]));
+        // Le chemin commence au bloqueur et finit à la tâche bloquée
+        assert_eq!(find_cycle(&blockers, b, d), Some(vec![d, c, b]));
+    }
+
+    #[test]
+    fn diamond_is_not_a_cycle() {
+        let [top, left, right, bottom] = tasks();
+        // top bloquée par left et right, toutes deux bloquées par bottom
+        let blockers = graph(&[(top, left), (top, right), (left, bottom), (right, bottom)]);
+        let other = Uuid::new_v4();
+        assert_eq!(find_cycle(&blockers, other, top), None);
+        // Raccourci dans le sens du graphe : top bloquée directement par bottom
+        assert_eq!(find_cycle(&blockers, top, bottom), None);
+        // Sens inverse : bottom bloquée par top fermerait deux boucles
+        let cycle = find_cycle(&blockers, bottom, top).unwrap();
+        assert_eq!(cycle.len(), 3);
+        assert_eq!((cycle[0], cycle[2]), (top, bottom));
+        assert!(cycle[1] == left || cycle[1] == right);
+    }
+
+    #[test]
+    fn unrelated_components_do_not_interfere() {
+        let [a, b, c, d] = tasks();
+        let blockers = graph(&[(b, a), (d, c)]);
+        assert_eq!(find_cycle(&blockers, a, c), None);
+        assert_eq!(find_cycle(&blockers, c, b), None);
+        assert_eq!(find_cycle(&HashMap::new(), a, b), None);
+    }
+}
@@ -0,0 +1,188 @@
+// OptiTask/backend-api/src/task_tree.rs
+// Hiérarchie des tâches (parent_task_id) : validation des déplacements, parcours
+// des sous-tâches et cumuls (avancement, temps suivi).
+// Une sous-tâche est toujours dans le projet de sa tâche parente.
+
+use crate::error_handler::ServiceError;
+use crate::models::{Task, TaskApiResponse};
+use crate::ownership::ensure_task_owned;
+use crate::schema::tasks;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{Array, BigInt, Bool, Integer, Uuid as DieselUuid};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Nombre de niveaux maximal, tâche racine comprise
+pub const MAX_TASK_DEPTH: i32 = 5;
+
+#[derive(QueryableByName, Debug)]
+struct TaskRollup {
+    #[diesel(sql_type = DieselUuid)]
+    task_id: Uuid,
+    #[diesel(sql_type = BigInt)]
+    subtasks_done: i64,
+    #[diesel(sql_type = BigInt)]
+    subtasks_total: i64,
+    #[diesel(sql_type = BigInt)]
+    tracked_seconds: i64,
+    #[diesel(sql_type = BigInt)]
+    total_tracked_seconds: i64,
+}
+
+#[derive(QueryableByName, Debug)]
+struct TreeLevel {
+    #[diesel(sql_type = Integer)]
+    levels: i32,
+}
+
+#[derive(QueryableByName, Debug)]
+struct TreeNode {
+    #[diesel(sql_type = DieselUuid)]
+    id: Uuid,
+}
+
+// Niveau de la tâche dans son arbre (1 : tâche racine)
+fn depth_of(conn: &mut PgConnection, task_id: Uuid) -> Result<i32, ServiceError> {
+    sql_query(
+        "WITH RECURSIVE ancestors AS ( \
+            SELECT id, parent_task_id FROM tasks WHERE id = $1 \
+            UNION \
+            SELECT t.id, t.parent_task_id FROM tasks t \
+            JOIN ancestors a ON t.id = a.parent_task_id \
+         ) \
+         SELECT COUNT(*)::INTEGER AS levels FROM ancestors",
+    )
+    .bind::<DieselUuid, _>(task_id)
+    .get_result::<TreeLevel>(conn)
+    .map(|level| level.levels)
+    .map_err(ServiceError::from)
+}
+
+// Nombre de niveaux du sous-arbre de la tâche (1 : pas de sous-tâche)
+fn height_of(conn: &mut PgConnection, task_id: Uuid) -> Result<i32, ServiceError> {
+    sql_query(
+        "WITH RECURSIVE subtree AS ( \
+            SELECT id, 1 AS level FROM tasks WHERE id = $1 \
+            UNION ALL \
+            SELECT t.id, s.level + 1 FROM tasks t \
+            JOIN subtree s ON t.parent_task_id = s.id \
+            WHERE t.deleted_at IS NULL AND s.level <= $2 \
+         ) \
+         SELECT MAX(level)::INTEGER AS levels FROM subtree",
+    )
+    .bind::<DieselUuid, _>(task_id)
+    .bind::<Integer, _>(MAX_TASK_DEPTH)
+    .get_result::<TreeLevel>(conn)
+    .map(|level| level.levels)
+    .map_err(ServiceError::from)
+}
+
+// Sous-tâches de tous niveaux (hors tâche elle-même). `include_trashed` : parcourt
+// aussi les sous-tâches dans la corbeille (restauration).
+pub fn descendant_ids(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    include_trashed: bool,
+) -> Result<Vec<Uuid>, ServiceError> {
+    let nodes = sql_query(
+        "WITH RECURSIVE subtree AS ( \
+            SELECT id FROM tasks WHERE parent_task_id = $1 AND ($2 OR deleted_at IS NULL) \
+            UNION \
+            SELECT t.id FROM tasks t \
+            JOIN subtree s ON t.parent_task_id = s.id \
+            WHERE $2 OR t.deleted_at IS NULL \
+         ) \
+         SELECT id FROM subtree",
+    )
+    .bind::<DieselUuid, _>(task_id)
+    .bind::<Bool, _>(include_trashed)
+    .load::<TreeNode>(conn)?;
+    Ok(nodes.into_iter().map(|node| node.id).collect())
+}
+
+// Vérifie que `parent_id` peut recevoir `task_id` (None : tâche en création) :
+// parent possédé et hors corbeille, pas de cycle, profondeur maximale respectée.
+// Retourne la tâche parente.
+pub fn ensure_valid_parent(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    task_id: Option<Uuid>,
+    parent_id: Uuid,
+) -> Result<Task, ServiceError> {
+    ensure_task_owned(conn, user_uuid, parent_id)?;
+    if let Some(moved_task_id) = task_id {
+        if moved_task_id == parent_id
+            || descendant_ids(conn, moved_task_id, false)?.contains(&parent_id)
+        {
+            return Err(ServiceError::BadRequest(format!(
+                "Task {} cannot be moved under itself or one of its subtasks.",
+                moved_task_id
+            )));
+        }
+    }
+    let moved_height = match task_id {
+        Some(moved_task_id) => height_of(conn, moved_task_id)?,
+        None => 1,
+    };
+    if depth_of(conn, parent_id)? + moved_height > MAX_TASK_DEPTH {
+        return Err(ServiceError::BadRequest(format!(
+            "Subtasks cannot be nested more than {} levels deep.",
+            MAX_TASK_DEPTH
+        )));
+    }
+    tasks::table
+        .filter(tasks::id.eq(parent_id))
+        .select(Task::as_select())
+        .first::<Task>(conn)
+        .map_err(ServiceError::from)
+}
+
+// Renseigne l'avancement (sous-tâches terminées / total) et le temps suivi cumulé
+pub fn apply_rollups(
+    conn: &mut PgConnection,
+    responses: &mut [TaskApiResponse],
+) -> Result<(), ServiceError> {
+    if responses.is_empty() {
+        return Ok(());
+    }
+    let task_ids: Vec<Uuid> = responses.iter().map(|response| response.id).collect();
+    let rollups: HashMap<Uuid, TaskRollup> = sql_query(
+        "WITH RECURSIVE tree AS ( \
+            SELECT id AS root_id, id AS task_id, 0 AS level FROM tasks WHERE id = ANY($1) \
+            UNION ALL \
+            SELECT tree.root_id, t.id, tree.level + 1 FROM tasks t \
+            JOIN tree ON t.parent_task_id = tree.task_id \
+            WHERE t.deleted_at IS NULL AND tree.level < $2 \
+         ) \
+         SELECT tree.root_id AS task_id, \
+            COUNT(*) FILTER (WHERE tree.level > 0 AND t.status_category = 'done')::BIGINT AS subtasks_done, \
+            COUNT(*) FILTER (WHERE tree.level > 0)::BIGINT AS subtasks_total, \
+            COALESCE(SUM(tracked.seconds) FILTER (WHERE tree.level = 0), 0)::BIGINT AS tracked_seconds, \
+            COALESCE(SUM(tracked.seconds), 0)::BIGINT AS total_tracked_seconds \
+         FROM tree \
+         JOIN tasks t ON t.id = tree.task_id \
+         LEFT JOIN LATERAL ( \
+            SELECT COALESCE(SUM(te.duration_seconds), 0)::BIGINT AS seconds \
+            FROM time_entries te WHERE te.task_id = tree.task_id \
+         ) tracked ON TRUE \
+         GROUP BY tree.root_id",
+    )
+    .bind::<Array<DieselUuid>, _>(&task_ids)
+    .bind::<Integer, _>(MAX_TASK_DEPTH)
+    .load::<TaskRollup>(conn)?
+    .into_iter()
+    .map(|rollup| (rollup.task_id, rollup))
+    .collect();
+
+    for response in responses.iter_mut() {
+        if let Some(rollup) = rollups.get(&response.id) {
+            response.subtasks_done = rollup.subtasks_done;
+            response.subtasks_total = rollup.subtasks_total;
+            response.tracked_seconds = rollup.tracked_seconds;
+            response.total_tracked_seconds = rollup.total_tracked_seconds;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+// OptiTask/backend-api/src/test_support.rs
+// Outils des tests qui ont besoin de Postgres. La base est celle de TEST_DATABASE_URL,
+// migrations appliquées. Ces tests sont marqués `#[ignore]` et se lancent avec
+// `cargo test -- --include-ignored` ; ils échouent si la variable manque.
+// Tout s'exécute dans une transaction de test jamais validée : la base reste intacte.
+
+use crate::auth_utils::AuthConfig;
+use crate::db::DbPool;
+use crate::schema::{labels, projects, tasks, time_entries, workflow_statuses};
+use chrono::{Duration, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::r2d2::{self, ConnectionManager, CustomizeConnection};
+use std::env;
+use uuid::Uuid;
+
+pub fn test_database_url() -> String {
+    dotenvy::dotenv().ok();
+    env::var("TEST_DATABASE_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .expect("TEST_DATABASE_URL must point at a migrated database to run database tests")
+}
+
+// Connexion dans une transaction de test. Les données insérées ici le sont avec le
+// rôle de connexion du backend, comme le feraient les jobs, donc hors RLS.
+pub fn test_connection() -> PgConnection {
+    let mut conn = PgConnection::establish(&test_database_url())
+        .expect("Failed to connect to TEST_DATABASE_URL");
+    conn.begin_test_transaction()
+        .expect("Failed to begin test transaction");
+    conn
+}
+
+#[derive(Debug)]
+struct TestTransaction;
+
+impl CustomizeConnection<PgConnection, r2d2::Error> for TestTransaction {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), r2d2::Error> {
+        conn.begin_test_transaction()
+            .map_err(r2d2::Error::QueryError)
+    }
+}
+
+// Pool d'une seule connexion, dans une transaction de test, pour appeler les handlers.
+// Les données à voir depuis les requêtes HTTP s'insèrent avant le premier appel :
+// les handlers laissent `SET LOCAL ROLE authenticated` actif dans la transaction de test.
+pub fn test_pool() -> DbPool {
+    let manager = ConnectionManager::<PgConnection>::new(test_database_url());
+    r2d2::Pool::builder()
+        .max_size(1)
+        .connection_customizer(Box::new(TestTransaction))
+        .build(manager)
+        .expect("Failed to create test database pool")
+}
+
+// Authentification des tests HTTP : header X-User-Id
+pub fn test_auth_config() -> AuthConfig {
+    AuthConfig::dev_mode_only()
+}
+
+pub fn seed_project(conn: &mut PgConnection, owner: Uuid) -> Uuid {
+    diesel::insert_into(projects::table)
+        .values((
+            projects::user_id.eq(owner),
+            projects::name.eq("Seeded project"),
+        ))
+        .returning(projects::id)
+        .get_result(conn)
+        .expect("Failed to seed project")
+}
+
+pub fn seed_task(conn: &mut PgConnection, owner: Uuid, project: Option<Uuid>) -> Uuid {
+    diesel::insert_into(tasks::table)
+        .values((
+            tasks::user_id.eq(owner),
+            tasks::project_id.eq(project),
+            tasks::title.eq("Seeded task"),
+        ))
+        .returning(tasks::id)
+        .get_result(conn)
+        .expect("Failed to seed task")
+}
+
+pub fn seed_label(conn: &mut PgConnection, owner: Uuid) -> Uuid {
+    diesel::insert_into(labels::table)
+        .values((labels::user_id.eq(owner), labels::name.eq("Seeded label")))
+        .returning(labels::id)
+        .get_result(conn)
+        .expect("Failed to seed label")
+}
+
+// Entrée terminée d'une heure, il y a deux jours
+pub fn seed_time_entry(conn: &mut PgConnection, owner: Uuid, task: Uuid) -> Uuid {
+    let start = Utc::now() - Duration::days(2);
+    diesel::insert_into(time_entries::table)
+        .values((
+            time_entries::user_id.eq(owner),
+            time_entries::task_id.eq(Some(task)),
+            time_entries::start_time.eq(start),
+            time_entries::end_time.eq(Some(start + Duration::hours(1))),
+            time_entries::duration_seconds.eq(Some(3600)),
+        ))
+        .returning(time_entries::id)
+        .get_result(conn)
+        .expect("Failed to seed time entry")
+}
+
+// Workflow du projet : 'todo', 'doing' (limite 1, politique `doing_policy`), 'done'
+pub fn seed_board(conn: &mut PgConnection, owner: Uuid, doing_policy: &str) -> Uuid {
+    let project = seed_project(conn, owner);
+    let statuses = [
+        ("todo", "todo", None),
+        ("doing", "in_progress", Some(1)),
+        ("done", "done", None),
+    ];
+    for (position, (key, category, wip_limit)) in statuses.into_iter().enumerate() {
+        diesel::insert_into(workflow_statuses::table)
+            .values((
+                workflow_statuses::user_id.eq(owner),
+                workflow_statuses::project_id.eq(Some(project)),
+                workflow_statuses::key.eq(key),
+                workflow_statuses::name.eq(key),
+                workflow_statuses::category.eq(category),
+                workflow_statuses::position.eq(position as i32),
+                workflow_statuses::wip_limit.eq(wip_limit),
+                workflow_statuses::wip_policy.eq(doing_policy),
+            ))
+            .execute(conn)
+            .expect("Failed to seed workflow status");
+    }
+    project
+}
+
+// Tâche du projet, au statut (et à la catégorie) donné
+pub fn seed_task_in(
+    conn: &mut PgConnection,
+    owner: Uuid,
+    project: Uuid,
+    task_status: &str,
+    category: &str,
+) -> Uuid {
+    let task = seed_task(conn, owner, Some(project));
+    diesel::update(tasks::table.find(task))
+        .set((
+            tasks::status.eq(task_status),
+            tasks::status_category.eq(category),
+        ))
+        .execute(conn)
+        .expect("Failed to set task status");
+    task
+}
@@ -0,0 +1,318 @@
+// OptiTask/backend-api/src/timesheets.rs
+// Semaines ISO des feuilles de temps et verrouillage des semaines approuvées.
+// Une entrée appartient à la semaine ISO du jour local (fuseau des réglages) de son début,
+// comme pour les analytics et la facturation.
+
+use crate::error_handler::ServiceError;
+use crate::handlers::analytics_handlers::{local_midnight_to_utc, settings_timezone};
+use crate::handlers::user_handlers::load_user_settings;
+use crate::models::TimesheetDayTotal;
+use crate::schema::{projects, timesheets};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{Text, Timestamptz, Uuid as DieselUuid};
+use uuid::Uuid;
+
+// Semaine ISO contenant `date`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsoWeek {
+    pub year: i32,
+    pub week: i32,
+    pub start: NaiveDate, // Lundi
+    pub end: NaiveDate,   // Dimanche
+}
+
+impl IsoWeek {
+    pub fn containing(date: NaiveDate) -> Self {
+        let iso = date.iso_week();
+        let start = NaiveDate::from_isoywd_opt(iso.year(), iso.week(), Weekday::Mon).unwrap();
+        IsoWeek {
+            year: iso.year(),
+            week: iso.week() as i32,
+            start,
+            end: start + Duration::days(6),
+        }
+    }
+}
+
+// Temps par projet et par jour local de la semaine, entrées en cours exclues
+pub fn load_week_totals(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    week: IsoWeek,
+    tz: Tz,
+) -> Result<Vec<TimesheetDayTotal>, ServiceError> {
+    sql_query(
+        "SELECT p.id AS project_id, COALESCE(p.name, 'No project') AS project_name, \
+            (te.start_time AT TIME ZONE $4)::DATE AS work_date, \
+            SUM(te.duration_seconds)::BIGINT AS seconds \
+         FROM time_entries te \
+         LEFT JOIN tasks t ON t.id = te.task_id \
+         LEFT JOIN projects p ON p.id = COALESCE(te.project_id, t.project_id) \
+         WHERE te.user_id = $1 AND te.end_time IS NOT NULL \
+         AND te.start_time >= $2 AND te.start_time < $3 \
+         GROUP BY p.id, p.name, work_date \
+         ORDER BY project_name ASC, work_date ASC",
+    )
+    .bind::<DieselUuid, _>(user_uuid)
+    .bind::<Timestamptz, _>(local_midnight_to_utc(week.start, tz))
+    .bind::<Timestamptz, _>(local_midnight_to_utc(week.end + Duration::days(1), tz))
+    .bind::<Text, _>(tz.name())
+    .load::<TimesheetDayTotal>(conn)
+    .map_err(ServiceError::from)
+}
+
+// Refuse la modification d'une entrée qui commence (ou commencerait) dans une semaine
+// approuvée de l'utilisateur. À appeler dans la transaction du handler, avant l'écriture.
+pub fn ensure_week_not_approved(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    instants: &[DateTime<Utc>],
+) -> Result<(), ServiceError> {
+    let settings = load_user_settings(conn, user_uuid)?;
+    let tz = settings_timezone(&settings)?;
+    for instant in instants {
+        let week = IsoWeek::containing(instant.with_timezone(&tz).date_naive());
+        let approved = timesheets::table
+            .filter(timesheets::user_id.eq(user_uuid))
+            .filter(timesheets::iso_year.eq(week.year))
+            .filter(timesheets::iso_week.eq(week.week))
+            .filter(timesheets::status.eq("approved"))
+            .select(timesheets::id)
+            .first::<Uuid>(conn)
+            .optional()?;
+        if let Some(timesheet_id) = approved {
+            return Err(ServiceError::Conflict(format!(
+                "Week {}-W{:02} is covered by approved timesheet {}; reopen it before changing its time entries.",
+                week.year, week.week, timesheet_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Approbateur de la semaine : le responsable désigné sur les projets où du temps a été
+// saisi. Jamais l'utilisateur lui-même ; `requested` ne sert qu'aux semaines vides, sinon il
+// doit correspondre au responsable des projets.
+pub fn resolve_week_approver(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    lines: &[TimesheetDayTotal],
+    requested: Option<Uuid>,
+) -> Result<Uuid, ServiceError> {
+    if lines.iter().any(|line| line.project_id.is_none()) {
+        return Err(ServiceError::BadRequest(
+            "Assign every time entry of the week to a project before submitting it.".to_string(),
+        ));
+    }
+    let mut project_ids: Vec<Uuid> = lines.iter().filter_map(|line| line.project_id).collect();
+    project_ids.sort();
+    project_ids.dedup();
+
+    let week_projects = projects::table
+        .filter(projects::id.eq_any(&project_ids))
+        .select((projects::name, projects::approver_id))
+        .load::<(String, Option<Uuid>)>(conn)?;
+    let without_approver: Vec<&str> = week_projects
+        .iter()
+        .filter(|(_, approver)| approver.is_none())
+        .map(|(name, _)| name.as_str())
+        .collect();
+    if !without_approver.is_empty() {
+        return Err(ServiceError::BadRequest(format!(
+            "Set an approver on project(s) {} before submitting this week.",
+            without_approver.join(", ")
+        )));
+    }
+    let mut approvers: Vec<Uuid> = week_projects
+        .iter()
+        .filter_map(|(_, approver)| *approver)
+        .collect();
+    approvers.sort();
+    approvers.dedup();
+
+    let approver =
+        match (approvers.as_slice(), requested) {
+            ([], Some(requested)) => requested,
+            ([], None) => {
+                return Err(ServiceError::BadRequest(
+                    "approver_id is required to submit a week without time entries.".to_string(),
+                ))
+            }
+            ([approver], None) => *approver,
+            ([approver], Some(requested)) if requested == *approver => *approver,
+            ([approver], Some(requested)) => {
+                return Err(ServiceError::BadRequest(format!(
+                    "Approver {} is not the approver of this week's projects ({}).",
+                    requested, approver
+                )))
+            }
+            _ => return Err(ServiceError::BadRequest(
+                "This week's projects have different approvers; a timesheet has a single approver."
+                    .to_string(),
+            )),
+        };
+    if approver == user_uuid {
+        return Err(ServiceError::BadRequest(
+            "You cannot approve your own timesheet.".to_string(),
+        ));
+    }
+    Ok(approver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::time_entries;
+    use crate::test_support::{seed_project, seed_task, seed_time_entry, test_connection};
+    use chrono_tz::UTC;
+
+    fn week_lines(conn: &mut PgConnection, owner: Uuid) -> Vec<TimesheetDayTotal> {
+        let week = IsoWeek::containing((Utc::now() - Duration::days(2)).date_naive());
+        load_week_totals(conn, owner, week, UTC).expect("Failed to load week totals")
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn approver_comes_from_the_week_projects() {
+        let mut conn = test_connection();
+        let (owner, manager, someone_else) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        for user in [owner, manager, someone_else] {
+            load_user_settings(&mut conn, user).expect("Failed to create user");
+        }
+        let project = seed_project(&mut conn, owner);
+        let task = seed_task(&mut conn, owner, Some(project));
+        seed_time_entry(&mut conn, owner, task);
+        let lines = week_lines(&mut conn, owner);
+
+        // Sans approbateur sur le projet, l'utilisateur ne peut pas en choisir un, ni lui-même
+        assert!(resolve_week_approver(&mut conn, owner, &lines, Some(owner)).is_err());
+        assert!(resolve_week_approver(&mut conn, owner, &lines, Some(manager)).is_err());
+
+        diesel::update(projects::table.filter(projects::id.eq(project)))
+            .set(projects::approver_id.eq(Some(manager)))
+            .execute(&mut conn)
+            .expect("Failed to set project approver");
+        assert_eq!(
+            resolve_week_approver(&mut conn, owner, &lines, None).unwrap(),
+            manager
+        );
+        assert!(resolve_week_approver(&mut conn, owner, &lines, Some(owner)).is_err());
+        assert!(resolve_week_approver(&mut conn, owner, &lines, Some(someone_else)).is_err());
+    }
+
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn database_refuses_self_approval() {
+        let mut conn = test_connection();
+        let owner = Uuid::new_v4();
+        load_user_settings(&mut conn, owner).expect("Failed to create user");
+        let project = seed_project(&mut conn, owner);
+
+        let self_project_approver = conn.transaction(|conn| {
+            diesel::update(projects::table.filter(projects::id.eq(project)))
+                .set(projects::approver_id.eq(Some(owner)))
+                .execute(conn)
+        });
+        assert!(self_project_approver.is_err());
+
+        let week = IsoWeek::containing(NaiveDate::from_ymd_opt(2026, 10, 19).unwrap());
+        let self_timesheet = conn.transaction(|conn| {
+            diesel::insert_into(timesheets::table)
+                .values((
+                    timesheets::user_id.eq(owner),
+                    timesheets::approver_id.eq(owner),
+                    timesheets::iso_year.eq(week.year),
+                    timesheets::iso_week.eq(week.week),
+                    timesheets::week_start.eq(week.start),
+                    timesheets::week_end.eq(week.end),
+                    timesheets::status.eq("submitted"),
+                ))
+                .execute(conn)
+        });
+        assert!(self_timesheet.is_err());
+    }
+
+    // Les écritures qui ne passent pas par ensure_week_not_approved sont refusées par le trigger
+    #[test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    fn database_locks_time_entries_of_approved_weeks() {
+        let mut conn = test_connection();
+        let (owner, manager) = (Uuid::new_v4(), Uuid::new_v4());
+        for user in [owner, manager] {
+            load_user_settings(&mut conn, user).expect("Failed to create user");
+        }
+        let task = seed_task(&mut conn, owner, None);
+        let monday: DateTime<Utc> = "2026-10-19T09:00:00Z".parse().unwrap();
+        let existing = diesel::insert_into(time_entries::table)
+            .values((
+                time_entries::user_id.eq(owner),
+                time_entries::task_id.eq(Some(task)),
+                time_entries::start_time.eq(monday),
+                time_entries::end_time.eq(Some(monday + Duration::hours(1))),
+                time_entries::duration_seconds.eq(Some(3600)),
+            ))
+            .returning(time_entries::id)
+            .get_result::<Uuid>(&mut conn)
+            .expect("Failed to seed time entry");
+        let week = IsoWeek::containing(monday.date_naive());
+        diesel::insert_into(timesheets::table)
+            .values((
+                timesheets::user_id.eq(owner),
+                timesheets::approver_id.eq(manager),
+                timesheets::iso_year.eq(week.year),
+                timesheets::iso_week.eq(week.week),
+                timesheets::week_start.eq(week.start),
+                timesheets::week_end.eq(week.end),
+                timesheets::status.eq("approved"),
+            ))
+            .execute(&mut conn)
+            .expect("Failed to approve timesheet");
+
+        let is_locked = |result: QueryResult<usize>| {
+            matches!(
+                result.map_err(ServiceError::from),
+                Err(ServiceError::Conflict(_))
+            )
+        };
+        let inserted = conn.transaction(|conn| {
+            diesel::insert_into(time_entries::table)
+                .values((
+                    time_entries::user_id.eq(owner),
+                    time_entries::task_id.eq(Some(task)),
+                    time_entries::start_time.eq(monday + Duration::days(2)),
+                ))
+                .execute(conn)
+        });
+        assert!(is_locked(inserted));
+        let updated = conn.transaction(|conn| {
+            diesel::update(time_entries::table.find(existing))
+                .set(time_entries::duration_seconds.eq(Some(60)))
+                .execute(conn)
+        });
+        assert!(is_locked(updated));
+        // Déplacer l'entrée hors de la semaine approuvée est aussi une modification de la semaine
+        let moved_out = conn.transaction(|conn| {
+            diesel::update(time_entries::table.find(existing))
+                .set(time_entries::start_time.eq(monday - Duration::days(7)))
+                .execute(conn)
+        });
+        assert!(is_locked(moved_out));
+        let deleted = conn
+            .transaction(|conn| diesel::delete(time_entries::table.find(existing)).execute(conn));
+        assert!(is_locked(deleted));
+
+        // La semaine précédente reste libre
+        let previous_week = diesel::insert_into(time_entries::table)
+            .values((
+                time_entries::user_id.eq(owner),
+                time_entries::task_id.eq(Some(task)),
+                time_entries::start_time.eq(monday - Duration::days(7)),
+            ))
+            .execute(&mut conn);
+        assert!(previous_week.is_ok());
+    }
+}
@@ -0,0 +1,621 @@
+// OptiTask/backend-api/src/workflows.rs
+// Workflows de statuts des tâches : workflow effectif d'un projet, validation des statuts
+// et des transitions, remplacement d'un workflow avec reprise des tâches concernées.
+// Résolution : workflow du projet, sinon workflow par défaut de l'utilisateur, sinon
+// workflow intégré (todo / inprogress / done). Un workflow sans transition permet tous
+// les passages.
+
+use crate::error_handler::ServiceError;
+use crate::models::{
+    NewWorkflowStatus, NewWorkflowTransition, UpdateWorkflowPayload, Workflow, WorkflowStatus,
+    WorkflowStatusView, WorkflowTransitionView,
+};
+use crate::schema::{tasks, workflow_statuses, workflow_transitions};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::Uuid as DieselUuid;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const STATUS_CATEGORIES: [&str; 3] = ["todo", "in_progress", "done"];
+const MAX_STATUS_KEY_LENGTH: usize = 40;
+
+// Workflow utilisé tant que ni le projet ni l'utilisateur n'en définissent
+const BUILTIN_STATUSES: [(&str, &str, &str); 3] = [
+    ("todo", "To do", "todo"),
+    ("inprogress", "In progress", "in_progress"),
+    ("done", "Done", "done"),
+];
+
+fn builtin_workflow() -> Workflow {
+    Workflow {
+        source: "default".to_string(),
+        project_id: None,
+        statuses: BUILTIN_STATUSES
+            .iter()
+            .enumerate()
+            .map(|(position, (key, name, category))| WorkflowStatusView {
+                key: key.to_string(),
+                name: name.to_string(),
+                category: category.to_string(),
+                position: position as i32,
+                wip_limit: None,
+                wip_policy: "warn".to_string(),
+            })
+            .collect(),
+        transitions: Vec::new(),
+    }
+}
+
+// Statut saisi par un client : casse et espaces autour ignorés
+pub fn normalize_status_key(raw: &str) -> String {
+    raw.trim().to_lowercase()
+}
+
+fn normalize_category(raw: &str) -> Result<String, ServiceError> {
+    let category = raw.trim().to_lowercase().replace('-', "_");
+    if STATUS_CATEGORIES.contains(&category.as_str()) {
+        Ok(category)
+    } else {
+        Err(ServiceError::BadRequest(format!(
+            "Invalid status category '{}'. Expected one of: {}",
+            raw,
+            STATUS_CATEGORIES.join(", ")
+        )))
+    }
+}
+
+// Statuts définis exactement pour ce périmètre (projet, ou défaut de l'utilisateur)
+fn statuses_in_scope(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    project_id: Option<Uuid>,
+) -> Result<Vec<WorkflowStatus>, ServiceError> {
+    let mut query = workflow_statuses::table
+        .filter(workflow_statuses::user_id.eq(user_uuid))
+        .order(workflow_statuses::position.asc())
+        .then_order_by(workflow_statuses::created_at.asc())
+        .select(WorkflowStatus::as_select())
+        .into_boxed();
+    query = match project_id {
+        Some(p_id) => query.filter(workflow_statuses::project_id.eq(p_id)),
+        None => query.filter(workflow_statuses::project_id.is_null()),
+    };
+    query
+        .load::<WorkflowStatus>(conn)
+        .map_err(ServiceError::from)
+}
+
+fn to_workflow(
+    conn: &mut PgConnection,
+    source: &str,
+    project_id: Option<Uuid>,
+    rows: Vec<WorkflowStatus>,
+) -> Result<Workflow, ServiceError> {
+    let keys_by_id: HashMap<Uuid, String> = rows
+        .iter()
+        .map(|status| (status.id, status.key.clone()))
+        .collect();
+    let status_ids: Vec<Uuid> = rows.iter().map(|status| status.id).collect();
+    let transition_ids: Vec<(Uuid, Uuid)> = workflow_transitions::table
+        .filter(workflow_transitions::from_status_id.eq_any(&status_ids))
+        .select((
+            workflow_transitions::from_status_id,
+            workflow_transitions::to_status_id,
+        ))
+        .load::<(Uuid, Uuid)>(conn)?;
+
+    let mut transitions: Vec<WorkflowTransitionView> = transition_ids
+        .into_iter()
+        .filter_map(|(from_id, to_id)| {
+            Some(WorkflowTransitionView {
+                from: keys_by_id.get(&from_id)?.clone(),
+                to: keys_by_id.get(&to_id)?.clone(),
+            })
+        })
+        .collect();
+    transitions.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+
+    Ok(Workflow {
+        source: source.to_string(),
+        project_id,
+        statuses: rows
+            .into_iter()
+            .map(|status| WorkflowStatusView {
+                key: status.key,
+                name: status.name,
+                category: status.category,
+                position: status.position,
+                wip_limit: status.wip_limit,
+                wip_policy: status.wip_policy,
+            })
+            .collect(),
+        transitions,
+    })
+}
+
+// Workflow effectif des tâches du projet (None : tâches sans projet)
+pub fn load_workflow(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    project_id: Option<Uuid>,
+) -> Result<Workflow, ServiceError> {
+    if let Some(p_id) = project_id {
+        let project_statuses = statuses_in_scope(conn, user_uuid, Some(p_id))?;
+        if !project_statuses.is_empty() {
+            return to_workflow(conn, "project", Some(p_id), project_statuses);
+        }
+    }
+    let default_statuses = statuses_in_scope(conn, user_uuid, None)?;
+    if !default_statuses.is_empty() {
+        return to_workflow(conn, "user", None, default_statuses);
+    }
+    Ok(builtin_workflow())
+}
+
+impl Workflow {
+    pub fn status(&self, key: &str) -> Option<&WorkflowStatusView> {
+        self.statuses.iter().find(|status| status.key == key)
+    }
+
+    fn keys(&self) -> String {
+        self.statuses
+            .iter()
+            .map(|status| status.key.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Statut d'une nouvelle tâche : premier statut 'todo', sinon premier statut
+    pub fn initial_status(&self) -> Result<&WorkflowStatusView, ServiceError> {
+        self.fallback_status("todo")
+    }
+
+    // Premier statut de la catégorie, sinon premier statut du workflow
+    pub fn fallback_status(&self, category: &str) -> Result<&WorkflowStatusView, ServiceError> {
+        self.statuses
+            .iter()
+            .find(|status| status.category == category)
+            .or_else(|| self.statuses.first())
+            .ok_or_else(|| ServiceError::InternalServerError("Workflow has no status".to_string()))
+    }
+
+    // Colonne du tableau d'une tâche : son statut, ou le premier statut de sa catégorie
+    // quand son statut n'est pas dans le workflow
+    pub fn column_for(
+        &self,
+        task_status: &str,
+        task_category: &str,
+    ) -> Result<&WorkflowStatusView, ServiceError> {
+        match self.status(task_status) {
+            Some(column) => Ok(column),
+            None => self.fallback_status(task_category),
+        }
+    }
+
+    pub fn resolve_status(&self, raw: &str) -> Result<&WorkflowStatusView, ServiceError> {
+        let key = normalize_status_key(raw);
+        self.status(&key).ok_or_else(|| {
+            ServiceError::BadRequest(format!(
+                "Unknown status '{}'. Expected one of: {}",
+                raw,
+                self.keys()
+            ))
+        })
+    }
+
+    // Un statut hors du workflow (tâche venant d'un autre projet) peut aller partout
+    pub fn ensure_transition(&self, from: &str, to: &str) -> Result<(), ServiceError> {
+        if from == to || self.transitions.is_empty() || self.status(from).is_none() {
+            return Ok(());
+        }
+        if self
+            .transitions
+            .iter()
+            .any(|transition| transition.from == from && transition.to == to)
+        {
+            return Ok(());
+        }
+        let allowed: Vec<&str> = self
+            .transitions
+            .iter()
+            .filter(|transition| transition.from == from)
+            .map(|transition| transition.to.as_str())
+            .collect();
+        Err(ServiceError::BadRequest(format!(
+            "The workflow does not allow moving a task from '{}' to '{}'. Allowed: {}",
+            from,
+            to,
+            if allowed.is_empty() {
+                "none".to_string()
+            } else {
+                allowed.join(", ")
+            }
+        )))
+    }
+}
+
+// Tâches hors corbeille que le tableau du projet affiche dans la colonne `column_key`
+// (statut hors workflow compris), dans l'ordre du tableau, avec leur `order`
+pub fn column_tasks(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    workflow: &Workflow,
+    column_key: &str,
+) -> Result<Vec<(Uuid, Option<i32>)>, ServiceError> {
+    let project_tasks = tasks::table
+        .filter(tasks::project_id.eq(project_id))
+        .filter(tasks::deleted_at.is_null())
+        .order(tasks::task_order.asc().nulls_last())
+        .then_order_by(tasks::created_at.asc())
+        .select((
+            tasks::id,
+            tasks::task_order,
+            tasks::status,
+            tasks::status_category,
+        ))
+        .load::<(Uuid, Option<i32>, String, String)>(conn)?;
+    let mut column = Vec::new();
+    for (task_id, task_order, task_status, task_category) in project_tasks {
+        if workflow.column_for(&task_status, &task_category)?.key == column_key {
+            column.push((task_id, task_order));
+        }
+    }
+    Ok(column)
+}
+
+// Limite WIP de la colonne `target` du tableau du projet, pour la tâche `entering_task_id`
+// qui y entre : Some(avertissement) si la colonne est pleine en politique 'warn', 409 en
+// 'reject'. Verrouille le tableau du projet jusqu'à la fin de la transaction.
+pub fn check_wip_limit(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    workflow: &Workflow,
+    target: &WorkflowStatusView,
+    entering_task_id: Uuid,
+) -> Result<Option<String>, ServiceError> {
+    let Some(wip_limit) = target.wip_limit else {
+        return Ok(None);
+    };
+    sql_query("SELECT pg_advisory_xact_lock(hashtext($1::TEXT))")
+        .bind::<DieselUuid, _>(project_id)
+        .execute(conn)?;
+    let task_count = column_tasks(conn, project_id, workflow, &target.key)?
+        .iter()
+        .filter(|(task_id, _)| *task_id != entering_task_id)
+        .count() as i64;
+    if task_count < wip_limit as i64 {
+        return Ok(None);
+    }
+    let message = format!(
+        "Column '{}' is at its WIP limit ({} tasks for a limit of {}).",
+        target.name, task_count, wip_limit
+    );
+    if target.wip_policy == "reject" {
+        return Err(ServiceError::ConflictWithDetails(
+            message,
+            json!({
+                "status": target.key,
+                "wip_limit": wip_limit,
+                "task_count": task_count,
+            }),
+        ));
+    }
+    Ok(Some(message))
+}
+
+// Donne aux tâches dont le statut n'existe pas dans `workflow` le premier statut de même
+// catégorie (tâches changées de projet)
+pub fn fit_tasks_to_workflow(
+    conn: &mut PgConnection,
+    workflow: &Workflow,
+    task_ids: &[Uuid],
+) -> Result<(), ServiceError> {
+    let task_statuses = tasks::table
+        .filter(tasks::id.eq_any(task_ids))
+        .select((tasks::id, tasks::status, tasks::status_category))
+        .load::<(Uuid, String, String)>(conn)?;
+    for (task_id, task_status, task_category) in task_statuses {
+        if workflow.status(&task_status).is_some() {
+            continue;
+        }
+        let fallback = workflow.fallback_status(&task_category)?;
+        diesel::update(tasks::table.filter(tasks::id.eq(task_id)))
+            .set((
+                tasks::status.eq(&fallback.key),
+                tasks::status_category.eq(&fallback.category),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+fn validate_statuses(
+    payload: &UpdateWorkflowPayload,
+) -> Result<Vec<WorkflowStatusView>, ServiceError> {
+    let mut seen_keys = HashSet::new();
+    let mut statuses = Vec::with_capacity(payload.statuses.len());
+    for (position, status) in payload.statuses.iter().enumerate() {
+        let key = normalize_status_key(&status.key);
+        if key.is_empty()
+            || key.len() > MAX_STATUS_KEY_LENGTH
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(ServiceError::BadRequest(format!(
+                "Invalid status key '{}': use 1 to {} characters among a-z, 0-9 and _",
+                status.key, MAX_STATUS_KEY_LENGTH
+            )));
+        }
+        if !seen_keys.insert(key.clone()) {
+            return Err(ServiceError::BadRequest(format!(
+                "Status '{}' is defined more than once",
+                key
+            )));
+        }
+        let name = status
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&key)
+            .to_string();
+        if status.wip_limit.is_some_and(|wip_limit| wip_limit < 1) {
+            return Err(ServiceError::BadRequest(format!(
+                "wip_limit of status '{}' must be at least 1",
+                key
+            )));
+        }
+        let wip_policy = match status.wip_policy.as_deref().map(str::trim) {
+            None | Some("warn") => "warn",
+            Some("reject") => "reject",
+            Some(other) => {
+                return Err(ServiceError::BadRequest(format!(
+                    "Invalid wip_policy '{}'. Expected 'warn' or 'reject'",
+                    other
+                )))
+            }
+        };
+        statuses.push(WorkflowStatusView {
+            category: normalize_category(&status.category)?,
+            key,
+            name,
+            position: position as i32,
+            wip_limit: status.wip_limit,
+            wip_policy: wip_policy.to_string(),
+        });
+    }
+    if !statuses.is_empty() && !statuses.iter().any(|status| status.category == "done") {
+        return Err(ServiceError::BadRequest(
+            "A workflow needs at least one status in the 'done' category".to_string(),
+        ));
+    }
+    Ok(statuses)
+}
+
+fn validate_transitions(
+    payload: &UpdateWorkflowPayload,
+    statuses: &[WorkflowStatusView],
+) -> Result<Vec<(String, String)>, ServiceError> {
+    let known_keys: HashSet<&str> = statuses.iter().map(|status| status.key.as_str()).collect();
+    let mut transitions = Vec::new();
+    for transition in &payload.transitions {
+        let from = normalize_status_key(&transition.from);
+        let to = normalize_status_key(&transition.to);
+        for key in [&from, &to] {
+            if !known_keys.contains(key.as_str()) {
+                return Err(ServiceError::BadRequest(format!(
+                    "Transition uses status '{}' which is not in the workflow",
+                    key
+                )));
+            }
+        }
+        if from == to {
+            return Err(ServiceError::BadRequest(format!(
+                "Transition from '{}' to itself is implicit",
+                from
+            )));
+        }
+        if !transitions.contains(&(from.clone(), to.clone())) {
+            transitions.push((from, to));
+        }
+    }
+    Ok(transitions)
+}
+
+// Tâches (corbeille comprise) qui suivent le workflow de ce périmètre
+fn tasks_following(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    project_id: Option<Uuid>,
+) -> Result<Vec<(Uuid, String)>, ServiceError> {
+    if let Some(p_id) = project_id {
+        return tasks::table
+            .filter(tasks::user_id.eq(user_uuid))
+            .filter(tasks::project_id.eq(p_id))
+            .select((tasks::id, tasks::status))
+            .load::<(Uuid, String)>(conn)
+            .map_err(ServiceError::from);
+    }
+    let projects_with_workflow: HashSet<Uuid> = workflow_statuses::table
+        .filter(workflow_statuses::user_id.eq(user_uuid))
+        .select(workflow_statuses::project_id)
+        .distinct()
+        .load::<Option<Uuid>>(conn)?
+        .into_iter()
+        .flatten()
+        .collect();
+    let user_tasks = tasks::table
+        .filter(tasks::user_id.eq(user_uuid))
+        .select((tasks::id, tasks::project_id, tasks::status))
+        .load::<(Uuid, Option<Uuid>, String)>(conn)?;
+    Ok(user_tasks
+        .into_iter()
+        .filter(|(_, task_project_id, _)| {
+            !task_project_id.is_some_and(|p_id| projects_with_workflow.contains(&p_id))
+        })
+        .map(|(task_id, _, task_status)| (task_id, task_status))
+        .collect())
+}
+
+// Remplace le workflow du projet (None : workflow par défaut de l'utilisateur) et
+// reprend les tâches concernées : statuts renommés ou supprimés via `status_mapping`,
+// catégories recalculées. 409 avec les statuts orphelins s'il en reste.
+// À appeler dans une transaction (run_as_user).
+pub fn replace_workflow(
+    conn: &mut PgConnection,
+    user_uuid: Uuid,
+    project_id: Option<Uuid>,
+    payload: &UpdateWorkflowPayload,
+) -> Result<Workflow, ServiceError> {
+    let statuses = validate_statuses(payload)?;
+    let transitions = validate_transitions(payload, &statuses)?;
+
+    let mut delete_query = diesel::delete(workflow_statuses::table)
+        .filter(workflow_statuses::user_id.eq(user_uuid))
+        .into_boxed();
+    delete_query = match project_id {
+        Some(p_id) => delete_query.filter(workflow_statuses::project_id.eq(p_id)),
+        None => delete_query.filter(workflow_statuses::project_id.is_null()),
+    };
+    delete_query.execute(conn)?;
+
+    if !statuses.is_empty() {
+        let new_statuses: Vec<NewWorkflowStatus> = statuses
+            .iter()
+            .map(|status| NewWorkflowStatus {
+                user_id: user_uuid,
+                project_id,
+                key: status.key.clone(),
+                name: status.name.clone(),
+                category: status.category.clone(),
+                position: status.position,
+                wip_limit: status.wip_limit,
+                wip_policy: status.wip_policy.clone(),
+            })
+            .collect();
+        let inserted: Vec<WorkflowStatus> = diesel::insert_into(workflow_statuses::table)
+            .values(&new_statuses)
+            .get_results::<WorkflowStatus>(conn)?;
+        let ids_by_key: HashMap<&str, Uuid> = inserted
+            .iter()
+            .map(|status| (status.key.as_str(), status.id))
+            .collect();
+        let new_transitions: Vec<NewWorkflowTransition> = transitions
+            .iter()
+            .map(|(from, to)| NewWorkflowTransition {
+                from_status_id: ids_by_key[from.as_str()],
+                to_status_id: ids_by_key[to.as_str()],
+                user_id: user_uuid,
+            })
+            .collect();
+        if !new_transitions.is_empty() {
+            diesel::insert_into(workflow_transitions::table)
+                .values(&new_transitions)
+                .execute(conn)?;
+        }
+    }
+
+    // Workflow effectif après remplacement (hérité si `statuses` est vide)
+    let workflow = load_workflow(conn, user_uuid, project_id)?;
+
+    let mut task_ids_by_status: HashMap<String, Vec<Uuid>> = HashMap::new();
+    for (task_id, task_status) in tasks_following(conn, user_uuid, project_id)? {
+        task_ids_by_status
+            .entry(task_status)
+            .or_default()
+            .push(task_id);
+    }
+    let mut unmapped_statuses = Vec::new();
+    for (old_status, task_ids) in task_ids_by_status {
+        let target_key = match payload.status_mapping.get(&old_status) {
+            Some(mapped) => normalize_status_key(mapped),
+            None => old_status.clone(),
+        };
+        let Some(target) = workflow.status(&target_key) else {
+            if payload.status_mapping.contains_key(&old_status) {
+                return Err(ServiceError::BadRequest(format!(
+                    "status_mapping maps '{}' to '{}' which is not in the workflow",
+                    old_status, target_key
+                )));
+            }
+            unmapped_statuses.push(old_status);
+            continue;
+        };
+        diesel::update(
+            tasks::table.filter(tasks::id.eq_any(&task_ids)).filter(
+                tasks::status
+                    .ne(&target.key)
+                    .or(tasks::status_category.ne(&target.category)),
+            ),
+        )
+        .set((
+            tasks::status.eq(&target.key),
+            tasks::status_category.eq(&target.category),
+        ))
+        .execute(conn)?;
+    }
+    if !unmapped_statuses.is_empty() {
+        unmapped_statuses.sort();
+        return Err(ServiceError::ConflictWithDetails(
+            "Some tasks use statuses that are not in the new workflow; map them with status_mapping.".to_string(),
+            json!({ "unmapped_statuses": unmapped_statuses }),
+        ));
+    }
+
+    Ok(workflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configure_routes;
+    use crate::test_support::{seed_board, seed_task_in, test_auth_config, test_pool};
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn task_update_returns_wip_warnings() {
+        let pool = test_pool();
+        let owner = Uuid::new_v4();
+        let (task_in_doing, entering_task) = {
+            let mut conn = pool.get().expect("Failed to get test connection");
+            let project = seed_board(&mut conn, owner, "warn");
+            (
+                seed_task_in(&mut conn, owner, project, "doing", "in_progress"),
+                seed_task_in(&mut conn, owner, project, "todo", "todo"),
+            )
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_auth_config()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let request = test::TestRequest::put()
+            .uri(&format!("/tasks/{}", entering_task))
+            .insert_header(("X-User-Id", owner.to_string()))
+            .set_json(json!({ "status": "doing" }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(body["id"], json!(entering_task));
+        assert_eq!(body["status"], json!("doing"));
+        assert_eq!(body["warnings"].as_array().map(Vec::len), Some(1));
+
+        // Sans entrée dans une colonne pleine, la réponse reste la tâche seule
+        let request = test::TestRequest::put()
+            .uri(&format!("/tasks/{}", task_in_doing))
+            .insert_header(("X-User-Id", owner.to_string()))
+            .set_json(json!({ "title": "Renamed" }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(body["title"], json!("Renamed"));
+        assert!(body.get("warnings").is_none());
+    }
+}
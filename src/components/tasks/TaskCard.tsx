@@ -117,7 +117,7 @@ export default function TaskCard({
 
                     <div className="flex-grow min-w-0">
                         <h3
-                            className={`font-medium text-sm leading-tight ${task.status === 'done'
+                            className={`font-medium text-sm leading-tight ${task.status_category === 'done'
                                 ? 'line-through text-muted-foreground'
                                 : 'text-foreground'
                                 }`}
@@ -932,7 +932,7 @@ export default function TaskList({
     // Statistiques pour le header
     const taskStats = {
         total: tasks.length,
-        completed: tasks.filter(t => t.status === 'done').length,
+        completed: tasks.filter(t => t.status_category === 'done').length,
         overdue: tasks.filter(t => t.due_date && new Date(t.due_date) < new Date()).length,
     };
 
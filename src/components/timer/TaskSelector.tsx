@@ -28,7 +28,7 @@ export const TaskSelector = ({
     };
 
     // Filter out completed tasks
-    const availableTasks = tasks.filter(task => task.status !== 'done');
+    const availableTasks = tasks.filter(task => task.status_category !== 'done');
     const selectedTask = selectedTaskId ? tasks.find(t => t.id === selectedTaskId) : null;
     const selectedProject = selectedTask ? getProjectNameForTask(selectedTask.project_id) : null;
 
@@ -1,7 +1,7 @@
 // src/services/analyticsApi.ts
 import { Session } from "next-auth";
-import { ApiError, apiRequest } from "./common"; // Supposant que common.ts existe et exporte ces types/fonctions
-import { AnalyticsQueryArgs, ProductivityTrendPoint, TimeByProjectStat } from "./types";
+import { API_BASE_URL, ApiError, apiRequest } from "./common"; // Supposant que common.ts existe et exporte ces types/fonctions
+import { AnalyticsQueryArgs, BillableReport, ProductivityTrendPoint, TimeByProjectStat, TimeEntryReportRow } from "./types";
 
 
 const ANALYTICS_API_ENDPOINT = '/analytics'; // Basé sur votre scope Actix
@@ -48,4 +48,85 @@ export async function fetchProductivityTrend(
         { method: 'GET' },
         session
     );
-}
\ No newline at end of file
+}
+
+export async function fetchBillableReport(
+    session: Session | null,
+    params: AnalyticsQueryArgs
+): Promise<BillableReport | ApiError> {
+    if (!session?.user?.id) {
+        return { status: "error", statusCode: 401, message: "User not authenticated for fetchBillableReport" };
+    }
+
+    const queryParams = new URLSearchParams();
+    if (params.period && params.period !== 'custom') queryParams.append('period', params.period);
+    if (params.start_date) queryParams.append('start_date', params.start_date);
+    if (params.end_date) queryParams.append('end_date', params.end_date);
+    if (params.tz) queryParams.append('tz', params.tz);
+    if (params.project_id) queryParams.append('project_id', params.project_id);
+    if (params.client_id) queryParams.append('client_id', params.client_id);
+
+    const queryString = queryParams.toString();
+
+    return apiRequest<BillableReport>(
+        `${ANALYTICS_API_ENDPOINT}/billable${queryString ? '?' + queryString : ''}`,
+        { method: 'GET' },
+        session
+    );
+}
+
+// Paramètres communs au rapport détaillé et à son export CSV
+function entriesReportQuery(params: AnalyticsQueryArgs, format: 'json' | 'csv'): string {
+    const queryParams = new URLSearchParams();
+    if (params.period && params.period !== 'custom') queryParams.append('period', params.period);
+    if (params.start_date) queryParams.append('start_date', params.start_date);
+    if (params.end_date) queryParams.append('end_date', params.end_date);
+    if (params.tz) queryParams.append('tz', params.tz);
+    if (params.project_id) queryParams.append('project_id', params.project_id);
+    if (params.client_id) queryParams.append('client_id', params.client_id);
+    if (params.q) queryParams.append('q', params.q);
+    queryParams.append('format', format);
+    return queryParams.toString();
+}
+
+/**
+ * Rapport détaillé : une ligne par entrée, avec projet, tâche et note.
+ */
+export async function fetchEntriesReport(
+    session: Session | null,
+    params: AnalyticsQueryArgs
+): Promise<TimeEntryReportRow[] | ApiError> {
+    if (!session?.user?.id) {
+        return { status: "error", statusCode: 401, message: "User not authenticated for fetchEntriesReport" };
+    }
+    return apiRequest<TimeEntryReportRow[]>(
+        `${ANALYTICS_API_ENDPOINT}/entries?${entriesReportQuery(params, 'json')}`,
+        { method: 'GET' },
+        session
+    );
+}
+
+/**
+ * Export CSV du rapport détaillé (apiRequest ne traite que du JSON).
+ */
+export async function downloadEntriesCsv(
+    session: Session | null,
+    params: AnalyticsQueryArgs
+): Promise<Blob | ApiError> {
+    if (!session?.user?.id) {
+        return { status: "error", statusCode: 401, message: "User not authenticated for downloadEntriesCsv" };
+    }
+    try {
+        const response = await fetch(`${API_BASE_URL}${ANALYTICS_API_ENDPOINT}/entries?${entriesReportQuery(params, 'csv')}`, {
+            method: 'GET',
+            headers: { 'X-User-Id': session.user.id },
+        });
+        if (!response.ok) {
+            return { status: "error", statusCode: response.status, message: "Failed to export time entries" };
+        }
+        return await response.blob();
+    } catch (error) {
+        console.error("Network error while exporting time entries:", error);
+        return { status: "error", statusCode: 500, message: error instanceof Error ? error.message : "Network error" };
+    }
+}
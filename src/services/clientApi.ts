@@ -0,0 +1,87 @@
+// src/services/clientApi.ts
+import { Session } from "next-auth";
+import { ApiError, apiRequest, ApiResponseWithMessage } from "./common";
+import { Client, HourlyRate } from "./types";
+
+// --- FONCTIONS API POUR LES CLIENTS ET LES TAUX HORAIRES ---
+
+function notAuthenticated(action: string): ApiError {
+  return { status: "error", statusCode: 401, message: `User not authenticated for ${action}` };
+}
+
+export async function fetchClients(session: Session | null): Promise<Client[] | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchClients");
+  return apiRequest<Client[]>('/clients', { method: 'GET' }, session);
+}
+
+export async function createClient(
+  session: Session | null,
+  clientData: { name: string; email?: string | null; address?: string | null }
+): Promise<Client | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("createClient");
+  return apiRequest<Client>('/clients', { method: 'POST', body: JSON.stringify(clientData) }, session);
+}
+
+export async function updateClient(
+  session: Session | null,
+  clientId: string,
+  clientData: { name?: string; email?: string | null; address?: string | null }
+): Promise<Client | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("updateClient");
+  return apiRequest<Client>(`/clients/${clientId}`, { method: 'PUT', body: JSON.stringify(clientData) }, session);
+}
+
+/**
+ * Supprime un client : ses projets sont conservés sans client, ses taux sont supprimés.
+ */
+export async function deleteClient(
+  session: Session | null,
+  clientId: string
+): Promise<ApiResponseWithMessage | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("deleteClient");
+  return apiRequest<ApiResponseWithMessage>(`/clients/${clientId}`, { method: 'DELETE' }, session);
+}
+
+/**
+ * Historique des taux horaires, éventuellement restreint à une cible.
+ */
+export async function fetchHourlyRates(
+  session: Session | null,
+  filters?: { client_id?: string; project_id?: string; task_id?: string }
+): Promise<HourlyRate[] | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchHourlyRates");
+  const queryParams = new URLSearchParams();
+  if (filters?.client_id) queryParams.append('client_id', filters.client_id);
+  if (filters?.project_id) queryParams.append('project_id', filters.project_id);
+  if (filters?.task_id) queryParams.append('task_id', filters.task_id);
+
+  const queryString = queryParams.toString();
+  return apiRequest<HourlyRate[]>(`/rates${queryString ? '?' + queryString : ''}`, { method: 'GET' }, session);
+}
+
+/**
+ * Ajoute un taux (un changement de tarif est un nouveau taux avec sa date d'effet).
+ * Exactement un de client_id, project_id ou task_id doit être fourni.
+ */
+export async function createHourlyRate(
+  session: Session | null,
+  rateData: {
+    client_id?: string;
+    project_id?: string;
+    task_id?: string;
+    amount_cents: number;
+    currency: string;
+    effective_from: string;
+  }
+): Promise<HourlyRate | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("createHourlyRate");
+  return apiRequest<HourlyRate>('/rates', { method: 'POST', body: JSON.stringify(rateData) }, session);
+}
+
+export async function deleteHourlyRate(
+  session: Session | null,
+  rateId: string
+): Promise<ApiResponseWithMessage | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("deleteHourlyRate");
+  return apiRequest<ApiResponseWithMessage>(`/rates/${rateId}`, { method: 'DELETE' }, session);
+}
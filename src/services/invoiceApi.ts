@@ -0,0 +1,100 @@
+// src/services/invoiceApi.ts
+import { Session } from "next-auth";
+import { API_BASE_URL, ApiError, apiRequest, ApiResponseWithMessage } from "./common";
+import { Invoice, InvoiceStatus, InvoiceWithLineItems } from "./types";
+
+// --- FONCTIONS API POUR LES FACTURES ---
+
+function notAuthenticated(action: string): ApiError {
+  return { status: "error", statusCode: 401, message: `User not authenticated for ${action}` };
+}
+
+/**
+ * Génère une facture à partir du temps facturable non encore facturé du client sur la période.
+ * Les entrées incluses sont verrouillées tant que la facture existe.
+ */
+export async function createInvoice(
+  session: Session | null,
+  invoiceData: {
+    client_id: string;
+    date_from: string; // YYYY-MM-DD
+    date_to: string; // YYYY-MM-DD, inclus
+    group_by?: 'project' | 'task';
+    rounding_increment_minutes?: number; // Absent : règles d'arrondi de chaque projet
+    rounding_direction?: 'up' | 'down' | 'nearest';
+    issue_date?: string;
+    due_date?: string;
+    notes?: string;
+  }
+): Promise<InvoiceWithLineItems | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("createInvoice");
+  return apiRequest<InvoiceWithLineItems>('/invoices', { method: 'POST', body: JSON.stringify(invoiceData) }, session);
+}
+
+export async function fetchInvoices(
+  session: Session | null,
+  filters?: { status?: InvoiceStatus; client_id?: string }
+): Promise<Invoice[] | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchInvoices");
+  const queryParams = new URLSearchParams();
+  if (filters?.status) queryParams.append('status', filters.status);
+  if (filters?.client_id) queryParams.append('client_id', filters.client_id);
+
+  const queryString = queryParams.toString();
+  return apiRequest<Invoice[]>(`/invoices${queryString ? '?' + queryString : ''}`, { method: 'GET' }, session);
+}
+
+export async function fetchInvoice(
+  session: Session | null,
+  invoiceId: string
+): Promise<InvoiceWithLineItems | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchInvoice");
+  return apiRequest<InvoiceWithLineItems>(`/invoices/${invoiceId}`, { method: 'GET' }, session);
+}
+
+/**
+ * draft -> sent -> paid (un retour d'un cran en arrière est permis).
+ */
+export async function updateInvoiceStatus(
+  session: Session | null,
+  invoiceId: string,
+  status: InvoiceStatus
+): Promise<InvoiceWithLineItems | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("updateInvoiceStatus");
+  return apiRequest<InvoiceWithLineItems>(`/invoices/${invoiceId}/status`, { method: 'PUT', body: JSON.stringify({ status }) }, session);
+}
+
+/**
+ * Supprime une facture brouillon et libère ses entrées de temps.
+ */
+export async function deleteInvoice(
+  session: Session | null,
+  invoiceId: string
+): Promise<ApiResponseWithMessage | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("deleteInvoice");
+  return apiRequest<ApiResponseWithMessage>(`/invoices/${invoiceId}`, { method: 'DELETE' }, session);
+}
+
+/**
+ * Télécharge le document rendu (HTML ou PDF) ; apiRequest ne traite que du JSON.
+ */
+export async function fetchInvoiceDocument(
+  session: Session | null,
+  invoiceId: string,
+  format: 'html' | 'pdf'
+): Promise<Blob | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchInvoiceDocument");
+  try {
+    const response = await fetch(`${API_BASE_URL}/invoices/${invoiceId}/${format}`, {
+      method: 'GET',
+      headers: { 'X-User-Id': session.user.id },
+    });
+    if (!response.ok) {
+      return { status: "error", statusCode: response.status, message: `Failed to download invoice ${format}` };
+    }
+    return await response.blob();
+  } catch (error) {
+    console.error(`Network error while downloading invoice ${format}:`, error);
+    return { status: "error", statusCode: 500, message: error instanceof Error ? error.message : "Network error" };
+  }
+}
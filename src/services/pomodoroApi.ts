@@ -0,0 +1,82 @@
+// src/services/pomodoroApi.ts
+import { Session } from "next-auth";
+import { ApiError, apiRequest } from "./common";
+import { PomodoroState, StartPomodoroPayload } from "./types";
+
+// --- FONCTIONS API POUR LES SESSIONS POMODORO (état géré par le serveur) ---
+
+const POMODORO_API_ENDPOINT = '/pomodoro';
+
+function notAuthenticated(action: string): ApiError {
+  return { status: "error", statusCode: 401, message: `User not authenticated for ${action}` };
+}
+
+/**
+ * Démarre une session Pomodoro sur une tâche (phase de travail + time entry liée).
+ */
+export async function startPomodoro(
+  session: Session | null,
+  payload: StartPomodoroPayload
+): Promise<PomodoroState | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("startPomodoro");
+  return apiRequest<PomodoroState>(
+    `${POMODORO_API_ENDPOINT}/start`,
+    { method: 'POST', body: JSON.stringify(payload) },
+    session
+  );
+}
+
+/**
+ * Récupère la session active (null si aucune), pour reprendre le décompte sur n'importe quel client.
+ */
+export async function fetchCurrentPomodoro(
+  session: Session | null
+): Promise<PomodoroState | null | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchCurrentPomodoro");
+  return apiRequest<PomodoroState | null>(`${POMODORO_API_ENDPOINT}/current`, { method: 'GET' }, session);
+}
+
+/**
+ * Met la phase en pause et enregistre une interruption.
+ */
+export async function pausePomodoro(
+  session: Session | null,
+  reason?: string
+): Promise<PomodoroState | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("pausePomodoro");
+  return apiRequest<PomodoroState>(
+    `${POMODORO_API_ENDPOINT}/pause`,
+    { method: 'POST', body: JSON.stringify({ reason }) },
+    session
+  );
+}
+
+/**
+ * Reprend la phase en pause (ou démarre le travail après une pause terminée).
+ */
+export async function resumePomodoro(
+  session: Session | null
+): Promise<PomodoroState | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("resumePomodoro");
+  return apiRequest<PomodoroState>(`${POMODORO_API_ENDPOINT}/resume`, { method: 'POST' }, session);
+}
+
+/**
+ * Termine la phase courante immédiatement et passe à la suivante.
+ */
+export async function skipPomodoroPhase(
+  session: Session | null
+): Promise<PomodoroState | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("skipPomodoroPhase");
+  return apiRequest<PomodoroState>(`${POMODORO_API_ENDPOINT}/skip`, { method: 'POST' }, session);
+}
+
+/**
+ * Termine la session.
+ */
+export async function stopPomodoro(
+  session: Session | null
+): Promise<PomodoroState | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("stopPomodoro");
+  return apiRequest<PomodoroState>(`${POMODORO_API_ENDPOINT}/stop`, { method: 'POST' }, session);
+}
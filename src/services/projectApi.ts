@@ -1,11 +1,17 @@
 import { Session } from "next-auth";
 import { ApiError, apiRequest, isApiError } from "./common"; // Importer depuis common.ts
-import { CreateProjectPayload, Project, UpdateProjectData } from "./types";
+import { CreateProjectPayload, Project, RoundingDirection, RoundingScope, UpdateProjectData } from "./types";
 
 // --- INTERFACES SPÉCIFIQUES AUX PROJETS ---
 interface BackendUpdateProjectPayload { // Interne à ce module si nécessaire
     name?: string;
     color?: string | null;
+    client_id?: string | null;
+    billable?: boolean;
+    rounding_increment_minutes?: number;
+    rounding_direction?: RoundingDirection;
+    rounding_scope?: RoundingScope;
+    approver_id?: string | null;
 }
 
 // --- FONCTIONS API POUR LES PROJETS ---
@@ -24,6 +30,12 @@ export async function createProject(session: Session | null, projectData: Create
   const payloadForBackend = {
     name: projectData.name,
     color: projectData.color === undefined ? null : projectData.color,
+    client_id: projectData.client_id ?? null,
+    billable: projectData.billable,
+    rounding_increment_minutes: projectData.rounding_increment_minutes,
+    rounding_direction: projectData.rounding_direction,
+    rounding_scope: projectData.rounding_scope,
+    approver_id: projectData.approver_id ?? null,
   };
   return apiRequest<Project>(
     '/projects',
@@ -44,6 +56,16 @@ export async function updateProject(session: Session | null, projectId: string,
   if (Object.prototype.hasOwnProperty.call(projectData, 'color')) {
       payloadForBackend.color = projectData.color;
   }
+  if (Object.prototype.hasOwnProperty.call(projectData, 'client_id')) {
+      payloadForBackend.client_id = projectData.client_id;
+  }
+  if (projectData.billable !== undefined) payloadForBackend.billable = projectData.billable;
+  if (projectData.rounding_increment_minutes !== undefined) payloadForBackend.rounding_increment_minutes = projectData.rounding_increment_minutes;
+  if (projectData.rounding_direction !== undefined) payloadForBackend.rounding_direction = projectData.rounding_direction;
+  if (projectData.rounding_scope !== undefined) payloadForBackend.rounding_scope = projectData.rounding_scope;
+  if (Object.prototype.hasOwnProperty.call(projectData, 'approver_id')) {
+      payloadForBackend.approver_id = projectData.approver_id;
+  }
 
   return apiRequest<Project>(
     `/projects/${projectId}`,
@@ -73,7 +95,7 @@ export async function deleteProject(session: Session | null, projectId: string):
 
   // Si apiRequest retourne {} pour 204, on le transforme en DeleteSuccessResponse
   if (Object.keys(result).length === 0 && !isApiError(result)) {
-      return { status: "success", message: "Project deleted successfully." };
+      return { status: "success", message: "Project moved to trash." };
   }
   return result as DeleteSuccessResponse | ApiError; // Caster si on est sûr du type de succès
 }
\ No newline at end of file
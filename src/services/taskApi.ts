@@ -1,7 +1,7 @@
 // src/services/taskApi.ts
 import { Session } from "next-auth";
 import { ApiError, apiRequest } from "./common";
-import { CreateTaskPayload, DeleteSuccessResponse, FetchTasksFilters, TaskWithLabels, UpdateTaskData } from "./types";
+import { CreateTaskPayload, DeleteSuccessResponse, FetchTasksFilters, TaskDependency, TaskWithLabels, UpdatedTask, UpdateTaskData } from "./types";
 
 interface BackendUpdateTaskPayload {
   project_id?: string | null;
@@ -10,6 +10,8 @@ interface BackendUpdateTaskPayload {
   status?: string;
   due_date?: string | null;
   order?: number | null;
+  parent_task_id?: string | null;
+  recurrence_rule?: string | null;
 }
 
 // Interface pour la réponse paginée du backend
@@ -40,6 +42,7 @@ export async function fetchTasks(
   const queryParams = new URLSearchParams();
   if (filters?.project_id) queryParams.append('project_id', filters.project_id);
   if (filters?.status) queryParams.append('status', filters.status);
+  if (filters?.actionable !== undefined) queryParams.append('actionable', String(filters.actionable));
   if (filters?.page) queryParams.append('page', filters.page.toString());
   if (filters?.per_page) queryParams.append('per_page', filters.per_page.toString());
   
@@ -90,6 +93,8 @@ export async function createTask(session: Session | null, taskData: CreateTaskPa
     status: taskData.status === undefined ? null : taskData.status, // Le backend mettra 'todo' par défaut si null
     due_date: taskData.due_date === undefined ? null : taskData.due_date,
     order: taskData.order === undefined ? null : taskData.order,
+    parent_task_id: taskData.parent_task_id === undefined ? null : taskData.parent_task_id,
+    recurrence_rule: taskData.recurrence_rule === undefined ? null : taskData.recurrence_rule,
   };
   
   return apiRequest<TaskWithLabels>(
@@ -99,7 +104,7 @@ export async function createTask(session: Session | null, taskData: CreateTaskPa
   );
 }
 
-export async function updateTask(session: Session | null, taskId: string, taskData: UpdateTaskData): Promise<TaskWithLabels | ApiError> {
+export async function updateTask(session: Session | null, taskId: string, taskData: UpdateTaskData): Promise<UpdatedTask | ApiError> {
   if (!session?.user?.id) {
     return { status: "error", statusCode: 401, message: "User not authenticated for updateTask" };
   }
@@ -111,8 +116,10 @@ export async function updateTask(session: Session | null, taskId: string, taskDa
   if (Object.prototype.hasOwnProperty.call(taskData, 'description')) payloadForBackend.description = taskData.description;
   if (Object.prototype.hasOwnProperty.call(taskData, 'due_date')) payloadForBackend.due_date = taskData.due_date;
   if (Object.prototype.hasOwnProperty.call(taskData, 'order')) payloadForBackend.order = taskData.order;
+  if (Object.prototype.hasOwnProperty.call(taskData, 'parent_task_id')) payloadForBackend.parent_task_id = taskData.parent_task_id;
+  if (Object.prototype.hasOwnProperty.call(taskData, 'recurrence_rule')) payloadForBackend.recurrence_rule = taskData.recurrence_rule;
   
-  return apiRequest<TaskWithLabels>(
+  return apiRequest<UpdatedTask>(
     `/tasks/${taskId}`,
     { method: 'PUT', body: JSON.stringify(payloadForBackend) },
     session
@@ -131,14 +138,80 @@ export async function toggleTaskCompletion(session: Session | null, taskId: stri
   );
 }
 
-export async function deleteTask(session: Session | null, taskId: string): Promise<DeleteSuccessResponse | ApiError> {
+/**
+ * Met la tâche dans la corbeille. Une tâche avec des sous-tâches exige `children` :
+ * 'trash' (supprimées avec elle) ou 'promote' (remontent d'un niveau).
+ */
+export async function deleteTask(
+  session: Session | null,
+  taskId: string,
+  children?: 'trash' | 'promote'
+): Promise<DeleteSuccessResponse | ApiError> {
   if (!session?.user?.id) {
     return { status: "error", statusCode: 401, message: "User not authenticated for deleteTask" };
   }
   
   return apiRequest<DeleteSuccessResponse>(
-    `/tasks/${taskId}`,
+    `/tasks/${taskId}${children ? `?children=${children}` : ''}`,
+    { method: 'DELETE' },
+    session
+  );
+}
+export async function fetchSubtasks(session: Session | null, taskId: string): Promise<TaskWithLabels[] | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for fetchSubtasks" };
+  }
+
+  return apiRequest<TaskWithLabels[]>(
+    `/tasks/${taskId}/subtasks`,
+    { method: 'GET' },
+    session
+  );
+}
+
+/**
+ * Occurrences of a recurring task's series, completed ones included, oldest first.
+ */
+export async function fetchTaskOccurrences(session: Session | null, taskId: string): Promise<TaskWithLabels[] | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for fetchTaskOccurrences" };
+  }
+
+  return apiRequest<TaskWithLabels[]>(
+    `/tasks/${taskId}/occurrences`,
+    { method: 'GET' },
+    session
+  );
+}
+
+export async function addTaskDependency(
+  session: Session | null,
+  taskId: string,
+  blockedByTaskId: string
+): Promise<TaskDependency | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for addTaskDependency" };
+  }
+
+  return apiRequest<TaskDependency>(
+    `/tasks/${taskId}/dependencies`,
+    { method: 'POST', body: JSON.stringify({ blocked_by_task_id: blockedByTaskId }) },
+    session
+  );
+}
+
+export async function removeTaskDependency(
+  session: Session | null,
+  taskId: string,
+  blockedByTaskId: string
+): Promise<DeleteSuccessResponse | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for removeTaskDependency" };
+  }
+
+  return apiRequest<DeleteSuccessResponse>(
+    `/tasks/${taskId}/dependencies/${blockedByTaskId}`,
     { method: 'DELETE' },
     session
   );
-}
\ No newline at end of file
+}
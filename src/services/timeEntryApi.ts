@@ -1,23 +1,26 @@
 // src/services/timeEntryApi.ts
 import { Session } from "next-auth";
 import { ApiError, apiRequest, ApiResponseWithMessage } from "./common";
-import { CreateTimeEntryPayload, TimeEntry, UpdateTimeEntryData } from "./types";
+import { CreateTimeEntryPayload, OverlapResolution, TimeEntry, TimeEntryOverlap, UpdateTimeEntryData } from "./types";
 
 // --- FONCTIONS API POUR LES TIME ENTRIES ---
 
 /**
  * Récupère toutes les entrées de temps pour l'utilisateur authentifié.
- * Des filtres (task_id, date_from, date_to) peuvent être ajoutés via query params.
+ * Des filtres (task_id, project_id, q, date_from, date_to) peuvent être ajoutés via query params.
+ * `q` cherche dans les notes (insensible à la casse).
  */
 export async function fetchTimeEntries(
   session: Session | null,
-  filters?: { task_id?: string; date_from?: string; date_to?: string }
+  filters?: { task_id?: string; project_id?: string; q?: string; date_from?: string; date_to?: string }
 ): Promise<TimeEntry[] | ApiError> {
   if (!session?.user?.id) {
     return { status: "error", statusCode: 401, message: "User not authenticated for fetchTimeEntries" };
   }
   const queryParams = new URLSearchParams();
   if (filters?.task_id) queryParams.append('task_id', filters.task_id);
+  if (filters?.project_id) queryParams.append('project_id', filters.project_id);
+  if (filters?.q) queryParams.append('q', filters.q);
   if (filters?.date_from) queryParams.append('date_from', filters.date_from); // Assurez-vous que le backend attend ce format
   if (filters?.date_to) queryParams.append('date_to', filters.date_to);
   
@@ -31,19 +34,22 @@ export async function fetchTimeEntries(
 
 /**
  * Crée une nouvelle entrée de temps.
+ * Par défaut, un chevauchement avec une entrée existante renvoie une erreur 409 (`overlap: 'reject'`).
+ * En mode 'split', le backend renvoie la liste des parties créées.
  */
 export async function createTimeEntry(
   session: Session | null,
-  timeEntryData: CreateTimeEntryPayload
-): Promise<TimeEntry | ApiError> {
+  timeEntryData: CreateTimeEntryPayload,
+  overlap?: OverlapResolution
+): Promise<TimeEntry | TimeEntry[] | ApiError> {
   if (!session?.user?.id) {
     return { status: "error", statusCode: 401, message: "User not authenticated for createTimeEntry" };
   }
   // Le backend attend `is_pomodoro_session` comme `Option<bool>`,
   // donc si `undefined`, c'est ok, le backend utilisera son défaut (false).
   // Si `true` ou `false` est envoyé, ça sera pris en compte.
-  return apiRequest<TimeEntry>(
-    '/time-entries',
+  return apiRequest<TimeEntry | TimeEntry[]>(
+    `/time-entries${overlap ? `?overlap=${overlap}` : ''}`,
     {
       method: 'POST',
       body: JSON.stringify(timeEntryData),
@@ -72,8 +78,9 @@ export async function fetchTimeEntryById(
 export async function updateTimeEntry(
   session: Session | null,
   entryId: string,
-  timeEntryData: UpdateTimeEntryData
-): Promise<TimeEntry | ApiError> {
+  timeEntryData: UpdateTimeEntryData,
+  overlap?: OverlapResolution
+): Promise<TimeEntry | TimeEntry[] | ApiError> {
   if (!session?.user?.id) {
     return { status: "error", statusCode: 401, message: "User not authenticated for updateTimeEntry" };
   }
@@ -91,9 +98,15 @@ export async function updateTimeEntry(
     payloadForBackend.duration_seconds = timeEntryData.duration_seconds;
   }
   if (timeEntryData.is_pomodoro_session !== undefined) payloadForBackend.is_pomodoro_session = timeEntryData.is_pomodoro_session;
+  if (Object.prototype.hasOwnProperty.call(timeEntryData, 'billable')) {
+    payloadForBackend.billable = timeEntryData.billable; // null : hérite de la tâche
+  }
+  if (Object.prototype.hasOwnProperty.call(timeEntryData, 'note')) {
+    payloadForBackend.note = timeEntryData.note;
+  }
 
-  return apiRequest<TimeEntry>(
-    `/time-entries/${entryId}`,
+  return apiRequest<TimeEntry | TimeEntry[]>(
+    `/time-entries/${entryId}${overlap ? `?overlap=${overlap}` : ''}`,
     {
       method: 'PUT',
       body: JSON.stringify(payloadForBackend),
@@ -117,4 +130,120 @@ export async function deleteTimeEntry(
       { method: 'DELETE' },
       session
   );
-}
\ No newline at end of file
+}
+
+/**
+ * Démarre un timer côté serveur pour une tâche, ou pour un projet avec `{ projectId }`
+ * (start_time = maintenant).
+ * Renvoie une erreur 409 si un timer tourne déjà pour l'utilisateur.
+ */
+export async function startTimer(
+  session: Session | null,
+  target: string | { projectId: string },
+  isPomodoroSession?: boolean,
+  note?: string
+): Promise<TimeEntry | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for startTimer" };
+  }
+  const targetFields = typeof target === 'string' ? { task_id: target } : { project_id: target.projectId };
+  return apiRequest<TimeEntry>(
+    '/time-entries/start',
+    {
+      method: 'POST',
+      body: JSON.stringify({ ...targetFields, note, is_pomodoro_session: isPomodoroSession }),
+    },
+    session
+  );
+}
+
+/**
+ * Arrête le timer en cours ; la durée est calculée par le serveur.
+ */
+export async function stopTimer(
+  session: Session | null
+): Promise<TimeEntry | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for stopTimer" };
+  }
+  return apiRequest<TimeEntry>('/time-entries/stop', { method: 'POST' }, session);
+}
+
+/**
+ * Récupère le timer en cours (null si aucun), pour reprendre une session après rechargement.
+ */
+export async function fetchCurrentTimer(
+  session: Session | null
+): Promise<TimeEntry | null | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for fetchCurrentTimer" };
+  }
+  return apiRequest<TimeEntry | null>('/time-entries/current', { method: 'GET' }, session);
+}
+
+
+/**
+ * Liste les paires d'entrées qui se chevauchent (nettoyage de l'historique).
+ */
+export async function fetchTimeEntryOverlaps(
+  session: Session | null,
+  filters?: { date_from?: string; date_to?: string }
+): Promise<TimeEntryOverlap[] | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for fetchTimeEntryOverlaps" };
+  }
+  const queryParams = new URLSearchParams();
+  if (filters?.date_from) queryParams.append('date_from', filters.date_from);
+  if (filters?.date_to) queryParams.append('date_to', filters.date_to);
+
+  const queryString = queryParams.toString();
+  return apiRequest<TimeEntryOverlap[]>(
+    `/time-entries/overlaps${queryString ? '?' + queryString : ''}`,
+    { method: 'GET' },
+    session
+  );
+}
+
+/**
+ * Coupe une entrée en deux à l'instant `at` ; la seconde partie peut être réassignée à une autre tâche.
+ * Renvoie les deux parties, dans l'ordre chronologique.
+ */
+export async function splitTimeEntry(
+  session: Session | null,
+  entryId: string,
+  at: string,
+  taskId?: string
+): Promise<TimeEntry[] | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for splitTimeEntry" };
+  }
+  return apiRequest<TimeEntry[]>(
+    `/time-entries/${entryId}/split`,
+    {
+      method: 'POST',
+      body: JSON.stringify({ at, task_id: taskId }),
+    },
+    session
+  );
+}
+
+/**
+ * Fusionne des entrées consécutives d'une même tâche (trou toléré : 300 s par défaut).
+ */
+export async function mergeTimeEntries(
+  session: Session | null,
+  entryIds: string[],
+  maxGapSeconds?: number
+): Promise<TimeEntry | ApiError> {
+  if (!session?.user?.id) {
+    return { status: "error", statusCode: 401, message: "User not authenticated for mergeTimeEntries" };
+  }
+  return apiRequest<TimeEntry>(
+    '/time-entries/merge',
+    {
+      method: 'POST',
+      body: JSON.stringify({ entry_ids: entryIds, max_gap_seconds: maxGapSeconds }),
+    },
+    session
+  );
+}
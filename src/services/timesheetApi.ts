@@ -0,0 +1,90 @@
+// src/services/timesheetApi.ts
+import { Session } from "next-auth";
+import { ApiError, apiRequest } from "./common";
+import { Timesheet, TimesheetStatus, TimesheetWeek, TimesheetWithLines } from "./types";
+
+// --- FONCTIONS API POUR LES FEUILLES DE TEMPS HEBDOMADAIRES ---
+
+function notAuthenticated(action: string): ApiError {
+  return { status: "error", statusCode: 401, message: `User not authenticated for ${action}` };
+}
+
+/**
+ * Semaine ISO contenant `date` (YYYY-MM-DD, défaut : aujourd'hui) : temps par projet et par jour.
+ */
+export async function fetchTimesheetWeek(
+  session: Session | null,
+  date?: string
+): Promise<TimesheetWeek | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchTimesheetWeek");
+  const queryString = date ? `?date=${encodeURIComponent(date)}` : '';
+  return apiRequest<TimesheetWeek>(`/timesheets/week${queryString}`, { method: 'GET' }, session);
+}
+
+/**
+ * Mes feuilles (role "mine") ou celles que je dois approuver (role "review").
+ */
+export async function fetchTimesheets(
+  session: Session | null,
+  filters?: { status?: TimesheetStatus; role?: 'mine' | 'review' }
+): Promise<Timesheet[] | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchTimesheets");
+  const queryParams = new URLSearchParams();
+  if (filters?.status) queryParams.append('status', filters.status);
+  if (filters?.role) queryParams.append('role', filters.role);
+
+  const queryString = queryParams.toString();
+  return apiRequest<Timesheet[]>(`/timesheets${queryString ? '?' + queryString : ''}`, { method: 'GET' }, session);
+}
+
+export async function fetchTimesheet(
+  session: Session | null,
+  timesheetId: string
+): Promise<TimesheetWithLines | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchTimesheet");
+  return apiRequest<TimesheetWithLines>(`/timesheets/${timesheetId}`, { method: 'GET' }, session);
+}
+
+/**
+ * Soumet la semaine contenant `week_of` ; resoumet une feuille rouverte ou rejetée.
+ * L'approbateur est celui des projets de la semaine ; `approver_id` ne sert qu'à une semaine vide.
+ */
+export async function submitTimesheet(
+  session: Session | null,
+  submission: { week_of: string; approver_id?: string; comment?: string }
+): Promise<TimesheetWithLines | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("submitTimesheet");
+  return apiRequest<TimesheetWithLines>('/timesheets/submit', { method: 'POST', body: JSON.stringify(submission) }, session);
+}
+
+async function reviewTimesheet(
+  session: Session | null,
+  timesheetId: string,
+  action: 'approve' | 'reject' | 'reopen',
+  comment?: string
+): Promise<TimesheetWithLines | ApiError> {
+  if (!session?.user?.id) return notAuthenticated(`${action}Timesheet`);
+  return apiRequest<TimesheetWithLines>(
+    `/timesheets/${timesheetId}/${action}`,
+    { method: 'POST', body: JSON.stringify({ comment: comment ?? null }) },
+    session
+  );
+}
+
+export async function approveTimesheet(session: Session | null, timesheetId: string, comment?: string) {
+  return reviewTimesheet(session, timesheetId, 'approve', comment);
+}
+
+/**
+ * Le commentaire est obligatoire pour un rejet.
+ */
+export async function rejectTimesheet(session: Session | null, timesheetId: string, comment: string) {
+  return reviewTimesheet(session, timesheetId, 'reject', comment);
+}
+
+/**
+ * Rouvre une feuille (approbateur) ou retire une feuille en attente (auteur).
+ */
+export async function reopenTimesheet(session: Session | null, timesheetId: string, comment?: string) {
+  return reviewTimesheet(session, timesheetId, 'reopen', comment);
+}
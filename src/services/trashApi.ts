@@ -0,0 +1,55 @@
+// src/services/trashApi.ts
+import { Session } from "next-auth";
+import { ApiError, apiRequest, ApiResponseWithMessage } from "./common";
+import { Project, TrashContents, TrashedTask } from "./types";
+
+// --- FONCTIONS API POUR LA CORBEILLE ---
+// Les tâches et projets supprimés y restent jusqu'à leur restauration ou leur purge
+// (manuelle, ou automatique après `retention_days`). Leurs time entries sont conservées.
+
+function notAuthenticated(action: string): ApiError {
+  return { status: "error", statusCode: 401, message: `User not authenticated for ${action}` };
+}
+
+export async function fetchTrash(session: Session | null): Promise<TrashContents | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchTrash");
+  return apiRequest<TrashContents>('/trash', { method: 'GET' }, session);
+}
+
+/**
+ * Échoue (409) si le projet de la tâche est lui-même dans la corbeille.
+ */
+export async function restoreTask(
+  session: Session | null,
+  taskId: string
+): Promise<Omit<TrashedTask, 'purge_at'> | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("restoreTask");
+  return apiRequest<Omit<TrashedTask, 'purge_at'>>(`/trash/tasks/${taskId}/restore`, { method: 'POST' }, session);
+}
+
+/**
+ * Restaure le projet et les tâches supprimées en même temps que lui.
+ */
+export async function restoreProject(
+  session: Session | null,
+  projectId: string
+): Promise<Project | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("restoreProject");
+  return apiRequest<Project>(`/trash/projects/${projectId}/restore`, { method: 'POST' }, session);
+}
+
+export async function purgeTask(
+  session: Session | null,
+  taskId: string
+): Promise<ApiResponseWithMessage | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("purgeTask");
+  return apiRequest<ApiResponseWithMessage>(`/trash/tasks/${taskId}`, { method: 'DELETE' }, session);
+}
+
+export async function purgeProject(
+  session: Session | null,
+  projectId: string
+): Promise<ApiResponseWithMessage | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("purgeProject");
+  return apiRequest<ApiResponseWithMessage>(`/trash/projects/${projectId}`, { method: 'DELETE' }, session);
+}
@@ -23,6 +23,56 @@ export interface Project {
   created_at: string;
   /** Last update date (ISO string) */
   updated_at: string;
+  /** Owning client ID (optional) */
+  client_id: string | null;
+  /** Default billable flag for the project's tasks and time entries */
+  billable: boolean;
+  /** Rounding increment applied to reported and billed time (0: none) */
+  rounding_increment_minutes: number;
+  rounding_direction: RoundingDirection;
+  /** Round each entry, or the project's total of each local day */
+  rounding_scope: RoundingScope;
+  /** Set while the project is in the trash (ISO string) */
+  deleted_at: string | null;
+  /** User who approves the timesheets containing time on this project (never the owner) */
+  approver_id: string | null;
+}
+
+export type RoundingDirection = 'up' | 'down' | 'nearest';
+export type RoundingScope = 'entry' | 'day';
+
+/**
+ * Represents a billed client, owner of projects
+ */
+export interface Client {
+  id: string;
+  user_id: string;
+  name: string;
+  email: string | null;
+  /** Free-form postal address, printed on invoices */
+  address: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+/**
+ * Hourly rate of exactly one client, project or task, effective from a given day
+ * until the next rate of the same target.
+ */
+export interface HourlyRate {
+  id: string;
+  user_id: string;
+  client_id: string | null;
+  project_id: string | null;
+  task_id: string | null;
+  /** Amount per hour, in cents of `currency` */
+  amount_cents: number;
+  /** ISO 4217 code (e.g. "EUR") */
+  currency: string;
+  /** YYYY-MM-DD */
+  effective_from: string;
+  created_at: string;
+  updated_at: string;
 }
 
 /**
@@ -67,8 +117,144 @@ export interface TaskWithLabels {
   created_at: string;
   /** Last update date (ISO string) */
   updated_at: string;
+  /** Date the task moved to done (ISO string, null if not done) */
+  completed_at: string | null;
+  /** Billable override (null inherits the project's flag) */
+  billable: boolean | null;
+  /** Parent task ID (null for a top-level task) */
+  parent_task_id: string | null;
+  /** Normalized RRULE (e.g. 'FREQ=WEEKLY;BYDAY=MO'), null for a one-off task */
+  recurrence_rule: string | null;
+  /** First occurrence of the series (null for the first occurrence itself) */
+  recurrence_series_id: string | null;
+  /** Rank of the occurrence in its series, starting at 1 */
+  recurrence_index: number;
+  /** Category of the status in the task's workflow */
+  status_category: StatusCategory;
   /** Array of associated labels */
   labels: Label[];
+  /** Subtasks (all levels) in 'done' and in total */
+  subtasks_done: number;
+  subtasks_total: number;
+  /** Seconds tracked on the task itself, and on the task and its subtasks */
+  tracked_seconds: number;
+  total_tracked_seconds: number;
+  /** IDs of the tasks blocking this task, and of the tasks it blocks */
+  blocked_by: string[];
+  blocking: string[];
+  /** True while at least one blocking task is not 'done' */
+  is_blocked: boolean;
+}
+
+/**
+ * Deleted task waiting in the trash, until restored or purged at `purge_at`
+ */
+export type TrashedTask = Omit<TaskWithLabels, 'labels' | 'subtasks_done' | 'subtasks_total' | 'tracked_seconds' | 'total_tracked_seconds' | 'blocked_by' | 'blocking' | 'is_blocked'> & {
+  deleted_at: string;
+  purge_at: string;
+};
+
+/**
+ * Deleted project waiting in the trash; its tasks are restored with it
+ */
+export type TrashedProject = Project & {
+  deleted_at: string;
+  purge_at: string;
+};
+
+export interface TrashContents {
+  retention_days: number;
+  projects: TrashedProject[];
+  tasks: TrashedTask[];
+}
+
+/****************************
+ * STATUS WORKFLOWS
+ ****************************/
+
+export type StatusCategory = 'todo' | 'in_progress' | 'done';
+
+export interface WorkflowStatus {
+  /** Value stored in the task's `status` */
+  key: string;
+  name: string;
+  category: StatusCategory;
+  position: number;
+  /** Max tasks of the project in this column; null = unlimited */
+  wip_limit: number | null;
+  /** What happens when a task enters a full column */
+  wip_policy: WipPolicy;
+}
+
+export type WipPolicy = 'warn' | 'reject';
+
+export interface WorkflowTransition {
+  from: string;
+  to: string;
+}
+
+/**
+ * Effective workflow: the project's own, the user's default, or the built-in
+ * todo / inprogress / done. No transitions means every move is allowed.
+ */
+export interface Workflow {
+  source: 'project' | 'user' | 'default';
+  project_id: string | null;
+  statuses: WorkflowStatus[];
+  transitions: WorkflowTransition[];
+}
+
+/**
+ * Replaces a workflow. Empty `statuses` falls back to the inherited workflow.
+ * `status_mapping` gives the new status of tasks whose status (old key) disappears.
+ */
+export interface UpdateWorkflowPayload {
+  statuses: {
+    key: string;
+    name?: string;
+    category: StatusCategory;
+    wip_limit?: number | null;
+    wip_policy?: WipPolicy;
+  }[];
+  transitions?: WorkflowTransition[];
+  status_mapping?: Record<string, string>;
+}
+
+/** Kanban column: a workflow status with its tasks ordered by `order` */
+export interface BoardColumn extends WorkflowStatus {
+  task_count: number;
+  /** One more task triggers the WIP warning or rejection */
+  at_wip_limit: boolean;
+  tasks: TaskWithLabels[];
+}
+
+export interface Board {
+  project_id: string;
+  workflow_source: Workflow['source'];
+  transitions: WorkflowTransition[];
+  total_tasks: number;
+  columns: BoardColumn[];
+}
+
+/** Moves a task to column `status` at rank `position` (omitted = bottom) */
+export interface MoveBoardTaskPayload {
+  task_id: string;
+  status: string;
+  position?: number;
+}
+
+export interface MoveBoardTaskResponse {
+  task: TaskWithLabels;
+  /** WIP limit reached in a 'warn' column */
+  warnings: string[];
+}
+
+/**
+ * Response of a task update: the task, with the WIP warnings of the column it entered
+ */
+export interface UpdatedTask extends TaskWithLabels {
+  /** WIP limit reached in a 'warn' column (absent when there is none) */
+  warnings?: string[];
 }
 
 /****************************
@@ -83,6 +269,16 @@ export interface CreateProjectPayload {
   name: string;
   /** Optional color */
   color?: string | null;
+  /** Optional owning client */
+  client_id?: string | null;
+  /** Billable by default (false if omitted) */
+  billable?: boolean;
+  /** Rounding rules (defaults: 0 minutes, up, per entry) */
+  rounding_increment_minutes?: number;
+  rounding_direction?: RoundingDirection;
+  rounding_scope?: RoundingScope;
+  /** Timesheet approver (someone other than the owner) */
+  approver_id?: string | null;
 }
 
 /**
@@ -93,6 +289,16 @@ export interface UpdateProjectData {
   name?: string;
   /** New color (optional) */
   color?: string | null;
+  /** New client (null detaches the project) */
+  client_id?: string | null;
+  /** New billable flag */
+  billable?: boolean;
+  /** New rounding rules */
+  rounding_increment_minutes?: number;
+  rounding_direction?: RoundingDirection;
+  rounding_scope?: RoundingScope;
+  /** New timesheet approver (null removes it) */
+  approver_id?: string | null;
 }
 
 /**
@@ -131,6 +337,10 @@ export interface CreateTaskPayload {
   due_date?: string | null;
   /** Initial order (optional) */
   order?: number | null;
+  /** Parent task (optional); the subtask takes the parent's project */
+  parent_task_id?: string | null;
+  /** RRULE subset (FREQ, INTERVAL, BYDAY, BYMONTHDAY, COUNT, UNTIL); requires due_date */
+  recurrence_rule?: string | null;
 }
 
 /**
@@ -149,28 +359,91 @@ export interface UpdateTaskData {
   due_date?: string | null | undefined;
   /** New order (optional) */
   order?: number | null | undefined;
+  /** New parent task (null makes it a top-level task); subtasks move with it */
+  parent_task_id?: string | null | undefined;
+  /** New RRULE (null stops the recurrence; existing occurrences are kept) */
+  recurrence_rule?: string | null | undefined;
 }
 
 export interface TimeEntry {
   id: string; // UUID
   user_id: string; // UUID
-  task_id: string; // UUID
+  task_id: string | null; // UUID, null pour une entrée rattachée directement à un projet
+  project_id: string | null; // Renseigné seulement pour une entrée sans tâche
+  note: string | null; // Ce qui a été fait pendant l'entrée
   start_time: string; // ISO 8601 DateTime string (e.g., "2023-10-27T10:00:00Z")
   end_time: string | null; // ISO 8601 DateTime string or null
   duration_seconds: number | null;
   is_pomodoro_session: boolean;
   created_at: string; // ISO 8601 DateTime string
   updated_at: string; // ISO 8601 DateTime string
+  pomodoro_session_id: string | null; // UUID de la session Pomodoro (phases de travail)
+  auto_stop_reason: 'capped_at_max' | 'cut_at_last_activity' | 'flagged_long_running' | null; // Posé par l'arrêt automatique des timers oubliés
+  auto_stopped_at: string | null;
+  billable: boolean | null; // null : hérite de la tâche puis du projet
+  invoice_id: string | null; // Entrée facturée : verrouillée tant que la facture existe
+}
+
+// Résolution des chevauchements à la création/mise à jour d'une entrée (?overlap=...)
+export type OverlapResolution = 'reject' | 'trim_existing' | 'split' | 'allow';
+
+// Une paire d'entrées qui se chevauchent (GET /time-entries/overlaps)
+export interface TimeEntryOverlap {
+  first_entry: TimeEntry;
+  second_entry: TimeEntry;
+  overlap_start: string; // ISO 8601
+  overlap_end: string | null; // null si les deux entrées sont ouvertes
+  overlap_seconds: number | null;
+}
+
+// Correspond à PomodoroStateResponse (backend) : la session + le décompte de la phase courante
+export type PomodoroPhase = 'work' | 'short_break' | 'long_break';
+
+export interface PomodoroState {
+  id: string; // UUID de la session
+  user_id: string;
+  task_id: string;
+  status: 'active' | 'completed';
+  phase: PomodoroPhase;
+  work_minutes: number;
+  short_break_minutes: number;
+  long_break_minutes: number;
+  cycles_before_long_break: number;
+  completed_work_cycles: number;
+  interruption_count: number;
+  phase_duration_seconds: number;
+  phase_elapsed_seconds: number;
+  phase_resumed_at: string; // ISO 8601
+  paused_at: string | null;
+  ended_at: string | null;
+  created_at: string;
+  updated_at: string;
+  is_paused: boolean;
+  remaining_seconds: number;
+  phase_ends_at: string | null; // null si la phase est en pause
+  current_time_entry_id: string | null;
+}
+
+export interface StartPomodoroPayload {
+  task_id: string;
+  // Par défaut, les réglages de l'utilisateur (/me)
+  work_minutes?: number;
+  short_break_minutes?: number;
+  long_break_minutes?: number;
+  cycles_before_long_break?: number;
 }
 
 // Correspond à NewTimeEntry dans models.rs (backend) pour la création
 // et CreateTimeEntryPayload pour le frontend
 export interface CreateTimeEntryPayload {
-  task_id: string; // UUID
+  task_id?: string; // UUID ; exactement un de task_id et project_id
+  project_id?: string; // Travail transverse, hors tâche
+  note?: string;
   start_time: string; // ISO 8601 DateTime string (sera new Date().toISOString())
   end_time: string;   // ISO 8601 DateTime string (sera new Date().toISOString())
   duration_seconds: number;
   is_pomodoro_session?: boolean; // Optionnel, le backend a un défaut
+  billable?: boolean; // Absent : hérite de la tâche puis du projet
 }
 
 // Correspond à UpdateTimeEntryPayload (frontend) et UpdateTimeEntryChangeset (backend)
@@ -179,26 +452,194 @@ export interface UpdateTimeEntryData {
   end_time?: string | null; // Permet de mettre à null explicitement
   duration_seconds?: number | null;
   is_pomodoro_session?: boolean;
+  billable?: boolean | null; // null : hérite de la tâche puis du projet
+  note?: string | null;
   // updated_at est géré par le backend
 }
 
 // Types pour les réponses du backend (doivent correspondre aux structs Rust)
 export interface TimeByProjectStat {
-  project_id: string; // Uuid
+  project_id: string | null; // Uuid, null pour le bucket "No project"
   project_name: string;
   total_duration_seconds: number; // Reçu comme i64 de Rust, sera number en JS/TS
+  raw_duration_seconds: number; // Temps suivi (= total_duration_seconds)
+  rounded_duration_seconds: number; // Selon les règles d'arrondi du projet
+}
+
+export interface TimeByLabelStat {
+  label_id: string | null; // Uuid, null pour le bucket "Unlabeled"
+  label_name: string;
+  label_color: string | null;
+  total_duration_seconds: number;
 }
 
 export interface ProductivityTrendPoint {
   date_point: string; // NaiveDate de Rust, sera une chaîne YYYY-MM-DD
+  bucket_start: string; // NaiveDateTime local, début du bucket (YYYY-MM-DDTHH:MM:SS)
+  bucket_start_utc: string; // Même instant en UTC (ISO 8601), unique même à l'heure répétée en automne
   total_duration_seconds: number; // i64 de Rust, number en JS/TS
 }
 
 // Type pour les paramètres de requête
 export interface AnalyticsQueryArgs {
-  period?: 'this_week' | 'last_7_days' | 'this_month' | 'last_30_days' | 'custom';
+  period?:
+    | 'today'
+    | 'yesterday'
+    | 'this_week'
+    | 'last_week'
+    | 'this_month'
+    | 'last_month'
+    | 'this_quarter'
+    | 'this_year'
+    | `last_${number}_days`
+    | 'custom';
   start_date?: string; // YYYY-MM-DD
   end_date?: string;   // YYYY-MM-DD
+  tz?: string; // Fuseau IANA, par défaut celui des réglages
+  granularity?: 'hour' | 'day' | 'week' | 'month'; // Tendance uniquement
+  mode?: 'split' | 'double_count'; // Temps par label uniquement
+  project_id?: string; // Flux, rapport facturable et rapport détaillé
+  client_id?: string; // Rapport facturable et rapport détaillé
+  q?: string; // Rapport détaillé : recherche dans les notes
+}
+
+// Une entrée du rapport détaillé (GET /analytics/entries)
+export interface TimeEntryReportRow {
+  entry_id: string;
+  start_time: string;
+  end_time: string | null;
+  seconds: number;
+  client_id: string | null;
+  client_name: string | null;
+  project_id: string | null;
+  project_name: string | null;
+  task_id: string | null;
+  task_title: string | null;
+  note: string | null;
+  is_billable: boolean;
+}
+
+export interface BillableProjectStat {
+  client_id: string | null;
+  client_name: string | null;
+  project_id: string | null;
+  project_name: string;
+  currency: string | null; // null : temps facturable sans taux applicable
+  billable_seconds: number;
+  raw_duration_seconds: number;
+  rounded_duration_seconds: number; // Base du montant
+  amount_cents: number;
+}
+
+export interface BillableReport {
+  start_date: string;
+  end_date: string;
+  billable_seconds: number;
+  rounded_billable_seconds: number;
+  non_billable_seconds: number;
+  unrated_billable_seconds: number;
+  totals: { currency: string; billable_seconds: number; rounded_duration_seconds: number; amount_cents: number }[]; // Une ligne par devise
+  by_project: BillableProjectStat[];
+}
+
+export type InvoiceStatus = 'draft' | 'sent' | 'paid';
+
+/**
+ * Invoice generated from the unbilled billable time of a client.
+ * Client details are copied at creation so the document never changes afterwards.
+ */
+export interface Invoice {
+  id: string;
+  user_id: string;
+  client_id: string | null;
+  sequence_number: number;
+  /** e.g. "INV-2026-0007", sequential per user and year */
+  invoice_number: string;
+  status: InvoiceStatus;
+  client_name: string;
+  client_email: string | null;
+  client_address: string | null;
+  currency: string;
+  period_start: string; // YYYY-MM-DD
+  period_end: string; // YYYY-MM-DD
+  issue_date: string; // YYYY-MM-DD
+  due_date: string | null;
+  group_by: 'project' | 'task';
+  rounding_increment_minutes: number;
+  rounding_direction: RoundingDirection;
+  total_cents: number;
+  notes: string | null;
+  sent_at: string | null;
+  paid_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface InvoiceLineItem {
+  id: string;
+  invoice_id: string;
+  user_id: string;
+  project_id: string | null;
+  task_id: string | null;
+  description: string;
+  quantity_seconds: number; // Après arrondi
+  hourly_rate_cents: number;
+  amount_cents: number;
+  position: number;
+  created_at: string;
+}
+
+export interface InvoiceWithLineItems extends Invoice {
+  line_items: InvoiceLineItem[];
+  time_entry_count: number;
+}
+
+export type TimesheetStatus = 'open' | 'submitted' | 'approved' | 'rejected';
+
+/**
+ * Weekly timesheet (ISO week, in the user's timezone) submitted to an approver.
+ * While approved, the time entries of the week cannot be updated or deleted.
+ */
+export interface Timesheet {
+  id: string;
+  user_id: string;
+  approver_id: string;
+  iso_year: number;
+  iso_week: number;
+  week_start: string; // YYYY-MM-DD, Monday
+  week_end: string; // YYYY-MM-DD, Sunday
+  status: TimesheetStatus;
+  /** Total tracked time when submitted */
+  total_seconds: number;
+  submit_comment: string | null;
+  review_comment: string | null;
+  submitted_at: string | null;
+  reviewed_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+// Temps d'un projet sur un jour de la semaine
+export interface TimesheetDayTotal {
+  project_id: string | null;
+  project_name: string;
+  work_date: string; // YYYY-MM-DD
+  seconds: number;
+}
+
+export interface TimesheetWithLines extends Timesheet {
+  lines: TimesheetDayTotal[];
+}
+
+// GET /timesheets/week : agrégat en direct et feuille existante éventuelle
+export interface TimesheetWeek {
+  iso_year: number;
+  iso_week: number;
+  week_start: string;
+  week_end: string;
+  total_seconds: number;
+  lines: TimesheetDayTotal[];
+  timesheet: Timesheet | null;
 }
 
 /****************************
@@ -223,6 +664,18 @@ export interface FetchTasksFilters {
   project_id?: string;
   /** Filter by status */
   status?: string;
+  /** true: open tasks with no open blocker; false: every other task (blocked or done) */
+  actionable?: boolean;
+}
+
+/**
+ * Dependency edge: task_id is blocked by blocked_by_task_id
+ */
+export interface TaskDependency {
+  task_id: string;
+  blocked_by_task_id: string;
+  user_id: string;
+  created_at: string;
 }
 
 /**
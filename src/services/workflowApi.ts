@@ -0,0 +1,68 @@
+// src/services/workflowApi.ts
+import { Session } from "next-auth";
+import { ApiError, apiRequest } from "./common";
+import {
+  Board,
+  MoveBoardTaskPayload,
+  MoveBoardTaskResponse,
+  UpdateWorkflowPayload,
+  Workflow,
+} from "./types";
+
+// --- FONCTIONS API POUR LES WORKFLOWS DE STATUTS ---
+// Le statut d'une tâche doit appartenir au workflow de son projet. Remplacer un workflow
+// échoue (409, `details.unmapped_statuses`) si des tâches gardent un statut disparu sans
+// `status_mapping`. Entrer dans une colonne pleine en politique 'reject' échoue aussi (409).
+
+function notAuthenticated(action: string): ApiError {
+  return { status: "error", statusCode: 401, message: `User not authenticated for ${action}` };
+}
+
+export async function fetchDefaultWorkflow(session: Session | null): Promise<Workflow | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchDefaultWorkflow");
+  return apiRequest<Workflow>('/workflows/default', { method: 'GET' }, session);
+}
+
+export async function updateDefaultWorkflow(
+  session: Session | null,
+  payload: UpdateWorkflowPayload
+): Promise<Workflow | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("updateDefaultWorkflow");
+  return apiRequest<Workflow>('/workflows/default', { method: 'PUT', body: JSON.stringify(payload) }, session);
+}
+
+export async function fetchProjectWorkflow(
+  session: Session | null,
+  projectId: string
+): Promise<Workflow | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchProjectWorkflow");
+  return apiRequest<Workflow>(`/projects/${projectId}/workflow`, { method: 'GET' }, session);
+}
+
+export async function updateProjectWorkflow(
+  session: Session | null,
+  projectId: string,
+  payload: UpdateWorkflowPayload
+): Promise<Workflow | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("updateProjectWorkflow");
+  return apiRequest<Workflow>(`/projects/${projectId}/workflow`, { method: 'PUT', body: JSON.stringify(payload) }, session);
+}
+
+// --- TABLEAU KANBAN D'UN PROJET ---
+
+export async function fetchProjectBoard(
+  session: Session | null,
+  projectId: string
+): Promise<Board | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("fetchProjectBoard");
+  return apiRequest<Board>(`/projects/${projectId}/board`, { method: 'GET' }, session);
+}
+
+export async function moveBoardTask(
+  session: Session | null,
+  projectId: string,
+  payload: MoveBoardTaskPayload
+): Promise<MoveBoardTaskResponse | ApiError> {
+  if (!session?.user?.id) return notAuthenticated("moveBoardTask");
+  return apiRequest<MoveBoardTaskResponse>(`/projects/${projectId}/board/move`, { method: 'POST', body: JSON.stringify(payload) }, session);
+}